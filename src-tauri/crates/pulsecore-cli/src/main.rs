@@ -0,0 +1,1027 @@
+//! Headless front-end for the telemetry collector. Runs without a display or webview,
+//! so the same sampling code can be used on servers or over SSH.
+
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use pulsecore_core::{
+    core::{
+        collectors::system_collector::SystemCollector,
+        device_info::collect_hardware_info,
+        metric_path::MetricPath,
+        process_sampler::ProcessSampler,
+        process_scope::ProcessScope,
+        profile::ProfileSample,
+        profile_analysis::{self, AnalysisOptions, ReportFormat},
+        profile_diff::{self, RegressionTolerances},
+        profile_store::ProfileWriter,
+        profile_trace,
+        status_bar::{StatusBarEncoder, StatusBarProtocol},
+        taskbar::{segment_configs, validate_segments, TaskbarLayout},
+        units::{UnitFormatter, GIB, MIB},
+    },
+    types::{
+        default_metric_thresholds, HardwareInfo, NativeTaskbarConfig, ProfileScope,
+        ProfileStorageOptions, TelemetrySnapshot, UnitPreferences,
+    },
+};
+use serde::Serialize;
+
+const MIN_INTERVAL_MS: u64 = 200;
+const MAX_INTERVAL_MS: u64 = 60_000;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "pulsecore-cli",
+    version,
+    about = "Headless PulseCoreLite telemetry"
+)]
+struct Cli {
+    /// Output format.
+    #[arg(long, short, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+    #[command(flatten)]
+    units: UnitArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The desktop app's unit preferences; unset flags keep the defaults, or the `statusbar`
+/// config's own `units`.
+#[derive(Debug, Default, Args)]
+struct UnitArgs {
+    /// Show transfer rates in bytes (MB/s) or bits (Mbit/s).
+    #[arg(long, global = true, value_parser = ["bytes", "bits"])]
+    data_rate: Option<String>,
+    /// Size prefixes: binary (1024, KB), iec (1024, KiB) or si (1000, kB).
+    #[arg(long, global = true, value_parser = ["binary", "iec", "si"])]
+    prefix: Option<String>,
+    /// Temperature scale.
+    #[arg(long, global = true, value_parser = ["celsius", "fahrenheit"])]
+    temperature: Option<String>,
+    /// Decimal places for every value, overriding each view's own.
+    #[arg(long, global = true)]
+    precision: Option<u8>,
+    /// Digit grouping and decimal mark.
+    #[arg(long, global = true, value_parser = ["plain", "en-US", "de-DE", "fr-FR", "zh-CN"])]
+    number_locale: Option<String>,
+}
+
+impl UnitArgs {
+    /// `base` with every flag that was given applied on top.
+    fn apply(&self, base: UnitPreferences) -> UnitPreferences {
+        UnitPreferences {
+            data_rate: self.data_rate.clone().unwrap_or(base.data_rate),
+            prefix: self.prefix.clone().unwrap_or(base.prefix),
+            temperature: self.temperature.clone().unwrap_or(base.temperature),
+            precision: self.precision.or(base.precision),
+            number_locale: self.number_locale.clone().unwrap_or(base.number_locale),
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print a single telemetry snapshot.
+    Snapshot {
+        /// Sampling window used to compute CPU and network rates, in milliseconds.
+        #[arg(long, default_value_t = 1000)]
+        sample_ms: u64,
+    },
+    /// Print a snapshot every interval until interrupted.
+    Watch {
+        /// Interval between snapshots, in milliseconds.
+        #[arg(long, short, default_value_t = 1000)]
+        interval: u64,
+        /// Stop after this many snapshots.
+        #[arg(long, short)]
+        count: Option<u64>,
+    },
+    /// Print static hardware information.
+    Hardware,
+    /// Capture a profile for a fixed duration and print a summary.
+    Profile {
+        /// Capture length in seconds.
+        #[arg(long, short)]
+        duration: u64,
+        /// Interval between samples, in milliseconds.
+        #[arg(long, short, default_value_t = 1000)]
+        interval: u64,
+        /// Write the capture here, gzip-compressed and split into segments like the desktop
+        /// profiler (`<name>.001.jsonl.gz`, ...); `analyze`, `trace` and `compare` take this path.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Record this many of the busiest processes with each sample; 0 records none.
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+        /// Rank processes for `--top` by memory or CPU.
+        #[arg(long, value_enum, default_value_t = TopBy::Memory)]
+        top_by: TopBy,
+    },
+    /// Analyze a recorded profile (markdown for `table`, JSON otherwise).
+    Analyze {
+        /// Profile `.jsonl` file.
+        path: PathBuf,
+        /// Also write `<name>.report.md` / `.report.json` next to the profile.
+        #[arg(long)]
+        write: bool,
+    },
+    /// Convert a profile to Chrome Trace Event JSON (`chrome://tracing`, ui.perfetto.dev).
+    Trace {
+        /// Profile `.jsonl` file.
+        path: PathBuf,
+        /// Trace file to write; defaults to `<name>.trace.json` next to the profile.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Compare a profile against a baseline profile; exits non-zero on a regression.
+    Compare {
+        /// Profile to check (`.jsonl` capture or `.report.json`).
+        path: PathBuf,
+        /// Baseline to compare against (`.jsonl` capture or `.report.json`).
+        baseline: PathBuf,
+        /// Allowed rise of the app CPU percentiles, in percentage points.
+        #[arg(long)]
+        app_cpu_points: Option<f64>,
+        /// Allowed rise of the app memory percentiles, in percent.
+        #[arg(long)]
+        app_memory_pct: Option<f64>,
+        /// Allowed rise of the peak WebView memory, in percent.
+        #[arg(long)]
+        webview_memory_pct: Option<f64>,
+        /// Allowed rise of the p95 sample jitter, in milliseconds.
+        #[arg(long)]
+        jitter_ms: Option<f64>,
+    },
+    /// Stream the taskbar segments to i3bar/swaybar or a waybar custom module until interrupted.
+    Statusbar {
+        /// Status bar protocol to speak.
+        #[arg(long, short, value_enum, default_value_t = BarProtocol::Waybar)]
+        protocol: BarProtocol,
+        /// Interval between updates, in milliseconds.
+        #[arg(long, short, default_value_t = 1000)]
+        interval: u64,
+        /// Native taskbar config JSON (segment toggles, templates, thresholds); missing fields
+        /// take the desktop defaults.
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Metric path reported as waybar's `percentage`.
+        #[arg(long, default_value = "cpu.usage")]
+        percentage: String,
+        /// Write to this FIFO, file or Unix socket instead of stdout; reopened when the reader
+        /// goes away.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum BarProtocol {
+    I3bar,
+    Waybar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TopBy {
+    Memory,
+    Cpu,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Json,
+    Ndjson,
+    Table,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct MetricSummary {
+    avg: f64,
+    min: f64,
+    max: f64,
+    samples: u64,
+}
+
+impl MetricSummary {
+    fn push(&mut self, value: Option<f64>) {
+        let Some(value) = value.filter(|v| v.is_finite()) else {
+            return;
+        };
+        if self.samples == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.avg += (value - self.avg) / (self.samples + 1) as f64;
+        self.samples += 1;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ProfileSummary {
+    #[serde(rename = "startedAt")]
+    started_at: Option<DateTime<Utc>>,
+    #[serde(rename = "finishedAt")]
+    finished_at: Option<DateTime<Utc>>,
+    samples: u64,
+    output: Option<String>,
+    #[serde(rename = "cpuUsagePct")]
+    cpu_usage_pct: MetricSummary,
+    #[serde(rename = "cpuTemperatureC")]
+    cpu_temperature_c: MetricSummary,
+    #[serde(rename = "gpuUsagePct")]
+    gpu_usage_pct: MetricSummary,
+    #[serde(rename = "gpuTemperatureC")]
+    gpu_temperature_c: MetricSummary,
+    #[serde(rename = "memoryUsagePct")]
+    memory_usage_pct: MetricSummary,
+    #[serde(rename = "downloadBytesPerSec")]
+    download_bytes_per_sec: MetricSummary,
+    #[serde(rename = "uploadBytesPerSec")]
+    upload_bytes_per_sec: MetricSummary,
+}
+
+impl ProfileSummary {
+    fn record(&mut self, snapshot: &TelemetrySnapshot) {
+        self.samples += 1;
+        self.cpu_usage_pct.push(Some(snapshot.cpu.usage_pct));
+        self.cpu_temperature_c.push(snapshot.cpu.temperature_c);
+        self.gpu_usage_pct.push(snapshot.gpu.usage_pct);
+        self.gpu_temperature_c.push(snapshot.gpu.temperature_c);
+        self.memory_usage_pct.push(Some(snapshot.memory.usage_pct));
+        self.download_bytes_per_sec
+            .push(Some(snapshot.network.download_bytes_per_sec));
+        self.upload_bytes_per_sec
+            .push(Some(snapshot.network.upload_bytes_per_sec));
+    }
+
+    /// Label, summary and the metric path whose unit the values are shown in.
+    fn rows(&self) -> Vec<(&'static str, &MetricSummary, &'static str)> {
+        vec![
+            ("CPU", &self.cpu_usage_pct, "cpu.usage"),
+            ("CPU temp", &self.cpu_temperature_c, "cpu.temp"),
+            ("GPU", &self.gpu_usage_pct, "gpu.usage"),
+            ("GPU temp", &self.gpu_temperature_c, "gpu.temp"),
+            ("Memory", &self.memory_usage_pct, "mem.usage"),
+            ("Download", &self.download_bytes_per_sec, "net.down"),
+            ("Upload", &self.upload_bytes_per_sec, "net.up"),
+        ]
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        // A closed pipe (e.g. `| head`) is a normal way to stop `watch`.
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("pulsecore-cli: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let units = UnitFormatter::parse(&cli.units.apply(UnitPreferences::default()))
+        .map_err(io::Error::other)?;
+    match cli.command {
+        Command::Snapshot { sample_ms } => {
+            let mut collector = SystemCollector::new();
+            let snapshot = warm_up(&mut collector, clamp_interval(sample_ms));
+            print_snapshot(&mut out, cli.format, &units, &snapshot)
+        }
+        Command::Watch { interval, count } => {
+            let interval = clamp_interval(interval);
+            let mut collector = SystemCollector::new();
+            let mut next = warm_up_start(&mut collector, interval);
+            let mut emitted = 0u64;
+            if cli.format == OutputFormat::Table {
+                writeln!(out, "{}", watch_header())?;
+            }
+            while count.is_none_or(|limit| emitted < limit) {
+                sleep_until(next);
+                next += Duration::from_millis(interval);
+                let snapshot = collector.collect(interval);
+                match cli.format {
+                    OutputFormat::Table => writeln!(out, "{}", watch_row(&units, &snapshot))?,
+                    format => print_snapshot(&mut out, format, &units, &snapshot)?,
+                }
+                out.flush()?;
+                emitted += 1;
+            }
+            Ok(())
+        }
+        Command::Hardware => print_hardware(&mut out, cli.format, &collect_hardware_info()),
+        Command::Profile {
+            duration,
+            interval,
+            output,
+            top,
+            top_by,
+        } => {
+            let processes = process_scope(top, top_by).map_err(io::Error::other)?;
+            let summary = capture_profile(duration, clamp_interval(interval), output, processes)?;
+            print_summary(&mut out, cli.format, &units, &summary)
+        }
+        Command::Analyze { path, write } => {
+            let report = profile_analysis::analyze_profile(&path, &AnalysisOptions::default())
+                .map_err(io::Error::other)?;
+            let report_format = match cli.format {
+                OutputFormat::Table => ReportFormat::Markdown,
+                OutputFormat::Json | OutputFormat::Ndjson => ReportFormat::Json,
+            };
+            if write {
+                let written = profile_analysis::write_report(&path, &report, report_format)
+                    .map_err(io::Error::other)?;
+                eprintln!("report written to {}", written.display());
+            }
+            match cli.format {
+                OutputFormat::Table => writeln!(
+                    out,
+                    "{}",
+                    profile_analysis::render_markdown(&report).trim_end()
+                ),
+                format => write_json(&mut out, format, &report),
+            }
+        }
+        Command::Trace { path, output } => {
+            let export =
+                profile_trace::export_trace(&path, output.as_deref()).map_err(io::Error::other)?;
+            match cli.format {
+                OutputFormat::Table => writeln!(
+                    out,
+                    "{} events from {} samples -> {}",
+                    export.events, export.samples, export.trace_path
+                ),
+                format => write_json(&mut out, format, &export),
+            }
+        }
+        Command::Compare {
+            path,
+            baseline,
+            app_cpu_points,
+            app_memory_pct,
+            webview_memory_pct,
+            jitter_ms,
+        } => {
+            let defaults = RegressionTolerances::default();
+            let tolerances = RegressionTolerances {
+                app_cpu_points: app_cpu_points.unwrap_or(defaults.app_cpu_points),
+                app_memory_pct: app_memory_pct.unwrap_or(defaults.app_memory_pct),
+                webview_memory_pct: webview_memory_pct.unwrap_or(defaults.webview_memory_pct),
+                jitter_ms: jitter_ms.unwrap_or(defaults.jitter_ms),
+            };
+            let baseline = profile_diff::load_report(&baseline).map_err(io::Error::other)?;
+            let current = profile_diff::load_report(&path).map_err(io::Error::other)?;
+            let comparison = profile_diff::compare_reports(&baseline, &current, &tolerances);
+            match cli.format {
+                OutputFormat::Table => writeln!(
+                    out,
+                    "{}",
+                    profile_diff::render_markdown(&comparison).trim_end()
+                )?,
+                format => write_json(&mut out, format, &comparison)?,
+            }
+            if comparison.regressions > 0 {
+                return Err(io::Error::other(format!(
+                    "{} regression(s) over tolerance",
+                    comparison.regressions
+                )));
+            }
+            Ok(())
+        }
+        Command::Statusbar {
+            protocol,
+            interval,
+            config,
+            percentage,
+            output,
+        } => {
+            let mut config = match config {
+                Some(path) => load_taskbar_config(&path)?,
+                None => NativeTaskbarConfig::default(),
+            };
+            validate_segments(&segment_configs(&config)).map_err(io::Error::other)?;
+            config.units = cli.units.apply(config.units);
+            UnitFormatter::parse(&config.units).map_err(io::Error::other)?;
+            if config.thresholds.is_empty() {
+                config.thresholds = default_metric_thresholds();
+            }
+            let protocol = match protocol {
+                BarProtocol::I3bar => StatusBarProtocol::I3bar,
+                BarProtocol::Waybar => StatusBarProtocol::Waybar,
+            };
+            let percentage = MetricPath::parse(&percentage).map_err(io::Error::other)?;
+            let encoder = StatusBarEncoder::new(protocol, percentage);
+            stream_status_bar(
+                &mut out,
+                encoder,
+                &config,
+                clamp_interval(interval),
+                output.as_deref(),
+            )
+        }
+    }
+}
+
+fn load_taskbar_config(path: &Path) -> io::Result<NativeTaskbarConfig> {
+    let raw = std::fs::read_to_string(path)?;
+    serde_json::from_str(&raw).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
+
+/// Opens a status bar sink. Opening a FIFO blocks until a reader (the bar) attaches. Only
+/// FIFOs and sockets are accepted, so a mistyped path never becomes an ever-growing file.
+fn open_bar_output(path: &Path) -> io::Result<Box<dyn Write>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::{fs::FileTypeExt, net::UnixStream};
+        let metadata =
+            std::fs::metadata(path).map_err(|err| bar_output_error(path, &err.to_string()))?;
+        if metadata.file_type().is_socket() {
+            return Ok(Box::new(UnixStream::connect(path)?));
+        }
+        let file = OpenOptions::new().append(true).open(path)?;
+        if !file.metadata()?.file_type().is_fifo() {
+            return Err(bar_output_error(path, "not a FIFO or Unix socket"));
+        }
+        Ok(Box::new(file))
+    }
+    #[cfg(not(unix))]
+    {
+        // Named pipes (`\\.\pipe\...`) must already exist; nothing is created here either.
+        Ok(Box::new(OpenOptions::new().append(true).open(path)?))
+    }
+}
+
+#[cfg(unix)]
+fn bar_output_error(path: &Path, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "cannot stream to {}: {reason}; create it first with `mkfifo {}`",
+            path.display(),
+            path.display()
+        ),
+    )
+}
+
+fn stream_status_bar(
+    out: &mut impl Write,
+    mut encoder: StatusBarEncoder,
+    config: &NativeTaskbarConfig,
+    interval: u64,
+    output: Option<&Path>,
+) -> io::Result<()> {
+    let mut sink = output.map(open_bar_output).transpose()?;
+    let layout = TaskbarLayout::new(config);
+    let mut collector = SystemCollector::new();
+    let mut next = warm_up_start(&mut collector, interval);
+    loop {
+        sleep_until(next);
+        next += Duration::from_millis(interval);
+        let snapshot = collector.collect(interval);
+        let chunk = encoder.encode(&snapshot, &layout.build_segments(&snapshot));
+        let written = match sink.as_mut() {
+            Some(sink) => sink.write_all(chunk.as_bytes()).and_then(|()| sink.flush()),
+            None => out.write_all(chunk.as_bytes()).and_then(|()| out.flush()),
+        };
+        match (written, output) {
+            // The bar restarted: wait for the next reader and start a fresh stream for it.
+            (Err(err), Some(path)) if err.kind() == io::ErrorKind::BrokenPipe => {
+                sink = Some(open_bar_output(path)?);
+                encoder.reset();
+            }
+            (result, _) => result?,
+        }
+    }
+}
+
+fn clamp_interval(interval_ms: u64) -> u64 {
+    interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS)
+}
+
+/// The collector derives CPU and network rates from deltas, so the first sample is primed
+/// and discarded.
+fn warm_up_start(collector: &mut SystemCollector, interval_ms: u64) -> Instant {
+    let _ = collector.collect(interval_ms);
+    Instant::now() + Duration::from_millis(interval_ms)
+}
+
+fn warm_up(collector: &mut SystemCollector, interval_ms: u64) -> TelemetrySnapshot {
+    sleep_until(warm_up_start(collector, interval_ms));
+    collector.collect(interval_ms)
+}
+
+fn sleep_until(deadline: Instant) {
+    let now = Instant::now();
+    if deadline > now {
+        thread::sleep(deadline - now);
+    }
+}
+
+/// The `top` scope for `--top`, `None` when no processes are recorded.
+fn process_scope(top: usize, top_by: TopBy) -> Result<Option<ProcessScope>, String> {
+    if top == 0 {
+        return Ok(None);
+    }
+    let scope = ProfileScope {
+        mode: "top".to_string(),
+        top_n: top,
+        top_by: match top_by {
+            TopBy::Memory => "memory",
+            TopBy::Cpu => "cpu",
+        }
+        .to_string(),
+        include_app: false,
+        ..ProfileScope::default()
+    };
+    ProcessScope::parse(&scope).map(Some)
+}
+
+fn capture_profile(
+    duration_secs: u64,
+    interval_ms: u64,
+    output: Option<PathBuf>,
+    processes: Option<ProcessScope>,
+) -> io::Result<ProfileSummary> {
+    let mut writer = output
+        .as_deref()
+        .map(|path| ProfileWriter::create(path, ProfileStorageOptions::default()))
+        .transpose()
+        .map_err(io::Error::other)?;
+    let mut processes = processes.map(|scope| ProcessSampler::new(std::process::id(), scope));
+
+    let mut collector = SystemCollector::new();
+    let mut next = warm_up_start(&mut collector, interval_ms);
+    let deadline = next + Duration::from_secs(duration_secs.max(1));
+    let mut summary = ProfileSummary {
+        started_at: Some(Utc::now()),
+        output: writer
+            .as_ref()
+            .map(|writer| writer.path().to_string_lossy().to_string()),
+        ..Default::default()
+    };
+
+    while next <= deadline {
+        sleep_until(next);
+        next += Duration::from_millis(interval_ms);
+        let snapshot = collector.collect(interval_ms);
+        if let Some(writer) = writer.as_mut() {
+            let sample = ProfileSample {
+                timestamp: Utc::now(),
+                app_pid: std::process::id(),
+                refresh_rate_ms: interval_ms,
+                snapshot: snapshot.clone(),
+                processes: processes
+                    .as_mut()
+                    .map(ProcessSampler::sample)
+                    .unwrap_or_default(),
+                windows: Vec::new(),
+            };
+            writer.write_sample(&sample).map_err(io::Error::other)?;
+        }
+        summary.record(&snapshot);
+    }
+
+    if let Some(mut writer) = writer {
+        writer.close().map_err(io::Error::other)?;
+    }
+    summary.finished_at = Some(Utc::now());
+    Ok(summary)
+}
+
+fn write_json<T: Serialize>(
+    out: &mut impl Write,
+    format: OutputFormat,
+    value: &T,
+) -> io::Result<()> {
+    if format == OutputFormat::Json {
+        serde_json::to_writer_pretty(&mut *out, value)?;
+    } else {
+        serde_json::to_writer(&mut *out, value)?;
+    }
+    writeln!(out)
+}
+
+fn write_table(out: &mut impl Write, rows: &[(String, String)]) -> io::Result<()> {
+    let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    for (key, value) in rows {
+        writeln!(out, "{key:<width$}  {value}")?;
+    }
+    Ok(())
+}
+
+fn print_snapshot(
+    out: &mut impl Write,
+    format: OutputFormat,
+    units: &UnitFormatter,
+    snapshot: &TelemetrySnapshot,
+) -> io::Result<()> {
+    if format != OutputFormat::Table {
+        return write_json(out, format, snapshot);
+    }
+
+    let mut rows = vec![
+        ("Time".to_string(), snapshot.timestamp.to_rfc3339()),
+        (
+            "CPU".to_string(),
+            join_parts([
+                Some(units.percent(snapshot.cpu.usage_pct, 1)),
+                snapshot.cpu.temperature_c.map(|v| units.temperature(v, 0)),
+                snapshot
+                    .cpu
+                    .frequency_mhz
+                    .filter(|v| *v > 0)
+                    .map(|v| units.metric("cpu.freq", v as f64)),
+            ]),
+        ),
+        (
+            "GPU".to_string(),
+            join_parts([
+                snapshot.gpu.usage_pct.map(|v| units.percent(v, 1)),
+                snapshot.gpu.temperature_c.map(|v| units.temperature(v, 0)),
+                snapshot
+                    .gpu
+                    .memory_used_mb
+                    .zip(snapshot.gpu.memory_total_mb)
+                    .map(|(used, total)| {
+                        format!(
+                            "{}/{}",
+                            units.bytes(used * MIB, 1),
+                            units.bytes(total * MIB, 1)
+                        )
+                    }),
+            ]),
+        ),
+        (
+            "Memory".to_string(),
+            format!(
+                "{} ({}/{})",
+                units.percent(snapshot.memory.usage_pct, 1),
+                units.bytes(snapshot.memory.used_mb * MIB, 1),
+                units.bytes(snapshot.memory.total_mb * MIB, 1)
+            ),
+        ),
+        (
+            "Network".to_string(),
+            join_parts([
+                Some(format!(
+                    "down {}",
+                    units.rate(snapshot.network.download_bytes_per_sec, 1)
+                )),
+                Some(format!(
+                    "up {}",
+                    units.rate(snapshot.network.upload_bytes_per_sec, 1)
+                )),
+                snapshot
+                    .network
+                    .latency_ms
+                    .map(|v| units.metric("net.latency", v)),
+            ]),
+        ),
+    ];
+    if let Some(power) = snapshot.power_watts {
+        rows.push(("Power".to_string(), units.metric("power", power)));
+    }
+    for disk in &snapshot.disks {
+        rows.push((
+            format!("Disk {}", disk.name),
+            format!(
+                "{} ({}/{})",
+                units.percent(disk.usage_pct, 1),
+                units.bytes(disk.used_gb * GIB, 1),
+                units.bytes(disk.total_gb * GIB, 1)
+            ),
+        ));
+    }
+    write_table(out, &rows)
+}
+
+fn print_hardware(
+    out: &mut impl Write,
+    format: OutputFormat,
+    info: &HardwareInfo,
+) -> io::Result<()> {
+    if format != OutputFormat::Table {
+        return write_json(out, format, info);
+    }
+    let rows = vec![
+        ("CPU".to_string(), info.cpu_model.clone()),
+        (
+            "CPU max freq".to_string(),
+            info.cpu_max_freq_mhz
+                .map(|v| format!("{v} MHz"))
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        ("GPU".to_string(), info.gpu_model.clone()),
+        ("RAM".to_string(), info.ram_spec.clone()),
+        ("Disks".to_string(), info.disk_models.join(", ")),
+        ("Motherboard".to_string(), info.motherboard.clone()),
+        ("Brand".to_string(), info.device_brand.clone()),
+    ];
+    write_table(out, &rows)
+}
+
+fn print_summary(
+    out: &mut impl Write,
+    format: OutputFormat,
+    units: &UnitFormatter,
+    summary: &ProfileSummary,
+) -> io::Result<()> {
+    if format != OutputFormat::Table {
+        return write_json(out, format, summary);
+    }
+    writeln!(
+        out,
+        "{} samples{}",
+        summary.samples,
+        summary
+            .output
+            .as_deref()
+            .map(|path| format!(" -> {path}"))
+            .unwrap_or_default()
+    )?;
+    writeln!(
+        out,
+        "{:<10}  {:>12}  {:>12}  {:>12}",
+        "METRIC", "AVG", "MIN", "MAX"
+    )?;
+    for (label, metric, path) in summary.rows() {
+        if metric.samples == 0 {
+            continue;
+        }
+        writeln!(
+            out,
+            "{label:<10}  {:>12}  {:>12}  {:>12}",
+            units.metric(path, metric.avg),
+            units.metric(path, metric.min),
+            units.metric(path, metric.max)
+        )?;
+    }
+    Ok(())
+}
+
+fn watch_header() -> String {
+    format!(
+        "{:<8}  {:>7}  {:>7}  {:>7}  {:>7}  {:>7}  {:>13}  {:>13}",
+        "TIME", "CPU", "CPU TMP", "GPU", "GPU TMP", "MEM", "DOWN", "UP"
+    )
+}
+
+fn watch_row(units: &UnitFormatter, snapshot: &TelemetrySnapshot) -> String {
+    let dash = || "-".to_string();
+    format!(
+        "{:<8}  {:>7}  {:>7}  {:>7}  {:>7}  {:>7}  {:>13}  {:>13}",
+        snapshot
+            .timestamp
+            .with_timezone(&chrono::Local)
+            .format("%H:%M:%S"),
+        units.percent(snapshot.cpu.usage_pct, 1),
+        snapshot
+            .cpu
+            .temperature_c
+            .map(|v| units.temperature(v, 0))
+            .unwrap_or_else(dash),
+        snapshot
+            .gpu
+            .usage_pct
+            .map(|v| units.percent(v, 1))
+            .unwrap_or_else(dash),
+        snapshot
+            .gpu
+            .temperature_c
+            .map(|v| units.temperature(v, 0))
+            .unwrap_or_else(dash),
+        units.percent(snapshot.memory.usage_pct, 1),
+        units.rate(snapshot.network.download_bytes_per_sec, 1),
+        units.rate(snapshot.network.upload_bytes_per_sec, 1),
+    )
+}
+
+fn join_parts<const N: usize>(parts: [Option<String>; N]) -> String {
+    let joined = parts.into_iter().flatten().collect::<Vec<_>>().join(", ");
+    if joined.is_empty() {
+        "-".to_string()
+    } else {
+        joined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pulsecore_core::core::test_support;
+
+    use super::*;
+
+    fn render(print: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut out = Vec::new();
+        print(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn parses_global_format_and_profile_options() {
+        let cli = Cli::try_parse_from([
+            "pulsecore-cli",
+            "profile",
+            "--duration",
+            "5",
+            "--top-by",
+            "cpu",
+            "-f",
+            "ndjson",
+        ])
+        .unwrap();
+        assert_eq!(cli.format, OutputFormat::Ndjson);
+        let Command::Profile {
+            duration,
+            interval,
+            output,
+            top,
+            top_by,
+        } = cli.command
+        else {
+            panic!("expected profile, got {:?}", cli.command);
+        };
+        assert_eq!((duration, interval, top, top_by), (5, 1000, 10, TopBy::Cpu));
+        assert!(output.is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_values_and_missing_arguments() {
+        assert!(Cli::try_parse_from(["pulsecore-cli", "snapshot", "--format", "xml"]).is_err());
+        assert!(Cli::try_parse_from(["pulsecore-cli", "profile"]).is_err());
+        assert!(Cli::try_parse_from(["pulsecore-cli", "statusbar", "-p", "polybar"]).is_err());
+    }
+
+    #[test]
+    fn unit_flags_override_only_what_is_given() {
+        let cli = Cli::try_parse_from([
+            "pulsecore-cli",
+            "watch",
+            "--data-rate",
+            "bits",
+            "--temperature",
+            "fahrenheit",
+            "--precision",
+            "2",
+        ])
+        .unwrap();
+        let base = UnitPreferences {
+            prefix: "si".to_string(),
+            number_locale: "de-DE".to_string(),
+            ..UnitPreferences::default()
+        };
+        let prefs = cli.units.apply(base);
+        assert_eq!(prefs.data_rate, "bits");
+        assert_eq!(prefs.temperature, "fahrenheit");
+        assert_eq!(prefs.precision, Some(2));
+        assert_eq!(
+            (prefs.prefix.as_str(), prefs.number_locale.as_str()),
+            ("si", "de-DE")
+        );
+        let units = UnitFormatter::parse(&prefs).unwrap();
+        assert_eq!(units.rate(3.0 * MIB, 1), "25,17 Mbit/s");
+
+        assert!(Cli::try_parse_from(["pulsecore-cli", "snapshot", "--prefix", "metric"]).is_err());
+        assert!(
+            Cli::try_parse_from(["pulsecore-cli", "hardware", "--number-locale", "en-GB"]).is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bar_output_refuses_missing_paths_and_regular_files() {
+        let dir = std::env::temp_dir().join(format!("pulsecore-cli-bar-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let missing = dir.join("missing.fifo");
+        let err = open_bar_output(&missing).err().unwrap();
+        assert!(err.to_string().contains("mkfifo"), "{err}");
+        assert!(!missing.exists());
+
+        let regular = dir.join("regular.txt");
+        std::fs::write(&regular, "").unwrap();
+        let err = open_bar_output(&regular).err().unwrap();
+        assert!(err.to_string().contains("not a FIFO"), "{err}");
+        assert_eq!(std::fs::metadata(&regular).unwrap().len(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn statusbar_defaults_to_waybar_and_cpu_percentage() {
+        let cli = Cli::try_parse_from(["pulsecore-cli", "statusbar"]).unwrap();
+        assert_eq!(cli.format, OutputFormat::Table);
+        let Command::Statusbar {
+            protocol,
+            interval,
+            percentage,
+            ..
+        } = cli.command
+        else {
+            panic!("expected statusbar, got {:?}", cli.command);
+        };
+        assert_eq!(protocol, BarProtocol::Waybar);
+        assert_eq!(interval, 1000);
+        assert_eq!(percentage, "cpu.usage");
+    }
+
+    #[test]
+    fn intervals_are_clamped_and_top_zero_records_no_processes() {
+        assert_eq!(clamp_interval(0), MIN_INTERVAL_MS);
+        assert_eq!(clamp_interval(u64::MAX), MAX_INTERVAL_MS);
+        assert_eq!(clamp_interval(750), 750);
+        assert!(process_scope(0, TopBy::Memory).unwrap().is_none());
+        assert!(process_scope(5, TopBy::Cpu).unwrap().is_some());
+    }
+
+    #[test]
+    fn metric_summary_ignores_missing_and_non_finite_values() {
+        let mut summary = MetricSummary::default();
+        for value in [Some(4.0), None, Some(f64::NAN), Some(1.0), Some(7.0)] {
+            summary.push(value);
+        }
+        assert_eq!(summary.samples, 3);
+        assert_eq!((summary.min, summary.max), (1.0, 7.0));
+        assert!((summary.avg - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn snapshot_table_uses_the_unit_formatter() {
+        let mut snapshot = test_support::snapshot();
+        snapshot.cpu.temperature_c = Some(54.6);
+        snapshot.network.download_bytes_per_sec = 3.0 * MIB;
+        snapshot.disks.push(test_support::disk("C:", 100.0, 200.0));
+        let units = UnitFormatter::default();
+        let table = render(|out| print_snapshot(out, OutputFormat::Table, &units, &snapshot));
+
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(rows[1], "CPU      10.0%, 55°C");
+        assert_eq!(rows[2], "GPU      -");
+        assert_eq!(rows[3], "Memory   25.0% (4.0 GB/16.0 GB)");
+        assert_eq!(rows[4], "Network  down 3.0 MB/s, up 0 B/s");
+        assert_eq!(rows[5], "Disk C:  50.0% (100.0 GB/200.0 GB)");
+    }
+
+    #[test]
+    fn ndjson_snapshots_are_one_line_each() {
+        let snapshot = test_support::snapshot();
+        let units = UnitFormatter::default();
+        let line = render(|out| print_snapshot(out, OutputFormat::Ndjson, &units, &snapshot));
+        assert_eq!(line.lines().count(), 1);
+        let parsed: TelemetrySnapshot = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed.timestamp, snapshot.timestamp);
+    }
+
+    #[test]
+    fn watch_rows_line_up_with_the_header() {
+        let mut snapshot = test_support::snapshot();
+        snapshot.gpu.usage_pct = Some(100.0);
+        snapshot.network.upload_bytes_per_sec = 1023.9 * 1024.0;
+        let row = watch_row(&UnitFormatter::default(), &snapshot);
+        assert_eq!(row.len(), watch_header().len());
+        assert!(row.contains("100.0%"));
+        assert!(row.ends_with("1023.9 KB/s"));
+    }
+
+    #[test]
+    fn profile_summary_skips_metrics_without_samples() {
+        let mut summary = ProfileSummary::default();
+        summary.record(&test_support::snapshot_at(0, 20.0));
+        summary.record(&test_support::snapshot_at(1, 40.0));
+        let table = render(|out| {
+            print_summary(
+                out,
+                OutputFormat::Table,
+                &UnitFormatter::default(),
+                &summary,
+            )
+        });
+
+        assert!(table.starts_with("2 samples\n"));
+        let cpu = table.lines().find(|line| line.starts_with("CPU ")).unwrap();
+        assert_eq!(
+            cpu.split_whitespace().collect::<Vec<_>>(),
+            ["CPU", "30.0%", "20.0%", "40.0%"]
+        );
+        assert!(!table.contains("CPU temp"));
+        assert!(!table.contains("GPU"));
+    }
+}
@@ -332,12 +332,19 @@ pub struct SystemCollector {
     last_disk_poll: Instant,
     cpu_temp_cache: Option<f64>,
     last_cpu_temp_poll: Instant,
+    expensive_sources_paused: bool,
     #[cfg(target_os = "windows")]
     cpu_usage_query: Option<WindowsCpuUsageQuery>,
     #[cfg(target_os = "windows")]
     cpu_frequency_query: Option<WindowsCpuFrequencyQuery>,
 }
 
+impl Default for SystemCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SystemCollector {
     pub fn new() -> Self {
         let system = System::new();
@@ -388,6 +395,7 @@ impl SystemCollector {
             last_disk_poll: Instant::now() - Duration::from_secs(1),
             cpu_temp_cache: None,
             last_cpu_temp_poll: Instant::now() - Duration::from_secs(1),
+            expensive_sources_paused: false,
             #[cfg(target_os = "windows")]
             cpu_usage_query: WindowsCpuUsageQuery::new(),
             #[cfg(target_os = "windows")]
@@ -395,6 +403,12 @@ impl SystemCollector {
         }
     }
 
+    /// While paused, GPU, per-process, disk and temperature readings are served from the last
+    /// poll; CPU, memory and network stay live.
+    pub fn set_expensive_sources_paused(&mut self, paused: bool) {
+        self.expensive_sources_paused = paused;
+    }
+
     pub fn collect(&mut self, refresh_rate_ms: u64) -> TelemetrySnapshot {
         self.system.refresh_cpu_usage();
         self.system.refresh_memory();
@@ -440,7 +454,12 @@ impl SystemCollector {
             })
         };
 
-        let cpu_temp = self.refresh_cpu_temperature();
+        let paused = self.expensive_sources_paused;
+        let cpu_temp = if paused && self.warmup_done {
+            self.cpu_temp_cache
+        } else {
+            self.refresh_cpu_temperature()
+        };
 
         let memory_total_mb = self.system.total_memory() as f64 / (1024.0 * 1024.0);
         let memory_used_mb = self.system.used_memory() as f64 / (1024.0 * 1024.0);
@@ -450,7 +469,15 @@ impl SystemCollector {
             0.0
         };
 
-        let disks_vec = self.refresh_disk_metrics();
+        let swap_total_mb = self.system.total_swap() as f64 / (1024.0 * 1024.0);
+        let swap_used_mb = self.system.used_swap() as f64 / (1024.0 * 1024.0);
+        let has_swap = swap_total_mb > 0.0;
+
+        let disks_vec = if paused && !self.disk_cache.is_empty() {
+            self.disk_cache.clone()
+        } else {
+            self.refresh_disk_metrics()
+        };
 
         let (rx_total, tx_total) = Self::network_totals(&self.networks);
         let elapsed = self.prev_tick.elapsed().as_secs_f64().max(0.001);
@@ -466,9 +493,16 @@ impl SystemCollector {
         // from UI push interval to avoid 10ms settings causing counter thrashing.
         let gpu_sample_interval = Duration::from_millis(base_interval_ms.max(400));
         let app_usage_sample_interval = Duration::from_millis(base_interval_ms.max(300));
-        let gpu_metrics = self.refresh_gpu_metrics(gpu_sample_interval);
-        let (app_cpu_usage_pct, app_memory_mb) =
-            self.sample_app_usage_metrics(app_usage_sample_interval);
+        let gpu_metrics = if paused {
+            self.gpu_cache.clone()
+        } else {
+            self.refresh_gpu_metrics(gpu_sample_interval)
+        };
+        let (app_cpu_usage_pct, app_memory_mb) = if paused && self.app_usage_cache.0.is_some() {
+            self.app_usage_cache
+        } else {
+            self.sample_app_usage_metrics(app_usage_sample_interval)
+        };
 
         TelemetrySnapshot {
             timestamp: Utc::now(),
@@ -482,6 +516,8 @@ impl SystemCollector {
                 used_mb: memory_used_mb,
                 total_mb: memory_total_mb,
                 usage_pct: memory_usage_pct,
+                swap_used_mb: has_swap.then_some(swap_used_mb),
+                swap_total_mb: has_swap.then_some(swap_total_mb),
             },
             disks: disks_vec,
             network: NetworkMetrics {
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use image::RgbaImage;
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::{
+    core::ports::EventSink,
+    types::{
+        AlertEvent, AppSettings, ProfileStatus, ReminderScreenEventPayload, ReplayStatus,
+        TelemetrySnapshot,
+    },
+};
+
+pub const SNAPSHOT_EVENT: &str = "telemetry://snapshot";
+pub const REMINDER_TRIGGER_EVENT: &str = "reminder://trigger";
+pub const REPLAY_STATUS_EVENT: &str = "replay://status";
+pub const PROFILE_PROGRESS_EVENT: &str = "profile://progress";
+pub const PROFILE_FINISHED_EVENT: &str = "profile://finished";
+pub const SETTINGS_CHANGED_EVENT: &str = "settings://changed";
+pub const WINDOW_TITLE_EVENT: &str = "window://title";
+pub const TRAY_ICON_EVENT: &str = "tray://icon";
+
+/// Events the backend raises for front ends. `name()` is the channel the webview listens on.
+#[derive(Debug, Clone)]
+pub enum CoreEvent {
+    Snapshot(TelemetrySnapshot),
+    /// `alert://fired` or `alert://cleared`, depending on `state`.
+    Alert(AlertEvent),
+    /// Shown full screen by the first open window.
+    ReminderScreen(ReminderScreenEventPayload),
+    /// Profile replay started, paused, resumed, seeked, changed speed or ended.
+    ReplayStatus(ReplayStatus),
+    /// A running profile capture wrote more samples (throttled).
+    ProfileProgress(ProfileStatus),
+    /// A profile capture was stopped, ran out its duration or failed.
+    ProfileFinished(ProfileStatus),
+    /// Settings changed and were saved to `settings.json`.
+    SettingsChanged(AppSettings),
+    /// New title for the main window, rendered from `windowTitleTemplate` while it is visible.
+    WindowTitle(String),
+    /// A redrawn dynamic tray icon; only raised when the rounded value or band changed.
+    TrayIcon(Arc<RgbaImage>),
+}
+
+impl CoreEvent {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Snapshot(_) => SNAPSHOT_EVENT.to_string(),
+            Self::Alert(event) => format!("alert://{}", event.state),
+            Self::ReminderScreen(_) => REMINDER_TRIGGER_EVENT.to_string(),
+            Self::ReplayStatus(_) => REPLAY_STATUS_EVENT.to_string(),
+            Self::ProfileProgress(_) => PROFILE_PROGRESS_EVENT.to_string(),
+            Self::ProfileFinished(_) => PROFILE_FINISHED_EVENT.to_string(),
+            Self::SettingsChanged(_) => SETTINGS_CHANGED_EVENT.to_string(),
+            Self::WindowTitle(_) => WINDOW_TITLE_EVENT.to_string(),
+            Self::TrayIcon(_) => TRAY_ICON_EVENT.to_string(),
+        }
+    }
+
+    pub fn payload(&self) -> Value {
+        let value = match self {
+            Self::Snapshot(snapshot) => serde_json::to_value(snapshot),
+            Self::Alert(event) => serde_json::to_value(event),
+            Self::ReminderScreen(payload) => serde_json::to_value(payload),
+            Self::ReplayStatus(status) => serde_json::to_value(status),
+            Self::ProfileProgress(status) | Self::ProfileFinished(status) => {
+                serde_json::to_value(status)
+            }
+            Self::SettingsChanged(settings) => serde_json::to_value(settings),
+            Self::WindowTitle(title) => Ok(Value::String(title.clone())),
+            // Pixels are for the adapter drawing the tray, not for listeners.
+            Self::TrayIcon(_) => Ok(Value::Null),
+        };
+        value.unwrap_or(Value::Null)
+    }
+}
+
+/// In-process sink for front ends without a webview (CLI, tests). Events are dropped while
+/// nobody is subscribed.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<CoreEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        Self { tx }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CoreEvent> {
+        self.tx.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(64)
+    }
+}
+
+impl EventSink for EventBus {
+    fn publish(&self, event: CoreEvent) {
+        let _ = self.tx.send(event);
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::{vec_deque, VecDeque};
+
+use chrono::Duration;
+
+use crate::core::profile::ProfileSample;
+
+/// Rolling window of the most recent profile samples, so a capture triggered after a
+/// stutter still contains the minutes leading up to it.
+#[derive(Debug, Default)]
+pub struct FlightBuffer {
+    samples: VecDeque<ProfileSample>,
+    window_ms: u64,
+}
+
+impl FlightBuffer {
+    pub fn new(window_ms: u64) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            window_ms,
+        }
+    }
+
+    pub fn set_window(&mut self, window_ms: u64) {
+        self.window_ms = window_ms;
+        self.trim();
+    }
+
+    pub fn push(&mut self, sample: ProfileSample) {
+        self.samples.push_back(sample);
+        self.trim();
+    }
+
+    /// Drops samples older than the window, measured from the newest one.
+    fn trim(&mut self) {
+        let Some(newest) = self.samples.back().map(|sample| sample.timestamp) else {
+            return;
+        };
+        let oldest_kept = newest - Duration::milliseconds(self.window_ms as i64);
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| sample.timestamp < oldest_kept)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn span_ms(&self) -> u64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => {
+                (last.timestamp - first.timestamp).num_milliseconds().max(0) as u64
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn iter(&self) -> vec_deque::Iter<'_, ProfileSample> {
+        self.samples.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support;
+
+    fn sample(second: i64) -> ProfileSample {
+        test_support::profile_sample(test_support::snapshot_at(second, 1.0))
+    }
+
+    #[test]
+    fn buffer_keeps_only_the_trailing_window() {
+        let mut buffer = FlightBuffer::new(5_000);
+        for second in 0..10 {
+            buffer.push(sample(second));
+        }
+        assert_eq!(buffer.len(), 6);
+        assert_eq!(buffer.span_ms(), 5_000);
+        assert_eq!(buffer.iter().next().unwrap().timestamp.timestamp() % 60, 4);
+
+        buffer.set_window(2_000);
+        assert_eq!(buffer.len(), 3);
+    }
+}
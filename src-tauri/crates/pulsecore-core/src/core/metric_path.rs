@@ -0,0 +1,208 @@
+use crate::types::{DiskMetrics, TelemetrySnapshot};
+
+/// Dotted metric paths accepted by rules and templates. Disk metrics use
+/// `disk.<name|label|index>.<field>`, e.g. `disk.C.usage`.
+pub const METRIC_PATHS: &[&str] = &[
+    "cpu.usage",
+    "cpu.temp",
+    "cpu.freq",
+    "gpu.usage",
+    "gpu.temp",
+    "gpu.vram",
+    "gpu.vramTotal",
+    "gpu.freq",
+    "mem.usage",
+    "mem.used",
+    "mem.total",
+    "swap.usage",
+    "swap.used",
+    "swap.total",
+    "net.down",
+    "net.up",
+    "net.latency",
+    "app.cpu",
+    "app.mem",
+    "power",
+    "disk.<id>.usage",
+    "disk.<id>.used",
+    "disk.<id>.total",
+    "disk.<id>.read",
+    "disk.<id>.write",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskField {
+    Usage,
+    Used,
+    Total,
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetricPath {
+    CpuUsage,
+    CpuTemp,
+    CpuFreq,
+    GpuUsage,
+    GpuTemp,
+    GpuVram,
+    GpuVramTotal,
+    GpuFreq,
+    MemUsage,
+    MemUsed,
+    MemTotal,
+    SwapUsage,
+    SwapUsed,
+    SwapTotal,
+    NetDown,
+    NetUp,
+    NetLatency,
+    AppCpu,
+    AppMem,
+    Power,
+    Disk { id: String, field: DiskField },
+}
+
+impl MetricPath {
+    /// Parses a dotted path. Besides the short names in [`METRIC_PATHS`], the snapshot's
+    /// serialized field names (`gpu.temperature_c`, `memory.usage_pct`, ...) are accepted.
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let trimmed = path.trim();
+        if trimmed.is_empty() {
+            return Err("metric path is empty".to_string());
+        }
+        let lower = trimmed.to_ascii_lowercase();
+        let parsed = match lower.as_str() {
+            "cpu.usage" | "cpu.usage_pct" => Self::CpuUsage,
+            "cpu.temp" | "cpu.temperature_c" => Self::CpuTemp,
+            "cpu.freq" | "cpu.frequency_mhz" => Self::CpuFreq,
+            "gpu.usage" | "gpu.usage_pct" => Self::GpuUsage,
+            "gpu.temp" | "gpu.temperature_c" => Self::GpuTemp,
+            "gpu.vram" | "gpu.memory_used_mb" => Self::GpuVram,
+            "gpu.vramtotal" | "gpu.memory_total_mb" => Self::GpuVramTotal,
+            "gpu.freq" | "gpu.frequency_mhz" => Self::GpuFreq,
+            "mem.usage" | "memory.usage" | "memory.usage_pct" => Self::MemUsage,
+            "mem.used" | "memory.used" | "memory.used_mb" => Self::MemUsed,
+            "mem.total" | "memory.total" | "memory.total_mb" => Self::MemTotal,
+            "swap.usage" => Self::SwapUsage,
+            "swap.used" | "memory.swap_used_mb" => Self::SwapUsed,
+            "swap.total" | "memory.swap_total_mb" => Self::SwapTotal,
+            "net.down" | "network.download_bytes_per_sec" => Self::NetDown,
+            "net.up" | "network.upload_bytes_per_sec" => Self::NetUp,
+            "net.latency" | "network.latency_ms" => Self::NetLatency,
+            "app.cpu" | "appcpuusagepct" => Self::AppCpu,
+            "app.mem" | "appmemorymb" => Self::AppMem,
+            "power" | "power_watts" => Self::Power,
+            _ => return Self::parse_disk(trimmed),
+        };
+        Ok(parsed)
+    }
+
+    fn parse_disk(path: &str) -> Result<Self, String> {
+        let unknown = || {
+            format!(
+                "unknown metric path: {path} (expected one of {})",
+                METRIC_PATHS.join(", ")
+            )
+        };
+        let (prefix, rest) = path.split_once('.').ok_or_else(unknown)?;
+        if !prefix.eq_ignore_ascii_case("disk") && !prefix.eq_ignore_ascii_case("disks") {
+            return Err(unknown());
+        }
+        let (id, field) = rest.rsplit_once('.').ok_or_else(unknown)?;
+        let field = match field.to_ascii_lowercase().as_str() {
+            "usage" | "usage_pct" => DiskField::Usage,
+            "used" | "used_gb" => DiskField::Used,
+            "total" | "total_gb" => DiskField::Total,
+            "read" | "read_bytes_per_sec" => DiskField::Read,
+            "write" | "write_bytes_per_sec" => DiskField::Write,
+            _ => return Err(unknown()),
+        };
+        if id.trim().is_empty() {
+            return Err(format!("metric path is missing a disk id: {path}"));
+        }
+        Ok(Self::Disk {
+            id: id.trim().to_string(),
+            field,
+        })
+    }
+
+    pub fn value(&self, snapshot: &TelemetrySnapshot) -> Option<f64> {
+        let value = match self {
+            Self::CpuUsage => Some(snapshot.cpu.usage_pct),
+            Self::CpuTemp => snapshot.cpu.temperature_c,
+            Self::CpuFreq => snapshot.cpu.frequency_mhz.map(|v| v as f64),
+            Self::GpuUsage => snapshot.gpu.usage_pct,
+            Self::GpuTemp => snapshot.gpu.temperature_c,
+            Self::GpuVram => snapshot.gpu.memory_used_mb,
+            Self::GpuVramTotal => snapshot.gpu.memory_total_mb,
+            Self::GpuFreq => snapshot.gpu.frequency_mhz,
+            Self::MemUsage => Some(snapshot.memory.usage_pct),
+            Self::MemUsed => Some(snapshot.memory.used_mb),
+            Self::MemTotal => Some(snapshot.memory.total_mb),
+            Self::SwapUsage => snapshot
+                .memory
+                .swap_used_mb
+                .zip(snapshot.memory.swap_total_mb)
+                .filter(|(_, total)| *total > 0.0)
+                .map(|(used, total)| used / total * 100.0),
+            Self::SwapUsed => snapshot.memory.swap_used_mb,
+            Self::SwapTotal => snapshot.memory.swap_total_mb,
+            Self::NetDown => Some(snapshot.network.download_bytes_per_sec),
+            Self::NetUp => Some(snapshot.network.upload_bytes_per_sec),
+            Self::NetLatency => snapshot.network.latency_ms,
+            Self::AppCpu => snapshot.app_cpu_usage_pct,
+            Self::AppMem => snapshot.app_memory_mb,
+            Self::Power => snapshot.power_watts,
+            Self::Disk { id, field } => {
+                let disk = find_disk(&snapshot.disks, id)?;
+                match field {
+                    DiskField::Usage => Some(disk.usage_pct),
+                    DiskField::Used => Some(disk.used_gb),
+                    DiskField::Total => Some(disk.total_gb),
+                    DiskField::Read => disk.read_bytes_per_sec,
+                    DiskField::Write => disk.write_bytes_per_sec,
+                }
+            }
+        };
+        value.filter(|v| v.is_finite())
+    }
+}
+
+/// Matches `C`, `C:`, `C:\` or a volume label against a disk, falling back to an index.
+fn find_disk<'a>(disks: &'a [DiskMetrics], id: &str) -> Option<&'a DiskMetrics> {
+    let key = disk_key(id);
+    disks
+        .iter()
+        .find(|disk| disk_key(&disk.name) == key || disk_key(&disk.label) == key)
+        .or_else(|| id.parse::<usize>().ok().and_then(|index| disks.get(index)))
+}
+
+fn disk_key(value: &str) -> String {
+    value
+        .chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disk_paths_ignore_case() {
+        let usage = MetricPath::Disk {
+            id: "C:".to_string(),
+            field: DiskField::Usage,
+        };
+        assert_eq!(MetricPath::parse("disk.C:.usage"), Ok(usage.clone()));
+        assert_eq!(MetricPath::parse("Disk.C:.Usage"), Ok(usage.clone()));
+        assert_eq!(MetricPath::parse("DISKS.C:.USAGE_PCT"), Ok(usage));
+        assert!(MetricPath::parse("diskx.C.usage").is_err());
+        assert!(MetricPath::parse("disk. .usage")
+            .unwrap_err()
+            .contains("missing a disk id"));
+    }
+}
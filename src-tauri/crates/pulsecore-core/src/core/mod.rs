@@ -0,0 +1,29 @@
+pub mod collectors;
+pub mod device_info;
+pub mod events;
+pub mod flight_recorder;
+pub mod metric_path;
+pub mod ports;
+pub mod power;
+pub mod process_sampler;
+pub mod process_scope;
+pub mod process_stats;
+pub mod profile;
+pub mod profile_analysis;
+pub mod profile_diff;
+pub mod profile_store;
+pub mod profile_trace;
+pub mod reminders;
+pub mod replay;
+pub mod sampling;
+pub mod settings_store;
+pub mod sparkline;
+pub mod status_bar;
+pub mod store;
+pub mod taskbar;
+pub mod template;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod thresholds;
+pub mod tray_icon;
+pub mod units;
@@ -0,0 +1,158 @@
+use std::{
+    fs,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::{
+    core::{events::CoreEvent, profile::WindowSample},
+    types::SmtpEmailConfig,
+};
+
+pub type PortFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Wall-clock source, so schedulers can be driven by a fixed time in tests.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    fn local_now(&self) -> DateTime<Local> {
+        self.now().with_timezone(&Local)
+    }
+}
+
+/// Resolves where stores and captures live; the desktop app uses its app-data directory.
+pub trait PathProvider: Send + Sync {
+    fn data_dir(&self) -> Result<PathBuf, String>;
+
+    /// `data_dir()/file`, creating the directory on first use.
+    fn data_file(&self, file: &str) -> Result<PathBuf, String> {
+        let dir = self.data_dir()?;
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        Ok(dir.join(file))
+    }
+}
+
+/// Outbound message to a person, as opposed to [`CoreEvent`]s which go to front ends.
+#[derive(Debug, Clone)]
+pub enum Notification {
+    Email {
+        smtp: SmtpEmailConfig,
+        to: String,
+        subject: String,
+        text: String,
+        html: Option<String>,
+    },
+    Webhook {
+        endpoint_id: String,
+        event: String,
+        title: String,
+        text: String,
+    },
+}
+
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, notification: Notification) -> PortFuture<'a, Result<(), String>>;
+}
+
+/// Receives backend events; the adapter decides which front end sees them.
+pub trait EventSink: Send + Sync {
+    fn publish(&self, event: CoreEvent);
+}
+
+/// Reports the front end's windows, for profile samples and for skipping work nobody sees.
+pub trait WindowProbe: Send + Sync {
+    fn windows(&self) -> Vec<WindowSample>;
+
+    fn visible_labels(&self) -> Vec<String> {
+        self.windows()
+            .into_iter()
+            .filter(|window| window.visible)
+            .map(|window| window.label)
+            .collect()
+    }
+}
+
+/// The services core logic depends on. Front ends build one from their own adapters.
+#[derive(Clone)]
+pub struct CoreContext {
+    pub clock: Arc<dyn Clock>,
+    pub paths: Arc<dyn PathProvider>,
+    pub notifier: Arc<dyn Notifier>,
+    pub events: Arc<dyn EventSink>,
+    pub windows: Arc<dyn WindowProbe>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock pinned to a settable instant.
+#[derive(Debug)]
+pub struct FixedClock(Mutex<DateTime<Utc>>);
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self(Mutex::new(now))
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Stores everything under a fixed directory.
+#[derive(Debug, Clone)]
+pub struct DirPaths(pub PathBuf);
+
+impl PathProvider for DirPaths {
+    fn data_dir(&self) -> Result<PathBuf, String> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Front end without windows (CLI, tests).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoWindows;
+
+impl WindowProbe for NoWindows {
+    fn windows(&self) -> Vec<WindowSample> {
+        Vec::new()
+    }
+}
+
+/// Notifier that keeps every notification in memory instead of sending it.
+#[derive(Debug, Default)]
+pub struct RecordingNotifier {
+    sent: Mutex<Vec<Notification>>,
+}
+
+impl RecordingNotifier {
+    pub fn sent(&self) -> Vec<Notification> {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl Notifier for RecordingNotifier {
+    fn notify<'a>(&'a self, notification: Notification) -> PortFuture<'a, Result<(), String>> {
+        self.sent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(notification);
+        Box::pin(async { Ok(()) })
+    }
+}
@@ -0,0 +1,68 @@
+/// `Some(true)` when the machine runs on battery, `Some(false)` on AC, `None` when unknown
+/// (desktops without a battery, unsupported platforms).
+#[cfg(windows)]
+pub fn on_battery() -> Option<bool> {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    // BatteryFlag 128 = no system battery.
+    if status.BatteryFlag == 128 {
+        return None;
+    }
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut has_battery = false;
+    let mut mains_online = None;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Battery" => has_battery = true,
+            "Mains" => {
+                let online = read("online") == "1";
+                mains_online = Some(mains_online.unwrap_or(false) || online);
+            }
+            _ => {}
+        }
+    }
+    if !has_battery {
+        return None;
+    }
+    mains_online.map(|online| !online)
+}
+
+#[cfg(target_os = "macos")]
+pub fn on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        Some(true)
+    } else if text.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+pub fn on_battery() -> Option<bool> {
+    None
+}
@@ -0,0 +1,153 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Instant,
+};
+
+use chrono::DateTime;
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use crate::core::{
+    process_scope::{ProcessCandidate, ProcessScope},
+    process_stats::ProcessStats,
+    profile::{classify_process, ProcessSample},
+};
+
+/// Scans the process table for profile samples; `root_pid` and everything it spawned form
+/// the app tree.
+pub struct ProcessSampler {
+    system: System,
+    root_pid: Pid,
+    scope: ProcessScope,
+    /// When the process table was last refreshed; disk I/O counters are deltas since then.
+    last_refresh: Instant,
+}
+
+impl ProcessSampler {
+    pub fn new(root_pid: u32, scope: ProcessScope) -> Self {
+        Self {
+            system: System::new_all(),
+            root_pid: Pid::from_u32(root_pid),
+            scope,
+            last_refresh: Instant::now(),
+        }
+    }
+
+    pub fn root_pid(&self) -> u32 {
+        self.root_pid.as_u32()
+    }
+
+    /// The processes the scope selects, largest memory first.
+    pub fn sample(&mut self) -> Vec<ProcessSample> {
+        let mut refresh = ProcessRefreshKind::nothing()
+            .with_memory()
+            .with_cpu()
+            .with_disk_usage()
+            .with_exe(UpdateKind::OnlyIfNotSet);
+        if self.scope.needs_command_line() {
+            refresh = refresh.with_cmd(UpdateKind::OnlyIfNotSet);
+        }
+        self.system
+            .refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        let elapsed_secs = self.last_refresh.elapsed().as_secs_f64();
+        self.last_refresh = Instant::now();
+        collect_process_samples(&self.system, self.root_pid, &self.scope, elapsed_secs)
+    }
+}
+
+fn command_line(process: &Process) -> Option<String> {
+    let line = process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!line.is_empty()).then_some(line)
+}
+
+fn collect_process_samples(
+    system: &System,
+    root_pid: Pid,
+    scope: &ProcessScope,
+    elapsed_secs: f64,
+) -> Vec<ProcessSample> {
+    let mut children_map: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    let mut parent_map: HashMap<Pid, Pid> = HashMap::new();
+    for (pid, process) in system.processes() {
+        if let Some(parent) = process.parent() {
+            children_map.entry(parent).or_default().push(*pid);
+            parent_map.insert(*pid, parent);
+        }
+    }
+
+    let mut queue = VecDeque::new();
+    let mut app_tree = HashSet::new();
+    queue.push_back(root_pid);
+    while let Some(pid) = queue.pop_front() {
+        if !app_tree.insert(pid) {
+            continue;
+        }
+        if let Some(children) = children_map.get(&pid) {
+            for child in children {
+                queue.push_back(*child);
+            }
+        }
+    }
+
+    let candidates: Vec<ProcessCandidate> = system
+        .processes()
+        .iter()
+        .map(|(pid, process)| ProcessCandidate {
+            pid: pid.as_u32(),
+            name: process.name().to_string_lossy().to_string(),
+            command_line: if scope.needs_command_line() {
+                command_line(process)
+            } else {
+                None
+            },
+            // sysinfo on Windows reports process memory in bytes; normalize to MB.
+            memory_mb: process.memory() as f64 / 1024.0 / 1024.0,
+            cpu_pct: process.cpu_usage() as f64,
+            in_app_tree: app_tree.contains(pid),
+        })
+        .collect();
+
+    let stats = ProcessStats::capture();
+    let per_sec = |bytes: u64| (elapsed_secs > 0.0).then(|| bytes as f64 / elapsed_secs);
+    let mut samples = Vec::new();
+    for index in scope.select(&candidates) {
+        let candidate = &candidates[index];
+        let pid = Pid::from_u32(candidate.pid);
+        let Some(process) = system.process(pid) else {
+            continue;
+        };
+        let disk = process.disk_usage();
+        samples.push(ProcessSample {
+            pid: candidate.pid,
+            name: candidate.name.clone(),
+            parent_pid: parent_map.get(&pid).map(|p| p.as_u32()),
+            memory_mb: candidate.memory_mb,
+            cpu_pct: candidate.cpu_pct,
+            kind: if candidate.in_app_tree {
+                classify_process(&candidate.name)
+            } else {
+                "external".to_string()
+            },
+            thread_count: stats.thread_count(candidate.pid),
+            handle_count: stats.handle_count(candidate.pid),
+            disk_read_bytes_per_sec: per_sec(disk.read_bytes),
+            disk_write_bytes_per_sec: per_sec(disk.written_bytes),
+            start_time: DateTime::from_timestamp(process.start_time() as i64, 0),
+            command_line: if scope.include_command_line() {
+                candidate.command_line.clone()
+            } else {
+                None
+            },
+        });
+    }
+    samples.sort_by(|a, b| {
+        b.memory_mb
+            .partial_cmp(&a.memory_mb)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    samples
+}
@@ -0,0 +1,264 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::types::ProfileScope;
+
+const MAX_TOP_N: usize = 200;
+
+/// What the sampler knows about a process before deciding whether to record it.
+#[derive(Debug, Clone)]
+pub struct ProcessCandidate {
+    pub pid: u32,
+    pub name: String,
+    pub command_line: Option<String>,
+    pub memory_mb: f64,
+    pub cpu_pct: f64,
+    /// The app itself or anything it spawned.
+    pub in_app_tree: bool,
+}
+
+#[derive(Debug, Clone)]
+enum ScopeMode {
+    App,
+    Top {
+        count: usize,
+        by_cpu: bool,
+    },
+    Match {
+        names: Vec<String>,
+        pattern: Option<Regex>,
+    },
+}
+
+/// A validated [`ProfileScope`].
+#[derive(Debug, Clone)]
+pub struct ProcessScope {
+    mode: ScopeMode,
+    include_app: bool,
+    include_command_line: bool,
+}
+
+impl Default for ProcessScope {
+    fn default() -> Self {
+        Self {
+            mode: ScopeMode::App,
+            include_app: true,
+            include_command_line: false,
+        }
+    }
+}
+
+/// Lowercases a process name and drops a trailing `.exe`, so `Code.exe` matches `code`.
+fn normalize_name(name: &str) -> String {
+    let lower = name.trim().to_lowercase();
+    match lower.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => lower,
+    }
+}
+
+impl ProcessScope {
+    pub fn parse(scope: &ProfileScope) -> Result<Self, String> {
+        let mode = match scope.mode.trim().to_ascii_lowercase().as_str() {
+            "app" | "" => ScopeMode::App,
+            "top" => {
+                let by_cpu = match scope.top_by.trim().to_ascii_lowercase().as_str() {
+                    "memory" | "" => false,
+                    "cpu" => true,
+                    other => {
+                        return Err(format!(
+                            "unknown profile scope ranking: {other} (expected memory or cpu)"
+                        ))
+                    }
+                };
+                ScopeMode::Top {
+                    count: scope.top_n.clamp(1, MAX_TOP_N),
+                    by_cpu,
+                }
+            }
+            "match" => {
+                let names: Vec<String> = scope
+                    .names
+                    .iter()
+                    .map(|name| normalize_name(name))
+                    .filter(|name| !name.is_empty())
+                    .collect();
+                let pattern = match scope.pattern.as_deref().map(str::trim) {
+                    Some(pattern) if !pattern.is_empty() => Some(
+                        RegexBuilder::new(pattern)
+                            .case_insensitive(true)
+                            .build()
+                            .map_err(|e| format!("invalid profile scope pattern: {e}"))?,
+                    ),
+                    _ => None,
+                };
+                if names.is_empty() && pattern.is_none() {
+                    return Err("profile scope `match` needs names or a pattern".to_string());
+                }
+                ScopeMode::Match { names, pattern }
+            }
+            other => {
+                return Err(format!(
+                    "unknown profile scope: {other} (expected app, top or match)"
+                ))
+            }
+        };
+        Ok(Self {
+            mode,
+            include_app: scope.include_app,
+            include_command_line: scope.include_command_line,
+        })
+    }
+
+    pub fn include_command_line(&self) -> bool {
+        self.include_command_line
+    }
+
+    /// Whether matching needs command lines even when they are not recorded.
+    pub fn needs_command_line(&self) -> bool {
+        self.include_command_line
+            || matches!(
+                self.mode,
+                ScopeMode::Match {
+                    pattern: Some(_),
+                    ..
+                }
+            )
+    }
+
+    /// Indices into `candidates` of the processes to record.
+    pub fn select(&self, candidates: &[ProcessCandidate]) -> Vec<usize> {
+        let mut selected: Vec<usize> = match &self.mode {
+            ScopeMode::App => {
+                return (0..candidates.len())
+                    .filter(|&index| candidates[index].in_app_tree)
+                    .collect()
+            }
+            ScopeMode::Top { count, by_cpu } => {
+                let mut ranked: Vec<usize> = (0..candidates.len()).collect();
+                let key = |index: &usize| {
+                    let candidate = &candidates[*index];
+                    if *by_cpu {
+                        candidate.cpu_pct
+                    } else {
+                        candidate.memory_mb
+                    }
+                };
+                ranked.sort_by(|a, b| key(b).total_cmp(&key(a)));
+                ranked.truncate(*count);
+                ranked
+            }
+            ScopeMode::Match { names, pattern } => (0..candidates.len())
+                .filter(|&index| {
+                    let candidate = &candidates[index];
+                    names.contains(&normalize_name(&candidate.name))
+                        || pattern.as_ref().is_some_and(|pattern| {
+                            pattern.is_match(&candidate.name)
+                                || candidate
+                                    .command_line
+                                    .as_deref()
+                                    .is_some_and(|line| pattern.is_match(line))
+                        })
+                })
+                .collect(),
+        };
+        if self.include_app {
+            for (index, candidate) in candidates.iter().enumerate() {
+                if candidate.in_app_tree && !selected.contains(&index) {
+                    selected.push(index);
+                }
+            }
+        }
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(pid: u32, name: &str, memory_mb: f64, in_app_tree: bool) -> ProcessCandidate {
+        ProcessCandidate {
+            pid,
+            name: name.to_string(),
+            command_line: Some(format!("C:\\Apps\\{name}")),
+            memory_mb,
+            cpu_pct: 0.0,
+            in_app_tree,
+        }
+    }
+
+    fn pids(scope: &ProcessScope, candidates: &[ProcessCandidate]) -> Vec<u32> {
+        let mut pids: Vec<u32> = scope
+            .select(candidates)
+            .into_iter()
+            .map(|index| candidates[index].pid)
+            .collect();
+        pids.sort();
+        pids
+    }
+
+    #[test]
+    fn scopes_select_app_tree_top_n_and_matches() {
+        let mut candidates = vec![
+            candidate(1, "pulsecore.exe", 80.0, true),
+            candidate(2, "msedgewebview2.exe", 120.0, true),
+            candidate(3, "Code.exe", 900.0, false),
+            candidate(4, "chrome.exe", 1500.0, false),
+            candidate(5, "explorer.exe", 60.0, false),
+        ];
+        candidates[3].command_line = Some("chrome.exe --profile-directory=work".to_string());
+
+        let app = ProcessScope::parse(&ProfileScope::default()).unwrap();
+        assert_eq!(pids(&app, &candidates), vec![1, 2]);
+
+        let top = ProcessScope::parse(&ProfileScope {
+            mode: "top".to_string(),
+            top_n: 2,
+            include_app: false,
+            ..ProfileScope::default()
+        })
+        .unwrap();
+        assert_eq!(pids(&top, &candidates), vec![3, 4]);
+
+        let named = ProcessScope::parse(&ProfileScope {
+            mode: "match".to_string(),
+            names: vec!["code".to_string()],
+            pattern: Some("EXPLORER".to_string()),
+            ..ProfileScope::default()
+        })
+        .unwrap();
+        assert_eq!(pids(&named, &candidates), vec![1, 2, 3, 5]);
+
+        let by_command_line = ProcessScope::parse(&ProfileScope {
+            mode: "match".to_string(),
+            pattern: Some(r"--profile-directory=work".to_string()),
+            include_app: false,
+            ..ProfileScope::default()
+        })
+        .unwrap();
+        assert_eq!(pids(&by_command_line, &candidates), vec![4]);
+
+        assert!(ProcessScope::parse(&ProfileScope {
+            mode: "match".to_string(),
+            pattern: Some("(".to_string()),
+            ..ProfileScope::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn command_lines_are_opt_in() {
+        assert!(!ProcessScope::default().include_command_line());
+        let scope: ProfileScope = serde_json::from_str(r#"{"mode":"top"}"#).unwrap();
+        assert!(!ProcessScope::parse(&scope).unwrap().include_command_line());
+
+        let by_command_line = ProcessScope::parse(&ProfileScope {
+            mode: "match".to_string(),
+            pattern: Some("--profile".to_string()),
+            ..ProfileScope::default()
+        })
+        .unwrap();
+        assert!(!by_command_line.include_command_line());
+        assert!(by_command_line.needs_command_line());
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(windows)]
+use std::collections::HashMap;
+
+/// Per-process thread and handle counts that sysinfo does not expose on every platform.
+/// Take one per sample: on Windows it snapshots all thread counts in a single pass.
+#[derive(Debug, Default)]
+pub struct ProcessStats {
+    #[cfg(windows)]
+    threads: HashMap<u32, u32>,
+}
+
+impl ProcessStats {
+    #[cfg(windows)]
+    pub fn capture() -> Self {
+        use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+        use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+            TH32CS_SNAPPROCESS,
+        };
+
+        let mut threads = HashMap::new();
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if snapshot == INVALID_HANDLE_VALUE {
+                return Self { threads };
+            }
+            let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+            if Process32FirstW(snapshot, &mut entry) != 0 {
+                loop {
+                    threads.insert(entry.th32ProcessID, entry.cntThreads);
+                    if Process32NextW(snapshot, &mut entry) == 0 {
+                        break;
+                    }
+                }
+            }
+            CloseHandle(snapshot);
+        }
+        Self { threads }
+    }
+
+    #[cfg(not(windows))]
+    pub fn capture() -> Self {
+        Self::default()
+    }
+
+    #[cfg(windows)]
+    pub fn thread_count(&self, pid: u32) -> Option<u32> {
+        self.threads.get(&pid).copied()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn thread_count(&self, pid: u32) -> Option<u32> {
+        let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Threads:"))
+            .and_then(|value| value.trim().parse().ok())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    pub fn thread_count(&self, _pid: u32) -> Option<u32> {
+        None
+    }
+
+    /// Open handles on Windows, open file descriptors on Linux.
+    #[cfg(windows)]
+    pub fn handle_count(&self, pid: u32) -> Option<u32> {
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::Threading::{
+            GetProcessHandleCount, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+
+        unsafe {
+            let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if handle.is_null() {
+                return None;
+            }
+            let mut count = 0u32;
+            let ok = GetProcessHandleCount(handle, &mut count);
+            CloseHandle(handle);
+            (ok != 0).then_some(count)
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn handle_count(&self, pid: u32) -> Option<u32> {
+        let entries = std::fs::read_dir(format!("/proc/{pid}/fd")).ok()?;
+        Some(entries.count() as u32)
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    pub fn handle_count(&self, _pid: u32) -> Option<u32> {
+        None
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::VecDeque,
+    io::{BufRead, ErrorKind, Lines},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{
+        ports::PathProvider,
+        profile_store::{open_segment, profile_segments},
+    },
+    types::TelemetrySnapshot,
+};
+
+pub const PROFILE_DIR: &str = "profile-data";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSample {
+    pub pid: u32,
+    pub name: String,
+    #[serde(rename = "parentPid")]
+    pub parent_pid: Option<u32>,
+    #[serde(rename = "memoryMb")]
+    pub memory_mb: f64,
+    #[serde(rename = "cpuPct")]
+    pub cpu_pct: f64,
+    /// webview | app | child, or `external` for processes outside the app's tree.
+    pub kind: String,
+    #[serde(
+        rename = "threadCount",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub thread_count: Option<u32>,
+    /// Open handles on Windows, open file descriptors elsewhere.
+    #[serde(
+        rename = "handleCount",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub handle_count: Option<u32>,
+    #[serde(
+        rename = "diskReadBytesPerSec",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub disk_read_bytes_per_sec: Option<f64>,
+    #[serde(
+        rename = "diskWriteBytesPerSec",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub disk_write_bytes_per_sec: Option<f64>,
+    #[serde(rename = "startTime", default, skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<DateTime<Utc>>,
+    #[serde(
+        rename = "commandLine",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub command_line: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowSample {
+    pub label: String,
+    pub visible: bool,
+}
+
+/// One line of a `profile-*.jsonl` capture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSample {
+    pub timestamp: DateTime<Utc>,
+    #[serde(rename = "appPid")]
+    pub app_pid: u32,
+    #[serde(rename = "refreshRateMs")]
+    pub refresh_rate_ms: u64,
+    pub snapshot: TelemetrySnapshot,
+    #[serde(default)]
+    pub processes: Vec<ProcessSample>,
+    #[serde(default)]
+    pub windows: Vec<WindowSample>,
+}
+
+/// Groups processes in a capture: the app itself, its WebView2 hosts, everything else it spawned.
+pub fn classify_process(name: &str) -> String {
+    let lower = name.to_lowercase();
+    if lower.contains("msedgewebview2") {
+        "webview".to_string()
+    } else if lower.contains("pulsecore") {
+        "app".to_string()
+    } else {
+        "child".to_string()
+    }
+}
+
+/// Streams the samples of a capture across all of its segments, decompressing as needed.
+/// Lines that do not parse (e.g. a truncated last line) are counted and skipped, as is a
+/// gzip segment cut short by a crash; other I/O errors are yielded once and end the stream.
+pub struct ProfileReader {
+    pending: VecDeque<PathBuf>,
+    lines: Option<Lines<Box<dyn BufRead + Send>>>,
+    skipped_lines: u64,
+    failed: bool,
+}
+
+impl ProfileReader {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let segments = profile_segments(path);
+        if segments.is_empty() {
+            return Err(format!("{}: no such profile", path.display()));
+        }
+        Ok(Self {
+            pending: segments.into(),
+            lines: None,
+            skipped_lines: 0,
+            failed: false,
+        })
+    }
+
+    pub fn new(reader: impl BufRead + Send + 'static) -> Self {
+        let reader: Box<dyn BufRead + Send> = Box::new(reader);
+        Self {
+            pending: VecDeque::new(),
+            lines: Some(reader.lines()),
+            skipped_lines: 0,
+            failed: false,
+        }
+    }
+
+    pub fn skipped_lines(&self) -> u64 {
+        self.skipped_lines
+    }
+
+    fn next_line(&mut self) -> Option<Result<String, String>> {
+        loop {
+            if self.lines.is_none() {
+                let path = self.pending.pop_front()?;
+                match open_segment(&path) {
+                    Ok(reader) => self.lines = Some(reader.lines()),
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+            match self.lines.as_mut()?.next() {
+                Some(Ok(line)) => return Some(Ok(line)),
+                Some(Err(err)) if err.kind() == ErrorKind::UnexpectedEof => {
+                    self.skipped_lines += 1;
+                    self.lines = None;
+                }
+                Some(Err(err)) => return Some(Err(err.to_string())),
+                None => self.lines = None,
+            }
+        }
+    }
+}
+
+impl Iterator for ProfileReader {
+    type Item = Result<ProfileSample, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            let line = match self.next_line()? {
+                Ok(line) => line,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ProfileSample>(&line) {
+                Ok(sample) => return Some(Ok(sample)),
+                Err(_) => self.skipped_lines += 1,
+            }
+        }
+    }
+}
+
+pub fn resolve_profile_path(base_dir: &Path, path: &str) -> PathBuf {
+    let candidate = PathBuf::from(path);
+    let resolved = if candidate.is_absolute() {
+        candidate
+    } else {
+        base_dir.join(candidate)
+    };
+
+    if resolved.extension().is_some() {
+        return resolved;
+    }
+
+    let filename = format!("profile-{}.jsonl", Utc::now().format("%Y%m%d-%H%M%S"));
+    resolved.join(filename)
+}
+
+fn base_dir(paths: &dyn PathProvider) -> PathBuf {
+    paths
+        .data_dir()
+        .or_else(|_| std::env::current_dir().map_err(|e| e.to_string()))
+        .unwrap_or_else(|_| PathBuf::from("."))
+}
+
+pub fn ensure_profile_path(paths: &dyn PathProvider, path: &str) -> PathBuf {
+    // Resolve relative paths into app data to avoid dev hot-reload watching project folders.
+    let base_dir = base_dir(paths);
+    if path.trim().is_empty() {
+        return resolve_profile_path(&base_dir, PROFILE_DIR);
+    }
+
+    resolve_profile_path(&base_dir, path)
+}
+
+pub fn profile_output_dir(paths: &dyn PathProvider) -> PathBuf {
+    base_dir(paths).join(PROFILE_DIR)
+}
+
+/// Resolves a capture to read: absolute paths as given, relative ones against the profile
+/// directory first (a file or a segmented capture) and the data directory second.
+pub fn resolve_existing_profile(paths: &dyn PathProvider, path: &str) -> PathBuf {
+    let candidate = PathBuf::from(path.trim());
+    if candidate.is_absolute() {
+        return candidate;
+    }
+    let in_profile_dir = profile_output_dir(paths).join(&candidate);
+    if !profile_segments(&in_profile_dir).is_empty() {
+        return in_profile_dir;
+    }
+    base_dir(paths).join(candidate)
+}
@@ -0,0 +1,898 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    metric_path::MetricPath,
+    profile::{classify_process, ProfileReader, ProfileSample},
+    profile_store::capture_name,
+};
+
+/// Intervals longer than this multiple of the median interval count as gaps...
+const GAP_FACTOR: f64 = 2.0;
+/// ...provided they also exceed the median by this much, so jitter on fast captures is ignored.
+const MIN_GAP_EXCESS_MS: u64 = 500;
+/// Fewer samples than this give no meaningful memory trend.
+const MIN_TREND_SAMPLES: u64 = 3;
+const TOP_PROCESSES_PER_KIND: usize = 5;
+const MARKDOWN_GAP_ROWS: usize = 10;
+
+struct MetricSpec {
+    path: &'static str,
+    label: &'static str,
+    unit: &'static str,
+    /// Multiplier from the snapshot value to `unit`.
+    scale: f64,
+    thresholds: &'static [f64],
+}
+
+const METRICS: &[MetricSpec] = &[
+    MetricSpec {
+        path: "cpu.usage",
+        label: "CPU usage",
+        unit: "%",
+        scale: 1.0,
+        thresholds: &[50.0, 80.0, 90.0],
+    },
+    MetricSpec {
+        path: "mem.usage",
+        label: "Memory usage",
+        unit: "%",
+        scale: 1.0,
+        thresholds: &[70.0, 80.0, 90.0],
+    },
+    MetricSpec {
+        path: "gpu.usage",
+        label: "GPU usage",
+        unit: "%",
+        scale: 1.0,
+        thresholds: &[50.0, 80.0, 90.0],
+    },
+    MetricSpec {
+        path: "cpu.temp",
+        label: "CPU temperature",
+        unit: "°C",
+        scale: 1.0,
+        thresholds: &[70.0, 85.0],
+    },
+    MetricSpec {
+        path: "gpu.temp",
+        label: "GPU temperature",
+        unit: "°C",
+        scale: 1.0,
+        thresholds: &[70.0, 85.0],
+    },
+    MetricSpec {
+        path: "app.cpu",
+        label: "App CPU",
+        unit: "%",
+        scale: 1.0,
+        thresholds: &[1.0, 5.0],
+    },
+    MetricSpec {
+        path: "app.mem",
+        label: "App memory",
+        unit: "MB",
+        scale: 1.0,
+        thresholds: &[300.0, 500.0],
+    },
+    MetricSpec {
+        path: "net.down",
+        label: "Download",
+        unit: "KB/s",
+        scale: 1.0 / 1024.0,
+        thresholds: &[],
+    },
+    MetricSpec {
+        path: "net.up",
+        label: "Upload",
+        unit: "KB/s",
+        scale: 1.0 / 1024.0,
+        thresholds: &[],
+    },
+    MetricSpec {
+        path: "power",
+        label: "Power",
+        unit: "W",
+        scale: 1.0,
+        thresholds: &[],
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+}
+
+impl ReportFormat {
+    /// Accepts `markdown`/`md` (the default) and `json`.
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("markdown") | Some("md") => Ok(Self::Markdown),
+            Some("json") => Ok(Self::Json),
+            Some(other) => Err(format!(
+                "unknown report format: {other} (expected markdown or json)"
+            )),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Threshold overrides keyed by metric path (`cpu.usage`, `memory.usage_pct`, ...).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnalysisOptions {
+    #[serde(default)]
+    pub thresholds: HashMap<String, Vec<f64>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileReport {
+    pub source: String,
+    pub generated_at: DateTime<Utc>,
+    pub samples: u64,
+    pub skipped_lines: u64,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Typical spacing between samples, used to detect gaps.
+    pub median_interval_ms: u64,
+    /// Deviation of sample intervals from the median, gaps excluded.
+    #[serde(default)]
+    pub interval_jitter: IntervalJitter,
+    pub refresh_rates: Vec<RefreshRateUsage>,
+    pub gaps: Vec<SampleGap>,
+    pub gap_total_ms: u64,
+    pub metrics: Vec<MetricReport>,
+    pub process_kinds: Vec<ProcessKindReport>,
+}
+
+/// A report together with where it was written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileReportFile {
+    pub report_path: String,
+    pub report: ProfileReport,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshRateUsage {
+    pub refresh_rate_ms: u64,
+    pub samples: u64,
+    pub share_pct: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntervalJitter {
+    pub mean_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleGap {
+    /// Timestamp of the last sample before the gap.
+    pub after: DateTime<Utc>,
+    pub gap_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricReport {
+    pub metric: String,
+    pub label: String,
+    pub unit: String,
+    pub samples: u64,
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub above: Vec<ThresholdTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdTime {
+    pub threshold: f64,
+    /// Sample time spent strictly above the threshold; gaps count as one median interval.
+    pub duration_ms: u64,
+    pub share_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessKindReport {
+    pub kind: String,
+    /// Distinct (pid, name) pairs seen.
+    pub processes: u64,
+    /// Process rows across all samples.
+    pub records: u64,
+    pub peak_instances: u64,
+    pub peak_total_memory_mb: f64,
+    /// Least-squares slope of the kind's summed memory.
+    pub total_memory_slope_mb_per_min: Option<f64>,
+    /// Fastest growing processes of this kind.
+    pub top_processes: Vec<ProcessGrowth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessGrowth {
+    pub pid: u32,
+    pub name: String,
+    pub samples: u64,
+    pub first_memory_mb: f64,
+    pub last_memory_mb: f64,
+    pub peak_memory_mb: f64,
+    pub peak_cpu_pct: f64,
+    pub memory_slope_mb_per_min: Option<f64>,
+}
+
+/// Running least-squares fit of a value against seconds since the first sample.
+#[derive(Debug, Default)]
+struct Trend {
+    n: u64,
+    sum_t: f64,
+    sum_y: f64,
+    sum_tt: f64,
+    sum_ty: f64,
+}
+
+impl Trend {
+    fn push(&mut self, t: f64, y: f64) {
+        self.n += 1;
+        self.sum_t += t;
+        self.sum_y += y;
+        self.sum_tt += t * t;
+        self.sum_ty += t * y;
+    }
+
+    fn slope_per_min(&self) -> Option<f64> {
+        if self.n < MIN_TREND_SAMPLES {
+            return None;
+        }
+        let n = self.n as f64;
+        let denom = n * self.sum_tt - self.sum_t * self.sum_t;
+        if denom.abs() < f64::EPSILON {
+            return None;
+        }
+        Some((n * self.sum_ty - self.sum_t * self.sum_y) / denom * 60.0)
+    }
+}
+
+struct ProcessAccumulator {
+    name: String,
+    kind: String,
+    samples: u64,
+    first_mb: f64,
+    last_mb: f64,
+    peak_mb: f64,
+    peak_cpu: f64,
+    trend: Trend,
+}
+
+#[derive(Default)]
+struct KindAccumulator {
+    records: u64,
+    peak_instances: u64,
+    peak_total_mb: f64,
+    trend: Trend,
+}
+
+struct ResolvedMetric {
+    spec: &'static MetricSpec,
+    path: MetricPath,
+    thresholds: Vec<f64>,
+}
+
+/// Folds samples one at a time so large captures never sit in memory whole; only the
+/// per-sample metric values are kept for the percentiles.
+pub struct ProfileAnalyzer {
+    metrics: Vec<ResolvedMetric>,
+    timestamps: Vec<DateTime<Utc>>,
+    values: Vec<Vec<Option<f64>>>,
+    refresh_rates: BTreeMap<u64, u64>,
+    processes: HashMap<(u32, String), ProcessAccumulator>,
+    kinds: BTreeMap<String, KindAccumulator>,
+}
+
+impl ProfileAnalyzer {
+    pub fn new(options: &AnalysisOptions) -> Result<Self, String> {
+        let mut metrics = METRICS
+            .iter()
+            .map(|spec| {
+                Ok(ResolvedMetric {
+                    spec,
+                    path: MetricPath::parse(spec.path)?,
+                    thresholds: spec.thresholds.to_vec(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        for (key, thresholds) in &options.thresholds {
+            let path = MetricPath::parse(key)?;
+            let metric = metrics
+                .iter_mut()
+                .find(|metric| metric.path == path)
+                .ok_or_else(|| format!("metric is not part of the report: {key}"))?;
+            if thresholds.iter().any(|value| !value.is_finite()) {
+                return Err(format!("thresholds for {key} must be finite numbers"));
+            }
+            let mut thresholds = thresholds.clone();
+            thresholds.sort_by(f64::total_cmp);
+            thresholds.dedup();
+            metric.thresholds = thresholds;
+        }
+
+        let values = vec![Vec::new(); metrics.len()];
+        Ok(Self {
+            metrics,
+            timestamps: Vec::new(),
+            values,
+            refresh_rates: BTreeMap::new(),
+            processes: HashMap::new(),
+            kinds: BTreeMap::new(),
+        })
+    }
+
+    pub fn push(&mut self, sample: &ProfileSample) {
+        let start = self.timestamps.first().copied().unwrap_or(sample.timestamp);
+        let t = (sample.timestamp - start).num_milliseconds() as f64 / 1000.0;
+        self.timestamps.push(sample.timestamp);
+        for (metric, values) in self.metrics.iter().zip(self.values.iter_mut()) {
+            values.push(
+                metric
+                    .path
+                    .value(&sample.snapshot)
+                    .map(|value| value * metric.spec.scale),
+            );
+        }
+        *self
+            .refresh_rates
+            .entry(sample.refresh_rate_ms)
+            .or_default() += 1;
+
+        let mut per_kind: BTreeMap<String, (u64, f64)> = BTreeMap::new();
+        for process in &sample.processes {
+            let kind = if process.kind.is_empty() {
+                classify_process(&process.name)
+            } else {
+                process.kind.clone()
+            };
+            let entry = self
+                .processes
+                .entry((process.pid, process.name.clone()))
+                .or_insert_with(|| ProcessAccumulator {
+                    name: process.name.clone(),
+                    kind: kind.clone(),
+                    samples: 0,
+                    first_mb: process.memory_mb,
+                    last_mb: process.memory_mb,
+                    peak_mb: process.memory_mb,
+                    peak_cpu: process.cpu_pct,
+                    trend: Trend::default(),
+                });
+            entry.samples += 1;
+            entry.last_mb = process.memory_mb;
+            entry.peak_mb = entry.peak_mb.max(process.memory_mb);
+            entry.peak_cpu = entry.peak_cpu.max(process.cpu_pct);
+            entry.trend.push(t, process.memory_mb);
+
+            let totals = per_kind.entry(kind).or_default();
+            totals.0 += 1;
+            totals.1 += process.memory_mb;
+        }
+        for (kind, (instances, total_mb)) in per_kind {
+            let entry = self.kinds.entry(kind).or_default();
+            entry.records += instances;
+            entry.peak_instances = entry.peak_instances.max(instances);
+            entry.peak_total_mb = entry.peak_total_mb.max(total_mb);
+            entry.trend.push(t, total_mb);
+        }
+    }
+
+    pub fn finish(self, source: &Path, skipped_lines: u64) -> Result<ProfileReport, String> {
+        let (Some(&started_at), Some(&ended_at)) =
+            (self.timestamps.first(), self.timestamps.last())
+        else {
+            return Err("profile contains no readable samples".to_string());
+        };
+        let sample_count = self.timestamps.len() as u64;
+
+        let intervals: Vec<u64> = self
+            .timestamps
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).num_milliseconds().max(0) as u64)
+            .collect();
+        let median_interval_ms = {
+            let mut sorted = intervals.clone();
+            sorted.sort_unstable();
+            sorted.get(sorted.len() / 2).copied().unwrap_or(0)
+        };
+        let gap_threshold_ms = ((median_interval_ms as f64 * GAP_FACTOR) as u64)
+            .max(median_interval_ms + MIN_GAP_EXCESS_MS);
+
+        let mut gaps = Vec::new();
+        // Each sample stands for the time until the next one; gaps and the last sample
+        // count as one median interval.
+        let mut weights = Vec::with_capacity(self.timestamps.len());
+        for (index, interval) in intervals.iter().enumerate() {
+            if *interval > gap_threshold_ms {
+                gaps.push(SampleGap {
+                    after: self.timestamps[index],
+                    gap_ms: *interval,
+                });
+                weights.push(median_interval_ms);
+            } else {
+                weights.push(*interval);
+            }
+        }
+        weights.push(median_interval_ms);
+        let interval_jitter = interval_jitter(&intervals, median_interval_ms, gap_threshold_ms);
+
+        let metrics = self
+            .metrics
+            .iter()
+            .zip(&self.values)
+            .filter_map(|(metric, values)| summarize_metric(metric, values, &weights))
+            .collect();
+
+        let refresh_rates = self
+            .refresh_rates
+            .iter()
+            .map(|(rate, samples)| RefreshRateUsage {
+                refresh_rate_ms: *rate,
+                samples: *samples,
+                share_pct: *samples as f64 / sample_count as f64 * 100.0,
+            })
+            .collect();
+
+        let mut by_kind: BTreeMap<&str, Vec<ProcessGrowth>> = BTreeMap::new();
+        for ((pid, _), process) in &self.processes {
+            by_kind
+                .entry(process.kind.as_str())
+                .or_default()
+                .push(ProcessGrowth {
+                    pid: *pid,
+                    name: process.name.clone(),
+                    samples: process.samples,
+                    first_memory_mb: process.first_mb,
+                    last_memory_mb: process.last_mb,
+                    peak_memory_mb: process.peak_mb,
+                    peak_cpu_pct: process.peak_cpu,
+                    memory_slope_mb_per_min: process.trend.slope_per_min(),
+                });
+        }
+        let process_kinds = self
+            .kinds
+            .iter()
+            .map(|(kind, totals)| {
+                let mut processes = by_kind.remove(kind.as_str()).unwrap_or_default();
+                let distinct = processes.len() as u64;
+                processes.sort_by(|a, b| {
+                    let key = |p: &ProcessGrowth| p.memory_slope_mb_per_min.unwrap_or(f64::MIN);
+                    key(b).total_cmp(&key(a)).then(a.pid.cmp(&b.pid))
+                });
+                processes.truncate(TOP_PROCESSES_PER_KIND);
+                ProcessKindReport {
+                    kind: kind.clone(),
+                    processes: distinct,
+                    records: totals.records,
+                    peak_instances: totals.peak_instances,
+                    peak_total_memory_mb: totals.peak_total_mb,
+                    total_memory_slope_mb_per_min: totals.trend.slope_per_min(),
+                    top_processes: processes,
+                }
+            })
+            .collect();
+
+        Ok(ProfileReport {
+            source: source.to_string_lossy().to_string(),
+            generated_at: Utc::now(),
+            samples: sample_count,
+            skipped_lines,
+            started_at,
+            ended_at,
+            duration_ms: (ended_at - started_at).num_milliseconds().max(0) as u64,
+            median_interval_ms,
+            interval_jitter,
+            refresh_rates,
+            gap_total_ms: gaps.iter().map(|gap| gap.gap_ms).sum(),
+            gaps,
+            metrics,
+            process_kinds,
+        })
+    }
+}
+
+fn summarize_metric(
+    metric: &ResolvedMetric,
+    values: &[Option<f64>],
+    weights: &[u64],
+) -> Option<MetricReport> {
+    let present: Vec<(f64, u64)> = values
+        .iter()
+        .zip(weights)
+        .filter_map(|(value, weight)| value.map(|value| (value, *weight)))
+        .collect();
+    if present.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<f64> = present.iter().map(|(value, _)| *value).collect();
+    sorted.sort_by(f64::total_cmp);
+    let covered_ms: u64 = present.iter().map(|(_, weight)| weight).sum();
+
+    let above = metric
+        .thresholds
+        .iter()
+        .map(|threshold| {
+            let duration_ms: u64 = present
+                .iter()
+                .filter(|(value, _)| value > threshold)
+                .map(|(_, weight)| weight)
+                .sum();
+            ThresholdTime {
+                threshold: *threshold,
+                duration_ms,
+                share_pct: if covered_ms == 0 {
+                    0.0
+                } else {
+                    duration_ms as f64 / covered_ms as f64 * 100.0
+                },
+            }
+        })
+        .collect();
+
+    Some(MetricReport {
+        metric: metric.spec.path.to_string(),
+        label: metric.spec.label.to_string(),
+        unit: metric.spec.unit.to_string(),
+        samples: sorted.len() as u64,
+        min: sorted[0],
+        mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50: percentile(&sorted, 50.0),
+        p90: percentile(&sorted, 90.0),
+        p95: percentile(&sorted, 95.0),
+        p99: percentile(&sorted, 99.0),
+        max: sorted[sorted.len() - 1],
+        above,
+    })
+}
+
+fn interval_jitter(intervals: &[u64], median_ms: u64, gap_threshold_ms: u64) -> IntervalJitter {
+    let mut deviations: Vec<f64> = intervals
+        .iter()
+        .filter(|interval| **interval <= gap_threshold_ms)
+        .map(|interval| interval.abs_diff(median_ms) as f64)
+        .collect();
+    if deviations.is_empty() {
+        return IntervalJitter::default();
+    }
+    deviations.sort_by(f64::total_cmp);
+    IntervalJitter {
+        mean_ms: deviations.iter().sum::<f64>() / deviations.len() as f64,
+        p95_ms: percentile(&deviations, 95.0),
+        max_ms: deviations[deviations.len() - 1],
+    }
+}
+
+/// Nearest-rank percentile of an ascending, non-empty slice.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    let rank = (pct / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+pub fn analyze_profile(path: &Path, options: &AnalysisOptions) -> Result<ProfileReport, String> {
+    let mut analyzer = ProfileAnalyzer::new(options)?;
+    let mut reader = ProfileReader::open(path)?;
+    for sample in reader.by_ref() {
+        analyzer.push(&sample?);
+    }
+    analyzer.finish(path, reader.skipped_lines())
+}
+
+/// `profile-x.jsonl` or any of its segments -> `profile-x.report.md` (or `.report.json`)
+/// in the same folder.
+pub fn report_path(profile: &Path, format: ReportFormat) -> PathBuf {
+    profile.with_file_name(format!(
+        "{}.report.{}",
+        capture_name(profile),
+        format.extension()
+    ))
+}
+
+pub fn render_report(report: &ProfileReport, format: ReportFormat) -> Result<String, String> {
+    match format {
+        ReportFormat::Markdown => Ok(render_markdown(report)),
+        ReportFormat::Json => serde_json::to_string_pretty(report).map_err(|e| e.to_string()),
+    }
+}
+
+/// Writes the report next to the profile and returns its path.
+pub fn write_report(
+    profile: &Path,
+    report: &ProfileReport,
+    format: ReportFormat,
+) -> Result<PathBuf, String> {
+    let path = report_path(profile, format);
+    fs::write(&path, render_report(report, format)?)
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(path)
+}
+
+pub fn render_markdown(report: &ProfileReport) -> String {
+    let mut out = String::new();
+    let name = Path::new(&report.source)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| report.source.clone());
+    let _ = writeln!(out, "# Profile report: {name}\n");
+    let _ = writeln!(out, "- Source: `{}`", report.source);
+    let _ = writeln!(
+        out,
+        "- Generated: {}",
+        report.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    let _ = write!(out, "- Samples: {}", report.samples);
+    if report.skipped_lines > 0 {
+        let _ = write!(out, " ({} unreadable lines skipped)", report.skipped_lines);
+    }
+    let _ = writeln!(
+        out,
+        "\n- Span: {} to {} ({})",
+        report.started_at.format("%Y-%m-%d %H:%M:%S"),
+        report.ended_at.format("%Y-%m-%d %H:%M:%S"),
+        format_duration(report.duration_ms)
+    );
+    let _ = writeln!(
+        out,
+        "- Median interval: {} ms (jitter mean {:.1} ms, p95 {:.1} ms)",
+        report.median_interval_ms, report.interval_jitter.mean_ms, report.interval_jitter.p95_ms
+    );
+    let rates: Vec<String> = report
+        .refresh_rates
+        .iter()
+        .map(|rate| format!("{} ms ({:.1}%)", rate.refresh_rate_ms, rate.share_pct))
+        .collect();
+    let _ = writeln!(out, "- Refresh rates: {}", rates.join(", "));
+    if report.gaps.is_empty() {
+        let _ = writeln!(out, "- Gaps: none");
+    } else {
+        let longest = report.gaps.iter().map(|gap| gap.gap_ms).max().unwrap_or(0);
+        let _ = writeln!(
+            out,
+            "- Gaps: {} totalling {} (longest {})",
+            report.gaps.len(),
+            format_duration(report.gap_total_ms),
+            format_duration(longest)
+        );
+    }
+
+    let _ = writeln!(out, "\n## Metrics\n");
+    let _ = writeln!(
+        out,
+        "| Metric | Samples | Min | Mean | P50 | P90 | P95 | P99 | Max |"
+    );
+    let _ = writeln!(out, "|---|---:|---:|---:|---:|---:|---:|---:|---:|");
+    for metric in &report.metrics {
+        let _ = writeln!(
+            out,
+            "| {} ({}) | {} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} |",
+            metric.label,
+            metric.unit,
+            metric.samples,
+            metric.min,
+            metric.mean,
+            metric.p50,
+            metric.p90,
+            metric.p95,
+            metric.p99,
+            metric.max
+        );
+    }
+
+    let thresholds: Vec<(&MetricReport, &ThresholdTime)> = report
+        .metrics
+        .iter()
+        .flat_map(|metric| metric.above.iter().map(move |above| (metric, above)))
+        .collect();
+    if !thresholds.is_empty() {
+        let _ = writeln!(out, "\n## Time above thresholds\n");
+        let _ = writeln!(out, "| Metric | Threshold | Time | Share |");
+        let _ = writeln!(out, "|---|---:|---:|---:|");
+        for (metric, above) in thresholds {
+            let _ = writeln!(
+                out,
+                "| {} | > {} {} | {} | {:.1}% |",
+                metric.label,
+                above.threshold,
+                metric.unit,
+                format_duration(above.duration_ms),
+                above.share_pct
+            );
+        }
+    }
+
+    if !report.process_kinds.is_empty() {
+        let _ = writeln!(out, "\n## Processes by kind\n");
+        let _ = writeln!(
+            out,
+            "| Kind | Processes | Records | Peak instances | Peak total memory | Total memory trend |"
+        );
+        let _ = writeln!(out, "|---|---:|---:|---:|---:|---:|");
+        for kind in &report.process_kinds {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {:.1} MB | {} |",
+                kind.kind,
+                kind.processes,
+                kind.records,
+                kind.peak_instances,
+                kind.peak_total_memory_mb,
+                format_slope(kind.total_memory_slope_mb_per_min)
+            );
+        }
+        for kind in &report.process_kinds {
+            if kind.top_processes.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n### Fastest growing `{}` processes\n", kind.kind);
+            let _ = writeln!(
+                out,
+                "| PID | Name | Samples | First | Last | Peak | Peak CPU | Trend |"
+            );
+            let _ = writeln!(out, "|---:|---|---:|---:|---:|---:|---:|---:|");
+            for process in &kind.top_processes {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {:.1} MB | {:.1} MB | {:.1} MB | {:.1}% | {} |",
+                    process.pid,
+                    process.name,
+                    process.samples,
+                    process.first_memory_mb,
+                    process.last_memory_mb,
+                    process.peak_memory_mb,
+                    process.peak_cpu_pct,
+                    format_slope(process.memory_slope_mb_per_min)
+                );
+            }
+        }
+    }
+
+    if !report.gaps.is_empty() {
+        let mut gaps: Vec<&SampleGap> = report.gaps.iter().collect();
+        gaps.sort_by_key(|gap| std::cmp::Reverse(gap.gap_ms));
+        let _ = writeln!(out, "\n## Longest sample gaps\n");
+        let _ = writeln!(out, "| After | Gap |");
+        let _ = writeln!(out, "|---|---:|");
+        for gap in gaps.into_iter().take(MARKDOWN_GAP_ROWS) {
+            let _ = writeln!(
+                out,
+                "| {} | {} |",
+                gap.after.format("%Y-%m-%d %H:%M:%S%.3f"),
+                format_duration(gap.gap_ms)
+            );
+        }
+    }
+    out
+}
+
+fn format_slope(slope: Option<f64>) -> String {
+    match slope {
+        Some(value) => format!("{value:+.2} MB/min"),
+        None => "-".to_string(),
+    }
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!(
+            "{}h {:02}m {:02}s",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        )
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{:.1} s", ms as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support;
+
+    fn sample(second: i64, cpu: f64, webview_mb: f64) -> ProfileSample {
+        let mut sample = test_support::profile_sample(test_support::snapshot_at(second, cpu));
+        sample
+            .processes
+            .push(test_support::process(7, "msedgewebview2.exe", webview_mb));
+        sample
+    }
+
+    /// One sample per second, then a 10 s hole before the last one.
+    fn report() -> ProfileReport {
+        let mut analyzer = ProfileAnalyzer::new(&AnalysisOptions::default()).unwrap();
+        for (second, cpu, mb) in [
+            (0, 10.0, 100.0),
+            (1, 60.0, 101.0),
+            (2, 95.0, 102.0),
+            (3, 20.0, 103.0),
+            (13, 30.0, 113.0),
+        ] {
+            analyzer.push(&sample(second, cpu, mb));
+        }
+        analyzer.finish(Path::new("p.jsonl"), 0).unwrap()
+    }
+
+    #[test]
+    fn report_finds_intervals_and_gaps() {
+        let report = report();
+        assert_eq!(report.samples, 5);
+        assert_eq!(report.median_interval_ms, 1000);
+        assert_eq!(report.gaps.len(), 1);
+        assert_eq!(report.gaps[0].gap_ms, 10_000);
+    }
+
+    #[test]
+    fn report_covers_percentiles_and_thresholds() {
+        let report = report();
+        let cpu = &report.metrics[0];
+        assert_eq!(cpu.metric, "cpu.usage");
+        assert_eq!((cpu.min, cpu.p50, cpu.max), (10.0, 30.0, 95.0));
+        // 60 and 95 are above 50; each stands for one second of the 5 s covered.
+        assert_eq!(cpu.above[0].duration_ms, 2000);
+        assert_eq!(cpu.above[0].share_pct, 40.0);
+        assert!(report.metrics.iter().all(|m| m.metric != "gpu.usage"));
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("| CPU usage | > 50 % | 2.0 s | 40.0% |"));
+    }
+
+    #[test]
+    fn report_tracks_process_memory_growth() {
+        let report = report();
+        let webview = &report.process_kinds[0];
+        assert_eq!(webview.kind, "webview");
+        assert_eq!(webview.peak_instances, 1);
+        let slope = webview.top_processes[0].memory_slope_mb_per_min.unwrap();
+        assert!((slope - 60.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn report_paths_use_the_capture_name() {
+        assert_eq!(
+            report_path(Path::new("/tmp/profile-1.jsonl"), ReportFormat::Json),
+            PathBuf::from("/tmp/profile-1.report.json")
+        );
+        assert_eq!(
+            report_path(
+                Path::new("/tmp/profile-1.002.jsonl.gz"),
+                ReportFormat::Markdown
+            ),
+            PathBuf::from("/tmp/profile-1.report.md")
+        );
+    }
+}
@@ -0,0 +1,401 @@
+use std::{fmt::Write as _, fs, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    ports::PathProvider,
+    profile_analysis::{self, AnalysisOptions, MetricReport, ProfileReport},
+    store,
+};
+
+const PROFILE_BASELINE_FILE: &str = "profile-baseline.json";
+
+fn default_app_cpu_points() -> f64 {
+    1.0
+}
+
+fn default_app_memory_pct() -> f64 {
+    10.0
+}
+
+fn default_webview_memory_pct() -> f64 {
+    15.0
+}
+
+fn default_jitter_ms() -> f64 {
+    50.0
+}
+
+/// How much worse the current profile may be before a check fails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegressionTolerances {
+    /// Allowed rise of the app CPU percentiles, in percentage points
+    #[serde(default = "default_app_cpu_points")]
+    pub app_cpu_points: f64,
+    /// Allowed rise of the app memory percentiles, relative to the baseline
+    #[serde(default = "default_app_memory_pct")]
+    pub app_memory_pct: f64,
+    /// Allowed rise of the peak total WebView memory, relative to the baseline
+    #[serde(default = "default_webview_memory_pct")]
+    pub webview_memory_pct: f64,
+    /// Allowed rise of the p95 sample interval jitter, in milliseconds
+    #[serde(default = "default_jitter_ms")]
+    pub jitter_ms: f64,
+}
+
+impl Default for RegressionTolerances {
+    fn default() -> Self {
+        Self {
+            app_cpu_points: default_app_cpu_points(),
+            app_memory_pct: default_app_memory_pct(),
+            webview_memory_pct: default_webview_memory_pct(),
+            jitter_ms: default_jitter_ms(),
+        }
+    }
+}
+
+/// The stored reference a release is checked against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileBaseline {
+    pub saved_at: DateTime<Utc>,
+    #[serde(default)]
+    pub tolerances: RegressionTolerances,
+    pub report: ProfileReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegressionCheck {
+    pub id: String,
+    pub label: String,
+    pub unit: String,
+    pub baseline: f64,
+    pub current: f64,
+    pub delta: f64,
+    /// Delta relative to the baseline; `None` when the baseline is zero.
+    pub delta_pct: Option<f64>,
+    /// Allowed delta, in `unit` for absolute checks and percent for relative ones.
+    pub tolerance: f64,
+    /// absolute | relative
+    pub tolerance_kind: String,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileComparison {
+    pub baseline_source: String,
+    pub current_source: String,
+    pub generated_at: DateTime<Utc>,
+    /// pass | fail
+    pub verdict: String,
+    pub regressions: usize,
+    pub tolerances: RegressionTolerances,
+    pub checks: Vec<RegressionCheck>,
+    /// Checks skipped because one of the profiles has no data for them.
+    pub skipped: Vec<String>,
+}
+
+enum Tolerance {
+    Absolute(f64),
+    Relative(f64),
+}
+
+struct CheckSpec {
+    id: &'static str,
+    label: &'static str,
+    unit: &'static str,
+    tolerance: Tolerance,
+}
+
+type MetricPick = fn(&MetricReport) -> f64;
+
+/// (check id, metric path, label, unit, statistic)
+const PERCENTILE_CHECKS: &[(&str, &str, &str, &str, MetricPick)] = &[
+    ("app.cpu.p50", "app.cpu", "App CPU p50", "%", |m| m.p50),
+    ("app.cpu.p95", "app.cpu", "App CPU p95", "%", |m| m.p95),
+    ("app.cpu.p99", "app.cpu", "App CPU p99", "%", |m| m.p99),
+    ("app.mem.p50", "app.mem", "App memory p50", "MB", |m| m.p50),
+    ("app.mem.p95", "app.mem", "App memory p95", "MB", |m| m.p95),
+    ("app.mem.p99", "app.mem", "App memory p99", "MB", |m| m.p99),
+];
+
+fn metric<'a>(report: &'a ProfileReport, path: &str) -> Option<&'a MetricReport> {
+    report.metrics.iter().find(|metric| metric.metric == path)
+}
+
+fn webview_peak_mb(report: &ProfileReport) -> Option<f64> {
+    report
+        .process_kinds
+        .iter()
+        .find(|kind| kind.kind == "webview")
+        .map(|kind| kind.peak_total_memory_mb)
+}
+
+#[derive(Default)]
+struct Checks {
+    checks: Vec<RegressionCheck>,
+    skipped: Vec<String>,
+}
+
+impl Checks {
+    fn push(&mut self, spec: CheckSpec, baseline: Option<f64>, current: Option<f64>) {
+        match (baseline, current) {
+            (Some(baseline), Some(current)) => self.checks.push(check(spec, baseline, current)),
+            _ => self.skipped.push(spec.id.to_string()),
+        }
+    }
+}
+
+fn check(spec: CheckSpec, baseline: f64, current: f64) -> RegressionCheck {
+    let delta = current - baseline;
+    let delta_pct = (baseline.abs() > f64::EPSILON).then(|| delta / baseline * 100.0);
+    let (tolerance, tolerance_kind, passed) = match spec.tolerance {
+        Tolerance::Absolute(allowed) => (allowed, "absolute", delta <= allowed),
+        // A zero baseline has no relative scale; any rise fails.
+        Tolerance::Relative(allowed) => (
+            allowed,
+            "relative",
+            delta_pct.map_or(delta <= 0.0, |pct| pct <= allowed),
+        ),
+    };
+    RegressionCheck {
+        id: spec.id.to_string(),
+        label: spec.label.to_string(),
+        unit: spec.unit.to_string(),
+        baseline,
+        current,
+        delta,
+        delta_pct,
+        tolerance,
+        tolerance_kind: tolerance_kind.to_string(),
+        passed,
+    }
+}
+
+/// Compares `current` against `baseline`; only increases count as regressions.
+pub fn compare_reports(
+    baseline: &ProfileReport,
+    current: &ProfileReport,
+    tolerances: &RegressionTolerances,
+) -> ProfileComparison {
+    let mut results = Checks::default();
+    for (id, path, label, unit, pick) in PERCENTILE_CHECKS {
+        let tolerance = if *path == "app.cpu" {
+            Tolerance::Absolute(tolerances.app_cpu_points)
+        } else {
+            Tolerance::Relative(tolerances.app_memory_pct)
+        };
+        results.push(
+            CheckSpec {
+                id,
+                label,
+                unit,
+                tolerance,
+            },
+            metric(baseline, path).map(pick),
+            metric(current, path).map(pick),
+        );
+    }
+    results.push(
+        CheckSpec {
+            id: "webview.mem.peak",
+            label: "WebView memory (peak total)",
+            unit: "MB",
+            tolerance: Tolerance::Relative(tolerances.webview_memory_pct),
+        },
+        webview_peak_mb(baseline),
+        webview_peak_mb(current),
+    );
+    let jitter =
+        |report: &ProfileReport| (report.samples > 1).then_some(report.interval_jitter.p95_ms);
+    results.push(
+        CheckSpec {
+            id: "jitter.p95",
+            label: "Sample jitter (p95)",
+            unit: "ms",
+            tolerance: Tolerance::Absolute(tolerances.jitter_ms),
+        },
+        jitter(baseline),
+        jitter(current),
+    );
+
+    let Checks { checks, skipped } = results;
+    let regressions = checks.iter().filter(|check| !check.passed).count();
+    ProfileComparison {
+        baseline_source: baseline.source.clone(),
+        current_source: current.source.clone(),
+        generated_at: Utc::now(),
+        verdict: if regressions == 0 { "pass" } else { "fail" }.to_string(),
+        regressions,
+        tolerances: tolerances.clone(),
+        checks,
+        skipped,
+    }
+}
+
+/// Analyzes a capture, or reads a `.report.json` written by an earlier analysis.
+pub fn load_report(path: &Path) -> Result<ProfileReport, String> {
+    let is_report = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".report.json"));
+    if is_report {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        return serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()));
+    }
+    profile_analysis::analyze_profile(path, &AnalysisOptions::default())
+}
+
+pub fn read_baseline(paths: &dyn PathProvider) -> Option<ProfileBaseline> {
+    store::read_json(paths, PROFILE_BASELINE_FILE)
+}
+
+pub fn write_baseline(paths: &dyn PathProvider, baseline: &ProfileBaseline) -> Result<(), String> {
+    store::write_json(paths, PROFILE_BASELINE_FILE, baseline)
+}
+
+pub fn render_markdown(comparison: &ProfileComparison) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Profile comparison: {}\n",
+        comparison.verdict.to_uppercase()
+    );
+    let _ = writeln!(out, "- Baseline: `{}`", comparison.baseline_source);
+    let _ = writeln!(out, "- Current: `{}`", comparison.current_source);
+    let _ = writeln!(out, "- Regressions: {}", comparison.regressions);
+    if !comparison.skipped.is_empty() {
+        let _ = writeln!(
+            out,
+            "- Skipped (no data): {}",
+            comparison.skipped.join(", ")
+        );
+    }
+    let _ = writeln!(
+        out,
+        "\n| Check | Baseline | Current | Delta | Tolerance | Result |"
+    );
+    let _ = writeln!(out, "|---|---:|---:|---:|---:|---|");
+    for check in &comparison.checks {
+        let delta = match check.delta_pct {
+            Some(pct) => format!("{:+.2} {} ({pct:+.1}%)", check.delta, check.unit),
+            None => format!("{:+.2} {}", check.delta, check.unit),
+        };
+        let tolerance = match check.tolerance_kind.as_str() {
+            "relative" => format!("+{}%", check.tolerance),
+            _ => format!("+{} {}", check.tolerance, check.unit),
+        };
+        let _ = writeln!(
+            out,
+            "| {} | {:.2} {} | {:.2} {} | {} | {} | {} |",
+            check.label,
+            check.baseline,
+            check.unit,
+            check.current,
+            check.unit,
+            delta,
+            tolerance,
+            if check.passed { "pass" } else { "FAIL" }
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        profile_analysis::{IntervalJitter, ProcessKindReport},
+        test_support,
+    };
+
+    fn metric(path: &str, value: f64) -> MetricReport {
+        MetricReport {
+            metric: path.to_string(),
+            label: path.to_string(),
+            unit: String::new(),
+            samples: 10,
+            min: value,
+            mean: value,
+            p50: value,
+            p90: value,
+            p95: value,
+            p99: value,
+            max: value,
+            above: Vec::new(),
+        }
+    }
+
+    /// Ten flat samples one second apart.
+    fn report(app_mem: f64, app_cpu: f64, webview_mb: f64, jitter_p95: f64) -> ProfileReport {
+        let started_at = test_support::snapshot().timestamp;
+        ProfileReport {
+            source: "profile.jsonl".to_string(),
+            generated_at: started_at,
+            samples: 10,
+            skipped_lines: 0,
+            started_at,
+            ended_at: started_at + chrono::Duration::seconds(9),
+            duration_ms: 9000,
+            median_interval_ms: 1000,
+            interval_jitter: IntervalJitter {
+                mean_ms: 1.0,
+                p95_ms: jitter_p95,
+                max_ms: jitter_p95,
+            },
+            refresh_rates: Vec::new(),
+            gaps: Vec::new(),
+            gap_total_ms: 0,
+            metrics: vec![metric("app.mem", app_mem), metric("app.cpu", app_cpu)],
+            process_kinds: vec![ProcessKindReport {
+                kind: "webview".to_string(),
+                processes: 2,
+                records: 20,
+                peak_instances: 2,
+                peak_total_memory_mb: webview_mb,
+                total_memory_slope_mb_per_min: None,
+                top_processes: Vec::new(),
+            }],
+        }
+    }
+
+    fn baseline() -> ProfileReport {
+        report(200.0, 1.0, 300.0, 20.0)
+    }
+
+    #[test]
+    fn improvements_pass_every_check() {
+        let better = compare_reports(
+            &baseline(),
+            &report(150.0, 0.5, 250.0, 5.0),
+            &RegressionTolerances::default(),
+        );
+        assert_eq!(better.verdict, "pass");
+        assert_eq!(better.checks.len(), 8);
+        assert!(better.skipped.is_empty());
+    }
+
+    #[test]
+    fn verdict_fails_only_on_rises_beyond_tolerance() {
+        let worse = compare_reports(
+            &baseline(),
+            &report(230.0, 1.5, 330.0, 90.0),
+            &RegressionTolerances::default(),
+        );
+        assert_eq!(worse.verdict, "fail");
+        let failed: Vec<&str> = worse
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.id.as_str())
+            .collect();
+        assert_eq!(
+            failed,
+            vec!["app.mem.p50", "app.mem.p95", "app.mem.p99", "jitter.p95"]
+        );
+    }
+}
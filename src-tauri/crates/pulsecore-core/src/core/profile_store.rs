@@ -0,0 +1,536 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
+
+use crate::{core::profile::ProfileSample, types::ProfileStorageOptions};
+
+const MIN_SEGMENT_BYTES: u64 = 1024 * 1024;
+/// A total cap holds at least this many segments, so rotation has something to delete.
+const MIN_SEGMENTS_UNDER_CAP: u64 = 4;
+/// Samples are flushed at most this often; a crash loses no more than this much data.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Validates storage options; a size cap without rotation gets a segment size derived from it.
+pub fn normalize_storage_options(
+    input: ProfileStorageOptions,
+) -> Result<ProfileStorageOptions, String> {
+    let compression = input.compression.trim().to_ascii_lowercase();
+    if !matches!(compression.as_str(), "gzip" | "none") {
+        return Err(format!(
+            "unknown profile compression: {} (expected gzip or none)",
+            input.compression
+        ));
+    }
+    let max_total_bytes = match input.max_total_bytes {
+        0 => 0,
+        value => value.max(MIN_SEGMENT_BYTES * MIN_SEGMENTS_UNDER_CAP),
+    };
+    let mut segment_max_bytes = match input.segment_max_bytes {
+        0 => 0,
+        value => value.max(MIN_SEGMENT_BYTES),
+    };
+    if max_total_bytes > 0 {
+        let largest = max_total_bytes / MIN_SEGMENTS_UNDER_CAP;
+        if segment_max_bytes == 0 || segment_max_bytes > largest {
+            segment_max_bytes = largest;
+        }
+    }
+    Ok(ProfileStorageOptions {
+        compression,
+        segment_max_bytes,
+        segment_max_minutes: input.segment_max_minutes,
+        max_total_bytes,
+    })
+}
+
+/// `profile-x.jsonl`, `profile-x.jsonl.gz` and `profile-x.003.jsonl.gz` all share the
+/// capture name `profile-x`.
+pub fn capture_name(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "profile".to_string());
+    for suffix in [".jsonl.gz", ".jsonl", ".gz"] {
+        if let Some(stem) = name.strip_suffix(suffix) {
+            let base = stem
+                .rsplit_once('.')
+                .filter(|(_, index)| {
+                    !index.is_empty() && index.chars().all(|ch| ch.is_ascii_digit())
+                })
+                .map_or(stem, |(base, _)| base);
+            return base.to_string();
+        }
+    }
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or(name)
+}
+
+/// Orders a file among the segments of `name`: the unnumbered file first, then `.001`, ...
+fn segment_index(file_name: &str, name: &str) -> Option<u32> {
+    let rest = file_name.strip_prefix(name)?;
+    let rest = rest
+        .strip_suffix(".jsonl.gz")
+        .or_else(|| rest.strip_suffix(".jsonl"))?;
+    if rest.is_empty() {
+        return Some(0);
+    }
+    let digits = rest.strip_prefix('.')?;
+    if digits.is_empty() || !digits.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Files holding a capture, oldest first. An existing unnumbered file is read on its own;
+/// a capture path or any numbered segment brings in every segment from the same folder.
+pub fn profile_segments(path: &Path) -> Vec<PathBuf> {
+    let name = capture_name(path);
+    let numbered = path
+        .file_name()
+        .and_then(|file_name| segment_index(&file_name.to_string_lossy(), &name))
+        .is_some_and(|index| index > 0);
+    if path.is_file() && !numbered {
+        return vec![path.to_path_buf()];
+    }
+    let Ok(entries) = fs::read_dir(capture_dir(path)) else {
+        return Vec::new();
+    };
+    let mut segments: Vec<(u32, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let index = segment_index(&file_name, &name)?;
+            Some((index, entry.path()))
+        })
+        .collect();
+    segments.sort();
+    segments.into_iter().map(|(_, path)| path).collect()
+}
+
+fn capture_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Whether two paths name the same capture, i.e. their writers would share segment files.
+pub fn same_capture(a: &Path, b: &Path) -> bool {
+    capture_name(a) == capture_name(b) && capture_dir(a) == capture_dir(b)
+}
+
+/// Deletes the files an earlier capture under `path`'s name left behind, so readers do not
+/// stitch its segments onto the new ones.
+fn remove_stale_segments(path: &Path) -> Result<(), String> {
+    let name = capture_name(path);
+    let Ok(entries) = fs::read_dir(capture_dir(path)) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if segment_index(&file_name, &name).is_some() {
+            let stale = entry.path();
+            fs::remove_file(&stale).map_err(|e| format!("{}: {e}", stale.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Opens one segment, decompressing gzip (detected by its magic bytes) on the fly.
+pub fn open_segment(path: &Path) -> Result<Box<dyn BufRead + Send>, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let head = reader
+        .fill_buf()
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    if head.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Counts bytes that reach the file, i.e. after compression.
+struct CountingWriter<W> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+enum SegmentSink {
+    Plain(CountingWriter<BufWriter<File>>),
+    Gzip(GzEncoder<CountingWriter<BufWriter<File>>>),
+}
+
+struct Segment {
+    path: PathBuf,
+    sink: SegmentSink,
+    opened_at: Instant,
+}
+
+impl Segment {
+    fn create(path: PathBuf, gzip: bool) -> io::Result<Self> {
+        let file = CountingWriter {
+            inner: BufWriter::new(File::create(&path)?),
+            written: 0,
+        };
+        let sink = if gzip {
+            SegmentSink::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            SegmentSink::Plain(file)
+        };
+        Ok(Self {
+            path,
+            sink,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match &mut self.sink {
+            SegmentSink::Plain(writer) => writer,
+            SegmentSink::Gzip(writer) => writer,
+        }
+    }
+
+    fn bytes(&self) -> u64 {
+        match &self.sink {
+            SegmentSink::Plain(writer) => writer.written,
+            SegmentSink::Gzip(writer) => writer.get_ref().written,
+        }
+    }
+
+    /// Writes the gzip trailer and flushes; returns the segment size on disk.
+    fn finish(self) -> io::Result<u64> {
+        let mut file = match self.sink {
+            SegmentSink::Plain(writer) => writer,
+            SegmentSink::Gzip(writer) => writer.finish()?,
+        };
+        file.flush()?;
+        Ok(file.written)
+    }
+}
+
+/// Writes a capture as JSON lines, optionally gzip-compressed and split into numbered
+/// segments (`profile-x.001.jsonl.gz`, ...), deleting the oldest segments to stay under
+/// the total size cap. Readers find all segments from the capture path.
+pub struct ProfileWriter {
+    dir: PathBuf,
+    name: String,
+    options: ProfileStorageOptions,
+    current: Option<Segment>,
+    first_path: PathBuf,
+    /// Finished segments still on disk, oldest first, with their sizes.
+    closed: VecDeque<(PathBuf, u64)>,
+    next_index: u32,
+    deleted_segments: u32,
+    last_flush: Instant,
+}
+
+impl ProfileWriter {
+    /// `path` is the capture path as resolved by `ensure_profile_path`.
+    pub fn create(path: &Path, options: ProfileStorageOptions) -> Result<Self, String> {
+        let options = normalize_storage_options(options)?;
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        }
+        remove_stale_segments(path)?;
+        let mut writer = Self {
+            dir,
+            name: capture_name(path),
+            options,
+            current: None,
+            first_path: PathBuf::new(),
+            closed: VecDeque::new(),
+            next_index: 1,
+            deleted_segments: 0,
+            last_flush: Instant::now(),
+        };
+        writer.open_segment()?;
+        writer.first_path = writer.segment_path(1);
+        Ok(writer)
+    }
+
+    /// The first file written, e.g. `profile-x.001.jsonl.gz`; readers take it in place of
+    /// the capture path.
+    pub fn path(&self) -> &Path {
+        &self.first_path
+    }
+
+    fn rotating(&self) -> bool {
+        self.options.segment_max_bytes > 0 || self.options.segment_max_minutes > 0
+    }
+
+    fn gzip(&self) -> bool {
+        self.options.compression == "gzip"
+    }
+
+    fn segment_path(&self, index: u32) -> PathBuf {
+        let extension = if self.gzip() { "jsonl.gz" } else { "jsonl" };
+        let file_name = if self.rotating() {
+            format!("{}.{index:03}.{extension}", self.name)
+        } else {
+            format!("{}.{extension}", self.name)
+        };
+        self.dir.join(file_name)
+    }
+
+    fn open_segment(&mut self) -> Result<(), String> {
+        let path = self.segment_path(self.next_index);
+        self.next_index += 1;
+        let segment = Segment::create(path.clone(), self.gzip())
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        self.current = Some(segment);
+        Ok(())
+    }
+
+    fn rotation_due(&self, segment: &Segment) -> bool {
+        let by_size =
+            self.options.segment_max_bytes > 0 && segment.bytes() >= self.options.segment_max_bytes;
+        let by_time = self.options.segment_max_minutes > 0
+            && segment.opened_at.elapsed()
+                >= Duration::from_secs(self.options.segment_max_minutes * 60);
+        by_size || by_time
+    }
+
+    fn close_segment(&mut self) -> Result<(), String> {
+        if let Some(segment) = self.current.take() {
+            let path = segment.path.clone();
+            let bytes = segment
+                .finish()
+                .map_err(|e| format!("{}: {e}", path.display()))?;
+            self.closed.push_back((path, bytes));
+        }
+        Ok(())
+    }
+
+    /// Drops the oldest segments until the closed ones plus a full new one fit the cap.
+    fn enforce_cap(&mut self) {
+        if self.options.max_total_bytes == 0 {
+            return;
+        }
+        let budget = self
+            .options
+            .max_total_bytes
+            .saturating_sub(self.options.segment_max_bytes);
+        while self.closed.iter().map(|(_, bytes)| bytes).sum::<u64>() > budget {
+            let Some((path, _)) = self.closed.pop_front() else {
+                break;
+            };
+            if let Err(err) = fs::remove_file(&path) {
+                tracing::warn!("failed to delete profile segment {}: {err}", path.display());
+            }
+            self.deleted_segments += 1;
+        }
+    }
+
+    pub fn write_sample(&mut self, sample: &ProfileSample) -> Result<(), String> {
+        let line = serde_json::to_string(sample).map_err(|e| e.to_string())?;
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|segment| self.rotation_due(segment))
+        {
+            self.close_segment()?;
+            self.enforce_cap();
+            self.open_segment()?;
+        }
+        let segment = self
+            .current
+            .as_mut()
+            .ok_or_else(|| "profile writer is closed".to_string())?;
+        let writer = segment.writer();
+        writer
+            .write_all(line.as_bytes())
+            .and_then(|_| writer.write_all(b"\n"))
+            .map_err(|e| e.to_string())?;
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.last_flush = Instant::now();
+        match self.current.as_mut() {
+            Some(segment) => segment.writer().flush().map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
+    /// Bytes on disk across the segments that are still kept.
+    pub fn bytes_written(&self) -> u64 {
+        let closed: u64 = self.closed.iter().map(|(_, bytes)| bytes).sum();
+        closed + self.current.as_ref().map_or(0, Segment::bytes)
+    }
+
+    /// Segments written so far, including deleted ones.
+    pub fn segments_written(&self) -> u32 {
+        self.next_index - 1
+    }
+
+    pub fn deleted_segments(&self) -> u32 {
+        self.deleted_segments
+    }
+
+    /// Finishes the last segment; the writer stays usable only for the counters.
+    pub fn close(&mut self) -> Result<(), String> {
+        self.close_segment()?;
+        self.enforce_cap();
+        Ok(())
+    }
+}
+
+impl Drop for ProfileWriter {
+    fn drop(&mut self) {
+        let _ = self.close_segment();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{profile::ProfileReader, test_support};
+
+    /// Samples carry their index as the refresh rate so order can be checked on read-back.
+    fn sample(index: u64) -> ProfileSample {
+        let mut sample = test_support::profile_sample(test_support::snapshot());
+        sample.refresh_rate_ms = index;
+        sample
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn segments_share_the_capture_name() {
+        for path in [
+            "/tmp/profile-x.jsonl",
+            "/tmp/profile-x.jsonl.gz",
+            "/tmp/profile-x.003.jsonl.gz",
+            "/tmp/profile-x.1000.jsonl",
+        ] {
+            assert_eq!(capture_name(Path::new(path)), "profile-x", "{path}");
+        }
+        assert_eq!(capture_name(Path::new("/tmp/run.v2.jsonl")), "run.v2");
+    }
+
+    #[test]
+    fn gzip_capture_reads_back_transparently() {
+        let dir = temp_dir("pulsecore-profile-gzip");
+        let path = dir.join("profile-a.jsonl");
+        let mut writer = ProfileWriter::create(
+            &path,
+            ProfileStorageOptions {
+                segment_max_bytes: 0,
+                max_total_bytes: 0,
+                ..ProfileStorageOptions::default()
+            },
+        )
+        .unwrap();
+        for index in 0..50 {
+            writer.write_sample(&sample(index)).unwrap();
+        }
+        writer.close().unwrap();
+
+        assert!(dir.join("profile-a.jsonl.gz").is_file());
+        let rates: Vec<u64> = ProfileReader::open(&path)
+            .unwrap()
+            .map(|sample| sample.unwrap().refresh_rate_ms)
+            .collect();
+        assert_eq!(rates, (0..50).collect::<Vec<_>>());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_new_capture_replaces_stale_segments() {
+        let dir = temp_dir("pulsecore-profile-stale");
+        let path = dir.join("profile-c.jsonl");
+        for stale in [
+            "profile-c.jsonl",
+            "profile-c.001.jsonl.gz",
+            "profile-c.007.jsonl.gz",
+        ] {
+            fs::write(dir.join(stale), "stale\n").unwrap();
+        }
+        fs::write(dir.join("profile-cd.001.jsonl.gz"), "other capture\n").unwrap();
+
+        let mut writer = ProfileWriter::create(&path, ProfileStorageOptions::default()).unwrap();
+        writer.write_sample(&sample(1)).unwrap();
+        writer.close().unwrap();
+
+        assert_eq!(writer.path(), dir.join("profile-c.001.jsonl.gz"));
+        assert_eq!(profile_segments(&path), vec![writer.path().to_path_buf()]);
+        assert_eq!(
+            profile_segments(writer.path()),
+            vec![writer.path().to_path_buf()]
+        );
+        assert!(dir.join("profile-cd.001.jsonl.gz").is_file());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotation_numbers_segments_and_caps_total_size() {
+        let dir = temp_dir("pulsecore-profile-rotate");
+        let path = dir.join("profile-b.jsonl");
+        let options = ProfileStorageOptions {
+            compression: "none".to_string(),
+            segment_max_bytes: MIN_SEGMENT_BYTES,
+            segment_max_minutes: 0,
+            max_total_bytes: MIN_SEGMENT_BYTES * MIN_SEGMENTS_UNDER_CAP,
+        };
+        let mut writer = ProfileWriter::create(&path, options).unwrap();
+        let line_bytes = serde_json::to_string(&sample(0)).unwrap().len() as u64 + 1;
+        let total = MIN_SEGMENT_BYTES * 7 / line_bytes;
+        for index in 0..total {
+            writer.write_sample(&sample(index)).unwrap();
+        }
+        writer.close().unwrap();
+
+        assert!(writer.segments_written() >= 7);
+        assert!(writer.deleted_segments() > 0);
+        assert!(writer.bytes_written() <= MIN_SEGMENT_BYTES * MIN_SEGMENTS_UNDER_CAP);
+        let segments = profile_segments(&path);
+        assert_eq!(
+            segments.len() as u32,
+            writer.segments_written() - writer.deleted_segments()
+        );
+        assert!(segments[0]
+            .to_string_lossy()
+            .ends_with(&format!(".{:03}.jsonl", writer.deleted_segments() + 1)));
+        assert_eq!(profile_segments(&segments[1]), segments);
+
+        // The newest samples survive, in order.
+        let rates: Vec<u64> = ProfileReader::open(&path)
+            .unwrap()
+            .map(|sample| sample.unwrap().refresh_rate_ms)
+            .collect();
+        assert_eq!(rates.last(), Some(&(total - 1)));
+        assert!(rates.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
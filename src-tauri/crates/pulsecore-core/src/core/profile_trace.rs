@@ -0,0 +1,325 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::core::{
+    metric_path::MetricPath,
+    profile::{ProfileReader, ProfileSample},
+    profile_store::capture_name,
+};
+
+/// Trace pid of the machine-wide counter tracks and window events; pid 0 is a real process
+/// (System Idle) on Windows.
+const SYSTEM_PID: u32 = u32::MAX;
+
+/// A counter track and the metric paths plotted on it, each with a scale to the track's unit.
+struct CounterSpec {
+    name: &'static str,
+    series: &'static [(&'static str, &'static str, f64)],
+}
+
+const SYSTEM_COUNTERS: &[CounterSpec] = &[
+    CounterSpec {
+        name: "CPU usage (%)",
+        series: &[("usage", "cpu.usage", 1.0)],
+    },
+    CounterSpec {
+        name: "GPU usage (%)",
+        series: &[("usage", "gpu.usage", 1.0)],
+    },
+    CounterSpec {
+        name: "Temperature (°C)",
+        series: &[("cpu", "cpu.temp", 1.0), ("gpu", "gpu.temp", 1.0)],
+    },
+    CounterSpec {
+        name: "Memory used (MB)",
+        series: &[("used", "mem.used", 1.0)],
+    },
+    CounterSpec {
+        name: "GPU memory used (MB)",
+        series: &[("used", "gpu.vram", 1.0)],
+    },
+    CounterSpec {
+        name: "Network (KB/s)",
+        series: &[
+            ("down", "net.down", 1.0 / 1024.0),
+            ("up", "net.up", 1.0 / 1024.0),
+        ],
+    },
+    CounterSpec {
+        name: "App CPU (%)",
+        series: &[("usage", "app.cpu", 1.0)],
+    },
+    CounterSpec {
+        name: "App memory (MB)",
+        series: &[("used", "app.mem", 1.0)],
+    },
+    CounterSpec {
+        name: "Power (W)",
+        series: &[("power", "power", 1.0)],
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceExport {
+    pub trace_path: String,
+    pub samples: u64,
+    pub events: u64,
+    pub skipped_lines: u64,
+}
+
+/// `profile-x.jsonl[.gz]` -> `profile-x.trace.json` in the same folder.
+pub fn trace_path(profile: &Path) -> PathBuf {
+    profile.with_file_name(format!("{}.trace.json", capture_name(profile)))
+}
+
+/// Streams Chrome Trace Event JSON, which both `chrome://tracing` and ui.perfetto.dev open.
+struct TraceWriter<W: Write> {
+    out: W,
+    events: u64,
+}
+
+impl<W: Write> TraceWriter<W> {
+    fn new(mut out: W) -> Result<Self, String> {
+        out.write_all(b"{\"displayTimeUnit\":\"ms\",\"traceEvents\":[\n")
+            .map_err(|e| e.to_string())?;
+        Ok(Self { out, events: 0 })
+    }
+
+    fn event(&mut self, event: Value) -> Result<(), String> {
+        if self.events > 0 {
+            self.out.write_all(b",\n").map_err(|e| e.to_string())?;
+        }
+        serde_json::to_writer(&mut self.out, &event).map_err(|e| e.to_string())?;
+        self.events += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<u64, String> {
+        self.out.write_all(b"\n]}\n").map_err(|e| e.to_string())?;
+        self.out.flush().map_err(|e| e.to_string())?;
+        Ok(self.events)
+    }
+}
+
+/// A counter track's series as (arg key, metric, scale).
+type CounterSeries = Vec<(&'static str, MetricPath, f64)>;
+
+/// Turns samples into trace events: system counter tracks, a memory and CPU track per
+/// process, and an instant event whenever a window is shown or hidden.
+struct TraceBuilder {
+    counters: Vec<(&'static CounterSpec, CounterSeries)>,
+    started_at: Option<DateTime<Utc>>,
+    named_pids: HashSet<u32>,
+    windows: HashMap<String, bool>,
+}
+
+impl TraceBuilder {
+    fn new() -> Self {
+        let counters = SYSTEM_COUNTERS
+            .iter()
+            .map(|spec| {
+                let series = spec
+                    .series
+                    .iter()
+                    .filter_map(|(key, path, scale)| {
+                        MetricPath::parse(path)
+                            .ok()
+                            .map(|path| (*key, path, *scale))
+                    })
+                    .collect();
+                (spec, series)
+            })
+            .collect();
+        Self {
+            counters,
+            started_at: None,
+            named_pids: HashSet::new(),
+            windows: HashMap::new(),
+        }
+    }
+
+    fn header(&mut self) -> Vec<Value> {
+        self.named_pids.insert(SYSTEM_PID);
+        vec![process_name(SYSTEM_PID, "System")]
+    }
+
+    fn push(&mut self, sample: &ProfileSample) -> Vec<Value> {
+        let started_at = *self.started_at.get_or_insert(sample.timestamp);
+        let ts = (sample.timestamp - started_at)
+            .num_microseconds()
+            .unwrap_or(0)
+            .max(0);
+        let mut events = Vec::new();
+
+        for (spec, series) in &self.counters {
+            let args: serde_json::Map<String, Value> = series
+                .iter()
+                .filter_map(|(key, path, scale)| {
+                    path.value(&sample.snapshot)
+                        .map(|value| (key.to_string(), json!(value * scale)))
+                })
+                .collect();
+            if !args.is_empty() {
+                events.push(counter(SYSTEM_PID, spec.name, ts, Value::Object(args)));
+            }
+        }
+
+        for process in &sample.processes {
+            if self.named_pids.insert(process.pid) {
+                let label = if process.kind.is_empty() {
+                    process.name.clone()
+                } else {
+                    format!("{} ({})", process.name, process.kind)
+                };
+                events.push(process_name(process.pid, &label));
+            }
+            events.push(counter(
+                process.pid,
+                "Memory (MB)",
+                ts,
+                json!({ "memory": process.memory_mb }),
+            ));
+            events.push(counter(
+                process.pid,
+                "CPU (%)",
+                ts,
+                json!({ "cpu": process.cpu_pct }),
+            ));
+        }
+
+        for window in &sample.windows {
+            let previous = self.windows.insert(window.label.clone(), window.visible);
+            if previous == Some(window.visible) {
+                continue;
+            }
+            let action = if window.visible { "shown" } else { "hidden" };
+            events.push(json!({
+                "name": format!("{} {action}", window.label),
+                "cat": "window",
+                "ph": "i",
+                "s": "g",
+                "ts": ts,
+                "pid": SYSTEM_PID,
+                "tid": 0,
+                "args": { "label": window.label, "visible": window.visible },
+            }));
+        }
+        events
+    }
+}
+
+fn process_name(pid: u32, name: &str) -> Value {
+    json!({
+        "name": "process_name",
+        "ph": "M",
+        "pid": pid,
+        "tid": 0,
+        "args": { "name": name },
+    })
+}
+
+fn counter(pid: u32, name: &str, ts: i64, args: Value) -> Value {
+    json!({ "name": name, "ph": "C", "ts": ts, "pid": pid, "tid": 0, "args": args })
+}
+
+/// Converts a capture to Chrome Trace Event JSON at `output` (default: next to the profile).
+pub fn export_trace(profile: &Path, output: Option<&Path>) -> Result<TraceExport, String> {
+    let mut reader = ProfileReader::open(profile)?;
+    let output = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| trace_path(profile));
+    let file = File::create(&output).map_err(|e| format!("{}: {e}", output.display()))?;
+    let mut writer = TraceWriter::new(BufWriter::new(file))?;
+    let mut builder = TraceBuilder::new();
+    for event in builder.header() {
+        writer.event(event)?;
+    }
+    let mut samples = 0u64;
+    for sample in reader.by_ref() {
+        for event in builder.push(&sample?) {
+            writer.event(event)?;
+        }
+        samples += 1;
+    }
+    let events = writer.finish()?;
+    Ok(TraceExport {
+        trace_path: output.to_string_lossy().to_string(),
+        samples,
+        events,
+        skipped_lines: reader.skipped_lines(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{profile::WindowSample, test_support};
+
+    fn sample(second: i64, main_visible: bool) -> ProfileSample {
+        let mut snapshot = test_support::snapshot_at(second, 12.5);
+        snapshot.network.download_bytes_per_sec = 2048.0;
+        snapshot.network.upload_bytes_per_sec = 1024.0;
+        let mut sample = test_support::profile_sample(snapshot);
+        sample.app_pid = 42;
+        let mut app = test_support::process(42, "pulsecore.exe", 80.0);
+        app.parent_pid = None;
+        sample.processes.push(app);
+        sample.windows.push(WindowSample {
+            label: "main".to_string(),
+            visible: main_visible,
+        });
+        sample
+    }
+
+    fn trace() -> Vec<Value> {
+        let mut builder = TraceBuilder::new();
+        let mut events = builder.header();
+        for (second, visible) in [(0, true), (1, true), (2, false)] {
+            events.extend(builder.push(&sample(second, visible)));
+        }
+        events
+    }
+
+    fn count(events: &[Value], ph: &str, name: &str) -> usize {
+        events
+            .iter()
+            .filter(|event| event["ph"] == ph && event["name"] == name)
+            .count()
+    }
+
+    #[test]
+    fn builds_counter_tracks_for_reported_metrics() {
+        let events = trace();
+        assert_eq!(count(&events, "M", "process_name"), 2);
+        assert_eq!(count(&events, "C", "CPU usage (%)"), 3);
+        assert_eq!(count(&events, "C", "Memory (MB)"), 3);
+        assert_eq!(count(&events, "C", "GPU usage (%)"), 0);
+    }
+
+    #[test]
+    fn window_visibility_changes_become_instants() {
+        let events = trace();
+        assert_eq!(count(&events, "i", "main shown"), 1);
+        assert_eq!(count(&events, "i", "main hidden"), 1);
+    }
+
+    #[test]
+    fn network_counters_are_in_kilobytes() {
+        let events = trace();
+        let network = events
+            .iter()
+            .find(|event| event["name"] == "Network (KB/s)" && event["ts"] == 2_000_000)
+            .unwrap();
+        assert_eq!(network["args"]["down"], 2.0);
+        assert_eq!(network["args"]["up"], 1.0);
+    }
+}
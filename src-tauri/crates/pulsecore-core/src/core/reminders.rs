@@ -0,0 +1,428 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+
+use crate::{
+    core::{
+        events::CoreEvent,
+        ports::{CoreContext, Notification},
+    },
+    types::{ReminderScreenEventPayload, SmtpEmailConfig, TaskReminder},
+};
+
+/// A reminder slot that came due on this tick, keyed so it fires once per day.
+#[derive(Debug, Clone)]
+pub struct DueReminder {
+    pub reminder: TaskReminder,
+    pub fire_key: String,
+}
+
+/// Returns the enabled reminders due at `now` and records them in `fired`. Keys from
+/// previous days are pruned, so `fired` stays small.
+pub fn take_due_reminders(
+    reminders: &[TaskReminder],
+    now: DateTime<Local>,
+    fired: &mut HashMap<String, String>,
+) -> Vec<DueReminder> {
+    let current_time = format!("{:02}:{:02}", now.hour(), now.minute());
+    let weekday = now.weekday().number_from_monday() as u8;
+    let day_of_month = now.day() as u8;
+    let marker = format!("{:04}-{:02}-{:02}", now.year(), now.month(), now.day());
+
+    let suffix = format!("@{marker}");
+    fired.retain(|key, _| key.ends_with(&suffix));
+
+    let mut due = Vec::new();
+    for reminder in reminders.iter().filter(|reminder| reminder.enabled) {
+        for slot in collect_due_slots(reminder, &current_time, weekday, day_of_month, &marker) {
+            let fire_key = format!("{}|{}", reminder.id, slot);
+            if fired.contains_key(&fire_key) {
+                continue;
+            }
+            fired.insert(fire_key.clone(), now.to_rfc3339());
+            due.push(DueReminder {
+                reminder: reminder.clone(),
+                fire_key,
+            });
+        }
+    }
+    due
+}
+
+fn collect_due_slots(
+    reminder: &TaskReminder,
+    current_time: &str,
+    weekday: u8,
+    day_of_month: u8,
+    marker: &str,
+) -> Vec<String> {
+    let mut due = Vec::new();
+
+    for time in &reminder.daily_times {
+        if time == current_time {
+            due.push(format!("daily@{time}@{marker}"));
+        }
+    }
+    for slot in &reminder.weekly_slots {
+        if slot.weekday == weekday && slot.time == current_time {
+            due.push(format!("weekly@{}-{}@{marker}", slot.weekday, slot.time));
+        }
+    }
+    for slot in &reminder.monthly_slots {
+        if slot.day == day_of_month && slot.time == current_time {
+            due.push(format!("monthly@{}-{}@{marker}", slot.day, slot.time));
+        }
+    }
+
+    due
+}
+
+/// Delivers a reminder on its channel: full-screen reminders become an event, webhook and
+/// email reminders go through the notifier.
+pub async fn trigger_reminder(
+    ctx: &CoreContext,
+    smtp_config: Option<SmtpEmailConfig>,
+    reminder: &TaskReminder,
+) -> Result<(), String> {
+    if reminder.channel.eq_ignore_ascii_case("fullscreen") {
+        ctx.events
+            .publish(CoreEvent::ReminderScreen(ReminderScreenEventPayload {
+                title: reminder.title.clone(),
+                content: reminder.content.clone(),
+                content_type: reminder.content_type.clone(),
+                advanced_settings: reminder.advanced_settings.clone(),
+            }));
+        return Ok(());
+    }
+    if reminder.channel.eq_ignore_ascii_case("webhook") {
+        return ctx
+            .notifier
+            .notify(Notification::Webhook {
+                endpoint_id: reminder.webhook_id.clone(),
+                event: "reminder".to_string(),
+                title: reminder.title.clone(),
+                text: reminder_plain_body(reminder),
+            })
+            .await;
+    }
+    let smtp = smtp_config.ok_or_else(|| "SMTP config is required.".to_string())?;
+    let to = if reminder.email.trim().is_empty() {
+        smtp.from_email.trim().to_string()
+    } else {
+        reminder.email.trim().to_string()
+    };
+    if to.is_empty() {
+        return Err("recipient email is empty; configure SMTP from email first".to_string());
+    }
+    ctx.notifier
+        .notify(Notification::Email {
+            smtp,
+            to,
+            subject: format!("[PulseCore] {}", reminder.title),
+            text: reminder_plain_body(reminder),
+            html: reminder_html_body(reminder),
+        })
+        .await
+}
+
+pub fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn html_with_line_breaks(value: &str) -> String {
+    html_escape(value).replace('\n', "<br />")
+}
+
+fn is_http_url(value: &str) -> bool {
+    let normalized = value.trim().to_ascii_lowercase();
+    normalized.starts_with("https://") || normalized.starts_with("http://")
+}
+
+fn is_supported_image_src(value: &str) -> bool {
+    let normalized = value.trim().to_ascii_lowercase();
+    is_http_url(&normalized) || normalized.starts_with("data:image/")
+}
+
+fn looks_like_html_content(value: &str) -> bool {
+    let normalized = value.trim_start().to_ascii_lowercase();
+    normalized.starts_with("<!doctype")
+        || normalized.starts_with("<html")
+        || (normalized.contains('<') && normalized.contains('>'))
+}
+
+pub fn reminder_plain_body(reminder: &TaskReminder) -> String {
+    let content = reminder.content.trim();
+    if content.is_empty() {
+        return reminder.title.clone();
+    }
+
+    match reminder.content_type.as_str() {
+        "web" => format!("{}\n\nOpen web page:\n{content}", reminder.title),
+        "image" => format!("{}\n\nImage:\n{content}", reminder.title),
+        _ => reminder.content.clone(),
+    }
+}
+
+pub fn reminder_email_shell(title: &str, inner_html: String) -> String {
+    format!(
+        r#"<!doctype html>
+<html>
+  <body style="margin:0;padding:24px;background:#f6f8fb;color:#172033;font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Arial,sans-serif;">
+    <main style="max-width:720px;margin:0 auto;background:#ffffff;border:1px solid #dde5f0;border-radius:16px;padding:24px;">
+      <h1 style="margin:0 0 18px;font-size:22px;line-height:1.35;color:#101827;">{}</h1>
+      {}
+    </main>
+  </body>
+</html>"#,
+        html_escape(title),
+        inner_html
+    )
+}
+
+fn simple_markdown_to_email_html(value: &str) -> String {
+    let normalized = value.replace("\r\n", "\n").replace('\r', "\n");
+    let mut html = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut list_open = false;
+    let mut code_open = false;
+    let mut code_lines: Vec<String> = Vec::new();
+
+    let flush_paragraph = |html: &mut String, paragraph: &mut Vec<String>| {
+        if paragraph.is_empty() {
+            return;
+        }
+        html.push_str("<p style=\"margin:0 0 14px;line-height:1.65;\">");
+        html.push_str(&html_with_line_breaks(&paragraph.join("\n")));
+        html.push_str("</p>");
+        paragraph.clear();
+    };
+    let close_list = |html: &mut String, list_open: &mut bool| {
+        if *list_open {
+            html.push_str("</ul>");
+            *list_open = false;
+        }
+    };
+
+    for line in normalized.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            if code_open {
+                html.push_str("<pre style=\"margin:0 0 14px;padding:12px;border-radius:10px;background:#101827;color:#e8eef7;overflow:auto;\"><code>");
+                html.push_str(&html_escape(&code_lines.join("\n")));
+                html.push_str("</code></pre>");
+                code_lines.clear();
+                code_open = false;
+            } else {
+                flush_paragraph(&mut html, &mut paragraph);
+                close_list(&mut html, &mut list_open);
+                code_open = true;
+            }
+            continue;
+        }
+        if code_open {
+            code_lines.push(line.to_string());
+            continue;
+        }
+        if trimmed.is_empty() {
+            flush_paragraph(&mut html, &mut paragraph);
+            close_list(&mut html, &mut list_open);
+            continue;
+        }
+        if let Some(text) = trimmed.strip_prefix("# ") {
+            flush_paragraph(&mut html, &mut paragraph);
+            close_list(&mut html, &mut list_open);
+            html.push_str("<h2 style=\"margin:0 0 12px;font-size:20px;line-height:1.35;\">");
+            html.push_str(&html_escape(text));
+            html.push_str("</h2>");
+            continue;
+        }
+        if let Some(text) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            flush_paragraph(&mut html, &mut paragraph);
+            if !list_open {
+                html.push_str("<ul style=\"margin:0 0 14px;padding-left:22px;line-height:1.65;\">");
+                list_open = true;
+            }
+            html.push_str("<li>");
+            html.push_str(&html_with_line_breaks(text));
+            html.push_str("</li>");
+            continue;
+        }
+        paragraph.push(line.to_string());
+    }
+
+    if code_open {
+        html.push_str("<pre style=\"margin:0 0 14px;padding:12px;border-radius:10px;background:#101827;color:#e8eef7;overflow:auto;\"><code>");
+        html.push_str(&html_escape(&code_lines.join("\n")));
+        html.push_str("</code></pre>");
+    }
+    flush_paragraph(&mut html, &mut paragraph);
+    close_list(&mut html, &mut list_open);
+
+    if html.is_empty() {
+        "<p style=\"margin:0;line-height:1.65;\">No content.</p>".to_string()
+    } else {
+        html
+    }
+}
+
+pub fn reminder_html_body(reminder: &TaskReminder) -> Option<String> {
+    let content = reminder.content.trim();
+    match reminder.content_type.as_str() {
+        "markdown" => Some(reminder_email_shell(
+            &reminder.title,
+            simple_markdown_to_email_html(content),
+        )),
+        "web" => {
+            if looks_like_html_content(content) {
+                return Some(if content.to_ascii_lowercase().contains("<html") {
+                    content.to_string()
+                } else {
+                    reminder_email_shell(&reminder.title, content.to_string())
+                });
+            }
+
+            let inner = if is_http_url(content) {
+                let safe_url = html_escape(content);
+                format!(
+                    r#"<p style="margin:0 0 14px;line-height:1.65;">Most email clients block embedded web pages, so this reminder is sent as a safe link card.</p>
+<p style="margin:0 0 18px;"><a href="{0}" style="display:inline-block;padding:11px 16px;border-radius:10px;background:#2563eb;color:#ffffff;text-decoration:none;font-weight:600;">Open web page</a></p>
+<p style="margin:0;color:#526070;line-height:1.6;word-break:break-all;">{0}</p>"#,
+                    safe_url
+                )
+            } else if content.is_empty() {
+                "<p style=\"margin:0;line-height:1.65;\">No web URL configured.</p>".to_string()
+            } else {
+                format!(
+                    "<p style=\"margin:0;line-height:1.65;word-break:break-all;\">{}</p>",
+                    html_escape(content)
+                )
+            };
+            Some(reminder_email_shell(&reminder.title, inner))
+        }
+        "image" => {
+            let inner = if is_supported_image_src(content) {
+                let safe_src = html_escape(content);
+                format!(
+                    r#"<p style="margin:0 0 14px;line-height:1.65;">Image reminder:</p>
+<img src="{0}" alt="{1}" style="display:block;max-width:100%;height:auto;border-radius:12px;border:1px solid #dde5f0;" />"#,
+                    safe_src,
+                    html_escape(&reminder.title)
+                )
+            } else if content.is_empty() {
+                "<p style=\"margin:0;line-height:1.65;\">No image configured.</p>".to_string()
+            } else {
+                format!(
+                    "<p style=\"margin:0;line-height:1.65;word-break:break-all;\">{}</p>",
+                    html_escape(content)
+                )
+            };
+            Some(reminder_email_shell(&reminder.title, inner))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        core::{
+            events::EventBus,
+            ports::{DirPaths, FixedClock, NoWindows, RecordingNotifier},
+        },
+        types::WeeklyReminderSlot,
+    };
+
+    fn reminder(channel: &str) -> TaskReminder {
+        TaskReminder {
+            id: "r1".to_string(),
+            enabled: true,
+            title: "Stand up".to_string(),
+            channel: channel.to_string(),
+            email: String::new(),
+            webhook_id: "hook".to_string(),
+            daily_times: vec!["09:30".to_string()],
+            weekly_slots: vec![WeeklyReminderSlot {
+                weekday: 1,
+                time: "09:30".to_string(),
+            }],
+            monthly_slots: Vec::new(),
+            content_type: "text".to_string(),
+            content: "Take a break".to_string(),
+            advanced_settings: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn context(notifier: Arc<RecordingNotifier>, events: EventBus) -> CoreContext {
+        CoreContext {
+            clock: Arc::new(FixedClock::new(chrono::Utc::now())),
+            paths: Arc::new(DirPaths(std::env::temp_dir())),
+            notifier,
+            events: Arc::new(events),
+            windows: Arc::new(NoWindows),
+        }
+    }
+
+    #[test]
+    fn due_reminders_fire_once_per_slot_per_day() {
+        let monday = Local.with_ymd_and_hms(2026, 3, 2, 9, 30, 10).unwrap();
+        let reminders = vec![reminder("email")];
+        let mut fired = HashMap::new();
+
+        let due = take_due_reminders(&reminders, monday, &mut fired);
+        assert_eq!(due.len(), 2, "daily and weekly slots both match");
+        assert!(take_due_reminders(&reminders, monday, &mut fired).is_empty());
+
+        let next_monday = Local.with_ymd_and_hms(2026, 3, 9, 9, 30, 0).unwrap();
+        assert_eq!(
+            take_due_reminders(&reminders, next_monday, &mut fired).len(),
+            2
+        );
+        assert_eq!(fired.len(), 2, "previous day keys are pruned");
+
+        let later = Local.with_ymd_and_hms(2026, 3, 9, 9, 31, 0).unwrap();
+        assert!(take_due_reminders(&reminders, later, &mut fired).is_empty());
+    }
+
+    #[tokio::test]
+    async fn reminders_route_through_ports() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let bus = EventBus::default();
+        let mut events = bus.subscribe();
+        let ctx = context(notifier.clone(), bus);
+
+        trigger_reminder(&ctx, None, &reminder("fullscreen"))
+            .await
+            .unwrap();
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.name(), "reminder://trigger");
+        assert_eq!(event.payload()["title"], "Stand up");
+
+        trigger_reminder(&ctx, None, &reminder("webhook"))
+            .await
+            .unwrap();
+        assert!(trigger_reminder(&ctx, None, &reminder("email"))
+            .await
+            .is_err());
+        let sent = notifier.sent();
+        assert_eq!(sent.len(), 1);
+        assert!(matches!(
+            &sent[0],
+            Notification::Webhook { endpoint_id, .. } if endpoint_id == "hook"
+        ));
+    }
+}
@@ -0,0 +1,137 @@
+use std::{path::Path, time::Duration};
+
+use crate::{core::profile::ProfileReader, types::TelemetrySnapshot};
+
+pub const MIN_REPLAY_SPEED: f64 = 0.25;
+pub const MAX_REPLAY_SPEED: f64 = 64.0;
+/// Recorded gaps longer than this (suspended machine, paused capture) are shortened.
+const MAX_REPLAY_GAP_MS: u64 = 10_000;
+
+/// Snapshots of a profile capture with their offsets from the first sample.
+#[derive(Debug, Default)]
+pub struct ReplayTimeline {
+    offsets_ms: Vec<u64>,
+    snapshots: Vec<TelemetrySnapshot>,
+    skipped_lines: u64,
+}
+
+impl ReplayTimeline {
+    /// Loads a `.jsonl` capture, skipping lines that do not parse (e.g. a truncated last line).
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Self::from_reader(ProfileReader::open(path)?)
+    }
+
+    pub fn from_reader(mut reader: ProfileReader) -> Result<Self, String> {
+        let mut samples = reader.by_ref().collect::<Result<Vec<_>, _>>()?;
+        let skipped_lines = reader.skipped_lines();
+        if samples.is_empty() {
+            return Err("profile contains no readable samples".to_string());
+        }
+        samples.sort_by_key(|sample| sample.timestamp);
+
+        let start = samples[0].timestamp;
+        let offsets_ms = samples
+            .iter()
+            .map(|sample| (sample.timestamp - start).num_milliseconds().max(0) as u64)
+            .collect();
+        Ok(Self {
+            offsets_ms,
+            snapshots: samples.into_iter().map(|sample| sample.snapshot).collect(),
+            skipped_lines,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn skipped_lines(&self) -> u64 {
+        self.skipped_lines
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.offsets_ms.last().copied().unwrap_or(0)
+    }
+
+    pub fn offset_ms(&self, index: usize) -> u64 {
+        self.offsets_ms
+            .get(index)
+            .copied()
+            .unwrap_or_else(|| self.duration_ms())
+    }
+
+    pub fn snapshot(&self, index: usize) -> Option<&TelemetrySnapshot> {
+        self.snapshots.get(index)
+    }
+
+    /// First sample at or after `position_ms`.
+    pub fn index_at(&self, position_ms: u64) -> usize {
+        self.offsets_ms
+            .partition_point(|offset| *offset < position_ms)
+            .min(self.len().saturating_sub(1))
+    }
+
+    /// Wall-clock wait between `index - 1` and `index` at the given speed.
+    pub fn delay_before(&self, index: usize, speed: f64) -> Duration {
+        if index == 0 || index >= self.len() {
+            return Duration::ZERO;
+        }
+        let gap = (self.offsets_ms[index] - self.offsets_ms[index - 1]).min(MAX_REPLAY_GAP_MS);
+        let speed = speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED);
+        Duration::from_secs_f64(gap as f64 / 1000.0 / speed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::core::test_support;
+
+    fn line(secs: i64, cpu: f64) -> String {
+        let sample = test_support::profile_sample(test_support::snapshot_at(secs, cpu));
+        serde_json::to_string(&sample).unwrap()
+    }
+
+    /// Out of order, with a truncated line and a minute-long gap.
+    fn timeline() -> ReplayTimeline {
+        let text = [
+            line(1, 20.0),
+            line(0, 10.0),
+            "{truncated".to_string(),
+            line(60, 30.0),
+        ]
+        .join("\n");
+        ReplayTimeline::from_reader(ProfileReader::new(Cursor::new(text))).unwrap()
+    }
+
+    #[test]
+    fn timeline_sorts_samples_and_skips_bad_lines() {
+        let timeline = timeline();
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline.skipped_lines(), 1);
+        assert_eq!(timeline.duration_ms(), 60_000);
+        assert_eq!(timeline.snapshot(0).unwrap().cpu.usage_pct, 10.0);
+    }
+
+    #[test]
+    fn timeline_indexes_offsets() {
+        let timeline = timeline();
+        assert_eq!(timeline.index_at(0), 0);
+        assert_eq!(timeline.index_at(500), 1);
+        assert_eq!(timeline.index_at(90_000), 2);
+    }
+
+    #[test]
+    fn delays_scale_with_speed_and_cap_long_gaps() {
+        let timeline = timeline();
+        assert_eq!(timeline.delay_before(1, 1.0), Duration::from_secs(1));
+        assert_eq!(timeline.delay_before(1, 4.0), Duration::from_millis(250));
+        assert_eq!(timeline.delay_before(2, 1.0), Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,277 @@
+use crate::types::{AdaptiveSamplingConfig, SamplingStatus, TelemetrySnapshot};
+
+pub const MIN_INTERVAL_MS: u64 = 10;
+pub const MAX_INTERVAL_MS: u64 = 30_000;
+const MAX_BACKOFF: u32 = 8;
+/// Consecutive samples over budget before the interval doubles.
+const OVER_BUDGET_SAMPLES: u32 = 3;
+/// Consecutive samples under half the budget before the interval halves again.
+const UNDER_BUDGET_SAMPLES: u32 = 10;
+
+/// Who is reading snapshots right now, from most to least demanding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConsumerLevel {
+    /// Nothing reads snapshots; only `latest_snapshot` is kept warm.
+    Idle,
+    /// Alert rules, anomaly detection, MQTT, stream server, digest or flight recorder.
+    Background,
+    /// A visible window, the native taskbar or a running profile capture.
+    Interactive,
+}
+
+impl ConsumerLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Background => "background",
+            Self::Interactive => "interactive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SamplingPlan {
+    pub interval_ms: u64,
+    /// GPU, per-process, disk and temperature polling; see
+    /// `SystemCollector::set_expensive_sources_paused`.
+    pub expensive_sources: bool,
+    pub reason: &'static str,
+}
+
+/// Picks the telemetry cadence from the configured refresh rate, the current consumers,
+/// the power source and the app's own CPU use.
+#[derive(Debug, Clone)]
+pub struct AdaptiveSampler {
+    config: AdaptiveSamplingConfig,
+    backoff: u32,
+    over_budget: u32,
+    under_budget: u32,
+    last_app_cpu: Option<f64>,
+    last_plan: Option<(SamplingPlan, u64, ConsumerLevel, Option<bool>)>,
+}
+
+impl Default for AdaptiveSampler {
+    fn default() -> Self {
+        Self::new(AdaptiveSamplingConfig::default())
+    }
+}
+
+impl AdaptiveSampler {
+    pub fn new(config: AdaptiveSamplingConfig) -> Self {
+        Self {
+            config,
+            backoff: 1,
+            over_budget: 0,
+            under_budget: 0,
+            last_app_cpu: None,
+            last_plan: None,
+        }
+    }
+
+    pub fn config(&self) -> &AdaptiveSamplingConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: AdaptiveSamplingConfig) {
+        self.config = config;
+        self.backoff = 1;
+        self.over_budget = 0;
+        self.under_budget = 0;
+    }
+
+    pub fn plan(
+        &mut self,
+        configured_interval_ms: u64,
+        consumers: ConsumerLevel,
+        on_battery: Option<bool>,
+    ) -> SamplingPlan {
+        let configured = configured_interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+        let plan = if self.config.enabled {
+            self.adaptive_plan(configured, consumers, on_battery)
+        } else {
+            SamplingPlan {
+                interval_ms: configured,
+                expensive_sources: true,
+                reason: "fixed",
+            }
+        };
+        self.last_plan = Some((plan, configured, consumers, on_battery));
+        plan
+    }
+
+    fn adaptive_plan(
+        &self,
+        configured: u64,
+        consumers: ConsumerLevel,
+        on_battery: Option<bool>,
+    ) -> SamplingPlan {
+        let mut plan = match consumers {
+            ConsumerLevel::Interactive => SamplingPlan {
+                interval_ms: configured,
+                expensive_sources: true,
+                reason: "interactive",
+            },
+            ConsumerLevel::Background => SamplingPlan {
+                interval_ms: configured.max(self.config.background_interval_ms),
+                expensive_sources: true,
+                reason: "background",
+            },
+            ConsumerLevel::Idle => SamplingPlan {
+                interval_ms: configured.max(self.config.idle_interval_ms),
+                expensive_sources: false,
+                reason: "idle",
+            },
+        };
+        if self.config.power_saver_on_battery
+            && on_battery == Some(true)
+            && plan.interval_ms < self.config.power_saver_interval_ms
+        {
+            plan.interval_ms = self.config.power_saver_interval_ms;
+            plan.reason = "battery";
+        }
+        if self.backoff > 1 {
+            plan.interval_ms = plan.interval_ms.saturating_mul(self.backoff as u64);
+            plan.reason = "cpu-budget";
+        }
+        plan.interval_ms = plan.interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+        plan
+    }
+
+    /// Feeds a snapshot collected under `plan` into the budget back-off. Ticks with expensive
+    /// sources paused repeat the last app CPU reading, so counting them would ratchet the
+    /// back-off on one stale value.
+    pub fn observe_snapshot(&mut self, plan: &SamplingPlan, snapshot: &TelemetrySnapshot) {
+        if plan.expensive_sources {
+            self.observe_app_cpu(snapshot.app_cpu_usage_pct);
+        }
+    }
+
+    /// Feeds the app's own CPU use from the latest snapshot into the budget back-off.
+    pub fn observe_app_cpu(&mut self, app_cpu_pct: Option<f64>) {
+        self.last_app_cpu = app_cpu_pct;
+        let budget = self.config.app_cpu_budget_pct;
+        let Some(value) = app_cpu_pct.filter(|v| v.is_finite()) else {
+            return;
+        };
+        if !self.config.enabled || budget <= 0.0 {
+            self.backoff = 1;
+            return;
+        }
+        if value > budget {
+            self.under_budget = 0;
+            self.over_budget += 1;
+            if self.over_budget >= OVER_BUDGET_SAMPLES {
+                self.over_budget = 0;
+                self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+            }
+        } else if value < budget * 0.5 {
+            self.over_budget = 0;
+            self.under_budget += 1;
+            if self.under_budget >= UNDER_BUDGET_SAMPLES {
+                self.under_budget = 0;
+                self.backoff = (self.backoff / 2).max(1);
+            }
+        } else {
+            self.over_budget = 0;
+            self.under_budget = 0;
+        }
+    }
+
+    pub fn status(&self) -> SamplingStatus {
+        let (plan, configured, consumers, on_battery) = self.last_plan.unwrap_or((
+            SamplingPlan {
+                interval_ms: 0,
+                expensive_sources: true,
+                reason: "fixed",
+            },
+            0,
+            ConsumerLevel::Idle,
+            None,
+        ));
+        SamplingStatus {
+            enabled: self.config.enabled,
+            configured_interval_ms: configured,
+            effective_interval_ms: plan.interval_ms,
+            consumers: consumers.as_str().to_string(),
+            reason: plan.reason.to_string(),
+            on_battery,
+            app_cpu_usage_pct: self.last_app_cpu,
+            backoff_factor: self.backoff,
+            expensive_sources_paused: !plan.expensive_sources,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support;
+
+    #[test]
+    fn cadence_follows_consumers_and_power() {
+        let mut sampler = AdaptiveSampler::default();
+        let plan = sampler.plan(250, ConsumerLevel::Interactive, Some(false));
+        assert_eq!((plan.interval_ms, plan.expensive_sources), (250, true));
+
+        let plan = sampler.plan(250, ConsumerLevel::Background, None);
+        assert_eq!(plan.interval_ms, 2000);
+
+        let plan = sampler.plan(250, ConsumerLevel::Idle, None);
+        assert_eq!((plan.interval_ms, plan.expensive_sources), (5000, false));
+
+        let plan = sampler.plan(250, ConsumerLevel::Interactive, Some(true));
+        assert_eq!((plan.interval_ms, plan.reason), (3000, "battery"));
+
+        sampler.set_config(AdaptiveSamplingConfig {
+            enabled: false,
+            ..AdaptiveSamplingConfig::default()
+        });
+        let plan = sampler.plan(250, ConsumerLevel::Idle, Some(true));
+        assert_eq!((plan.interval_ms, plan.reason), (250, "fixed"));
+    }
+
+    #[test]
+    fn cpu_budget_backs_off_and_recovers() {
+        let mut sampler = AdaptiveSampler::default();
+        for _ in 0..OVER_BUDGET_SAMPLES * 2 {
+            sampler.observe_app_cpu(Some(9.0));
+        }
+        let plan = sampler.plan(500, ConsumerLevel::Interactive, None);
+        assert_eq!((plan.interval_ms, plan.reason), (2000, "cpu-budget"));
+        assert_eq!(sampler.status().backoff_factor, 4);
+
+        // In-band readings hold the current back-off.
+        sampler.observe_app_cpu(Some(2.0));
+        assert_eq!(sampler.status().backoff_factor, 4);
+
+        for _ in 0..UNDER_BUDGET_SAMPLES * 2 {
+            sampler.observe_app_cpu(Some(0.5));
+        }
+        assert_eq!(sampler.status().backoff_factor, 1);
+        assert_eq!(
+            sampler
+                .plan(500, ConsumerLevel::Interactive, None)
+                .interval_ms,
+            500
+        );
+    }
+
+    #[test]
+    fn paused_ticks_leave_the_back_off_alone() {
+        let mut sampler = AdaptiveSampler::default();
+        let mut snapshot = test_support::snapshot();
+        snapshot.app_cpu_usage_pct = Some(9.0);
+        let idle = sampler.plan(500, ConsumerLevel::Idle, None);
+        assert!(!idle.expensive_sources);
+        for _ in 0..OVER_BUDGET_SAMPLES * 4 {
+            sampler.observe_snapshot(&idle, &snapshot);
+        }
+        assert_eq!(sampler.status().backoff_factor, 1);
+
+        let interactive = sampler.plan(500, ConsumerLevel::Interactive, None);
+        for _ in 0..OVER_BUDGET_SAMPLES {
+            sampler.observe_snapshot(&interactive, &snapshot);
+        }
+        assert_eq!(sampler.status().backoff_factor, 2);
+    }
+}
@@ -0,0 +1,125 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    core::{ports::PathProvider, store},
+    types::AppSettings,
+};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+/// Where an unreadable `settings.json` is moved before the defaults replace it.
+pub const SETTINGS_BACKUP_FILE: &str = "settings.json.bak";
+/// The schema `settings.json` is written with; `MIGRATIONS` must reach it.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` turns a version `n` document into version `n + 1`.
+const MIGRATIONS: &[fn(Value) -> Value] = &[wrap_bare_settings];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsDocument {
+    version: u32,
+    settings: AppSettings,
+}
+
+/// Version 0 is the bare settings object the front end kept (and still exports).
+fn wrap_bare_settings(document: Value) -> Value {
+    json!({ "version": 1, "settings": document })
+}
+
+fn document_version(document: &Value) -> Result<u32, String> {
+    match document.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| format!("invalid settings version `{version}`")),
+    }
+}
+
+/// Brings a stored document up to [`SETTINGS_VERSION`]. Documents from a newer build are read
+/// as far as this one understands them; unknown fields are dropped on the next save.
+pub fn migrate_settings(mut document: Value) -> Result<AppSettings, String> {
+    if !document.is_object() {
+        return Err("settings must be a JSON object".to_string());
+    }
+    let mut version = document_version(&document)?;
+    while let Some(migrate) = MIGRATIONS.get(version as usize) {
+        document = migrate(document);
+        version += 1;
+    }
+    serde_json::from_value::<SettingsDocument>(document)
+        .map(|document| document.settings)
+        .map_err(|e| format!("invalid settings: {e}"))
+}
+
+/// Reads `settings.json`; `None` when there is none yet. A file that cannot be read is moved
+/// to [`SETTINGS_BACKUP_FILE`] so the next save does not destroy it.
+pub fn load_settings(paths: &dyn PathProvider) -> Result<Option<AppSettings>, String> {
+    let path = paths.data_file(SETTINGS_FILE)?;
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+    let settings = serde_json::from_str::<Value>(&text)
+        .map_err(|e| e.to_string())
+        .and_then(migrate_settings);
+    match settings {
+        Ok(settings) => Ok(Some(settings)),
+        Err(err) => {
+            let backup = paths.data_file(SETTINGS_BACKUP_FILE)?;
+            fs::rename(&path, &backup).map_err(|e| e.to_string())?;
+            Err(format!(
+                "{SETTINGS_FILE}: {err} (moved to {SETTINGS_BACKUP_FILE})"
+            ))
+        }
+    }
+}
+
+/// Writes `settings.json` at the current version, atomically.
+pub fn save_settings(paths: &dyn PathProvider, settings: &AppSettings) -> Result<(), String> {
+    let document = SettingsDocument {
+        version: SETTINGS_VERSION,
+        settings: settings.clone(),
+    };
+    store::write_json_atomic(paths, SETTINGS_FILE, &document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ports::DirPaths;
+
+    #[test]
+    fn migrates_and_round_trips_settings() {
+        let bare =
+            json!({"language": "en-US", "closeToTray": true, "memoryTrimIntervalMinutes": 9});
+        let settings = migrate_settings(bare).unwrap();
+        assert_eq!(settings.language, "en-US");
+        assert!(settings.close_to_tray);
+        assert_eq!(settings.memory_trim_interval_minutes, 9);
+        assert!(!settings.metric_thresholds.is_empty());
+        assert!(migrate_settings(json!({"version": "x", "settings": {}})).is_err());
+        assert!(migrate_settings(json!([1, 2])).is_err());
+
+        let dir = std::env::temp_dir().join(format!("pulsecore-settings-{}", std::process::id()));
+        let paths = DirPaths(dir.clone());
+        assert!(load_settings(&paths).unwrap().is_none());
+        save_settings(&paths, &settings).unwrap();
+        let stored: Value =
+            serde_json::from_str(&fs::read_to_string(dir.join(SETTINGS_FILE)).unwrap()).unwrap();
+        assert_eq!(stored["version"], SETTINGS_VERSION);
+        let loaded = load_settings(&paths).unwrap().unwrap();
+        assert_eq!(loaded.language, "en-US");
+
+        fs::write(dir.join(SETTINGS_FILE), "{ not json").unwrap();
+        assert!(load_settings(&paths)
+            .unwrap_err()
+            .contains(SETTINGS_BACKUP_FILE));
+        assert!(dir.join(SETTINGS_BACKUP_FILE).exists());
+        assert!(load_settings(&paths).unwrap().is_none());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Duration, Utc};
+use image::{Rgba, RgbaImage};
+
+use crate::{
+    core::{
+        metric_path::{DiskField, MetricPath},
+        thresholds::Rgb,
+    },
+    types::{SparklineConfig, TelemetrySnapshot},
+};
+
+const MAX_SECONDS: u32 = 600;
+const MIN_WIDTH: u32 = 8;
+const MAX_WIDTH: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparklineStyle {
+    Line,
+    Bar,
+}
+
+/// A validated [`SparklineConfig`] bound to the metric it plots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparklineSpec {
+    pub metric: MetricPath,
+    pub style: SparklineStyle,
+    pub seconds: u32,
+    /// Width in pixels; the height follows the row it is drawn in.
+    pub width: u32,
+    /// Top of the y axis; `None` scales to the largest value in the window.
+    pub max: Option<f64>,
+}
+
+impl SparklineSpec {
+    /// `fallback` is the segment's own metric, used when the config does not name one.
+    pub fn parse(config: &SparklineConfig, fallback: Option<&MetricPath>) -> Result<Self, String> {
+        let metric = match config.metric.as_deref().map(str::trim) {
+            Some(path) if !path.is_empty() => MetricPath::parse(path)?,
+            _ => fallback
+                .cloned()
+                .ok_or_else(|| "sparkline needs a metric".to_string())?,
+        };
+        let style = match config.style.trim().to_ascii_lowercase().as_str() {
+            "line" | "" => SparklineStyle::Line,
+            "bar" => SparklineStyle::Bar,
+            other => {
+                return Err(format!(
+                    "unknown sparkline style: {other} (expected line or bar)"
+                ))
+            }
+        };
+        // Percentages get a fixed 0-100 axis so a flat 3% line does not fill the chart.
+        let max = config
+            .max
+            .filter(|max| max.is_finite() && *max > 0.0)
+            .or(match metric {
+                MetricPath::CpuUsage
+                | MetricPath::GpuUsage
+                | MetricPath::MemUsage
+                | MetricPath::SwapUsage
+                | MetricPath::Disk {
+                    field: DiskField::Usage,
+                    ..
+                } => Some(100.0),
+                _ => None,
+            });
+        Ok(Self {
+            metric,
+            style,
+            seconds: config.seconds.clamp(1, MAX_SECONDS),
+            width: config.width.clamp(MIN_WIDTH, MAX_WIDTH),
+            max,
+        })
+    }
+}
+
+/// One metric's recent values, oldest first, with the snapshot time of each.
+type Series = VecDeque<(DateTime<Utc>, f64)>;
+
+/// Recent values of the metrics that sparklines plot, keyed by snapshot time.
+#[derive(Debug, Default)]
+pub struct MetricHistory {
+    series: Vec<(MetricPath, Series)>,
+}
+
+impl MetricHistory {
+    /// Appends `snapshot`'s values for every metric in `specs`, drops series no spec plots any
+    /// more and trims each one to the longest window asked of it.
+    pub fn record(&mut self, snapshot: &TelemetrySnapshot, specs: &[SparklineSpec]) {
+        self.series
+            .retain(|(metric, _)| specs.iter().any(|spec| &spec.metric == metric));
+        for spec in specs {
+            let index = match self
+                .series
+                .iter()
+                .position(|(metric, _)| metric == &spec.metric)
+            {
+                Some(index) => index,
+                None => {
+                    self.series.push((spec.metric.clone(), VecDeque::new()));
+                    self.series.len() - 1
+                }
+            };
+            let samples = &mut self.series[index].1;
+            if samples.back().map(|(at, _)| *at) == Some(snapshot.timestamp) {
+                continue;
+            }
+            if let Some(value) = spec.metric.value(snapshot) {
+                samples.push_back((snapshot.timestamp, value));
+            }
+        }
+        for (metric, samples) in &mut self.series {
+            let keep = specs
+                .iter()
+                .filter(|spec| &spec.metric == metric)
+                .map(|spec| spec.seconds)
+                .max()
+                .unwrap_or(0);
+            let cutoff = snapshot.timestamp - Duration::seconds(keep as i64);
+            while samples.front().is_some_and(|(at, _)| *at < cutoff) {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Values of `metric` over the last `seconds`, oldest first.
+    pub fn values(&self, metric: &MetricPath, seconds: u32) -> Vec<f64> {
+        let Some((_, samples)) = self.series.iter().find(|(other, _)| other == metric) else {
+            return Vec::new();
+        };
+        let Some((latest, _)) = samples.back() else {
+            return Vec::new();
+        };
+        let cutoff = *latest - Duration::seconds(seconds as i64);
+        samples
+            .iter()
+            .filter(|(at, _)| *at >= cutoff)
+            .map(|(_, value)| *value)
+            .collect()
+    }
+}
+
+/// Draws `values` (oldest first, right-aligned) into an opaque `width` x `height` RGBA image.
+/// Values below zero sit on the baseline; values above the axis top are clipped to it.
+pub fn rasterize(
+    values: &[f64],
+    style: SparklineStyle,
+    width: u32,
+    height: u32,
+    max: Option<f64>,
+    color: Rgb,
+    background: Rgb,
+) -> RgbaImage {
+    let width = width.max(1);
+    let height = height.max(1);
+    let mut image = RgbaImage::from_pixel(width, height, rgba(background));
+    // One pixel per value at most; older values that do not fit scroll off the left.
+    let values = &values[values.len().saturating_sub(width as usize)..];
+    if values.is_empty() {
+        return image;
+    }
+    let top = max
+        .unwrap_or_else(|| values.iter().copied().fold(0.0, f64::max))
+        .max(f64::EPSILON);
+    let bottom = height - 1;
+    let y_of = |value: f64| -> u32 {
+        let ratio = if value.is_finite() {
+            (value / top).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        bottom - (ratio * bottom as f64).round() as u32
+    };
+    let color = rgba(color);
+    let count = values.len() as u32;
+
+    match style {
+        SparklineStyle::Bar => {
+            let bar_width = (width / count).max(1);
+            let offset = width - bar_width * count;
+            for (index, value) in values.iter().enumerate() {
+                let x0 = offset + index as u32 * bar_width;
+                for x in x0..x0 + bar_width {
+                    for y in y_of(*value)..=bottom {
+                        image.put_pixel(x, y, color);
+                    }
+                }
+            }
+        }
+        SparklineStyle::Line => {
+            let x_of = |index: usize| -> i64 {
+                if count == 1 {
+                    (width - 1) as i64
+                } else {
+                    (index as f64 * (width - 1) as f64 / (count - 1) as f64).round() as i64
+                }
+            };
+            let points: Vec<(i64, i64)> = values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (x_of(index), y_of(*value) as i64))
+                .collect();
+            if let [(x, y)] = points[..] {
+                image.put_pixel(x as u32, y as u32, color);
+            }
+            for pair in points.windows(2) {
+                draw_line(&mut image, pair[0], pair[1], color);
+            }
+        }
+    }
+    image
+}
+
+fn rgba([r, g, b]: Rgb) -> Rgba<u8> {
+    Rgba([r, g, b, 255])
+}
+
+/// Bresenham; both ends are inside the image.
+fn draw_line(image: &mut RgbaImage, from: (i64, i64), to: (i64, i64), color: Rgba<u8>) {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let step_x = if x < to.0 { 1 } else { -1 };
+    let step_y = if y < to.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    loop {
+        image.put_pixel(x as u32, y as u32, color);
+        if (x, y) == to {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support;
+
+    const FG: Rgb = [255, 0, 0];
+    const BG: Rgb = [0, 0, 0];
+
+    fn lit(image: &RgbaImage, x: u32, y: u32) -> bool {
+        *image.get_pixel(x, y) == rgba(FG)
+    }
+
+    #[test]
+    fn rasterizes_bars() {
+        let bars = rasterize(
+            &[0.0, 50.0, 100.0, 200.0],
+            SparklineStyle::Bar,
+            8,
+            5,
+            Some(100.0),
+            FG,
+            BG,
+        );
+        assert_eq!(bars.dimensions(), (8, 5));
+        // Two pixels per bar: empty bars keep a baseline, full and clipped bars fill the column.
+        assert!(lit(&bars, 0, 4) && !lit(&bars, 0, 3));
+        assert!(lit(&bars, 2, 2) && !lit(&bars, 2, 1));
+        assert!((0..5).all(|y| lit(&bars, 5, y) && lit(&bars, 7, y)));
+    }
+
+    #[test]
+    fn rasterizes_lines() {
+        let line = rasterize(
+            &[0.0, 100.0],
+            SparklineStyle::Line,
+            5,
+            5,
+            Some(100.0),
+            FG,
+            BG,
+        );
+        for step in 0..5 {
+            assert!(lit(&line, step, 4 - step));
+        }
+        assert_eq!(line.pixels().filter(|pixel| **pixel == rgba(FG)).count(), 5);
+    }
+
+    #[test]
+    fn empty_series_leave_the_background() {
+        let empty = rasterize(&[], SparklineStyle::Line, 4, 4, None, FG, BG);
+        assert!(empty.pixels().all(|pixel| *pixel == rgba(BG)));
+    }
+
+    #[test]
+    fn history_keeps_the_requested_window() {
+        let spec = SparklineSpec::parse(
+            &SparklineConfig {
+                metric: Some("cpu.usage".to_string()),
+                seconds: 2,
+                ..SparklineConfig::default()
+            },
+            None,
+        )
+        .unwrap();
+        assert_eq!(spec.max, Some(100.0));
+        let mut history = MetricHistory::default();
+        for second in 0..5 {
+            let snapshot = test_support::snapshot_at(second, second as f64 * 10.0);
+            history.record(&snapshot, std::slice::from_ref(&spec));
+        }
+        assert_eq!(
+            history.values(&MetricPath::CpuUsage, 2),
+            vec![20.0, 30.0, 40.0]
+        );
+        assert_eq!(history.values(&MetricPath::CpuUsage, 1), vec![30.0, 40.0]);
+        assert!(history.values(&MetricPath::GpuUsage, 2).is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+use serde::Serialize;
+
+use crate::{
+    core::{
+        metric_path::MetricPath,
+        taskbar::{SegmentTone, TaskbarSegment},
+        thresholds::ThresholdLevel,
+    },
+    types::TelemetrySnapshot,
+};
+
+/// Which status bar the output is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusBarProtocol {
+    /// An endless JSON array of block lists, after a `{"version":1}` header (i3bar, swaybar).
+    I3bar,
+    /// One `{text, tooltip, class, percentage}` object per line (waybar `custom` modules).
+    Waybar,
+}
+
+#[derive(Debug, Serialize)]
+struct I3barBlock {
+    full_text: String,
+    short_text: String,
+    name: &'static str,
+    instance: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    urgent: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct WaybarStatus {
+    text: String,
+    tooltip: String,
+    class: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percentage: Option<u8>,
+}
+
+/// Turns taskbar segments into status bar lines, so bars on Linux show the same segments and
+/// threshold colors as the Windows taskbar.
+#[derive(Debug, Clone)]
+pub struct StatusBarEncoder {
+    protocol: StatusBarProtocol,
+    /// Reported as waybar's `percentage` (for its `format-icons`).
+    percentage: MetricPath,
+    started: bool,
+}
+
+impl StatusBarEncoder {
+    pub fn new(protocol: StatusBarProtocol, percentage: MetricPath) -> Self {
+        Self {
+            protocol,
+            percentage,
+            started: false,
+        }
+    }
+
+    /// Starts over with the protocol header, e.g. after a FIFO reader reconnects.
+    pub fn reset(&mut self) {
+        self.started = false;
+    }
+
+    /// The next chunk to write, newline-terminated; the first one carries the i3bar header.
+    pub fn encode(&mut self, snapshot: &TelemetrySnapshot, segments: &[TaskbarSegment]) -> String {
+        let first = !self.started;
+        self.started = true;
+        match self.protocol {
+            StatusBarProtocol::I3bar => {
+                let blocks: Vec<I3barBlock> = segments.iter().map(i3bar_block).collect();
+                let line = serde_json::to_string(&blocks).unwrap_or_else(|_| "[]".to_string());
+                if first {
+                    format!("{{\"version\":1}}\n[\n{line}\n")
+                } else {
+                    format!(",{line}\n")
+                }
+            }
+            StatusBarProtocol::Waybar => {
+                let status = WaybarStatus {
+                    text: segments
+                        .iter()
+                        .map(segment_text)
+                        .collect::<Vec<_>>()
+                        .join("  "),
+                    tooltip: segments
+                        .iter()
+                        .map(|segment| {
+                            format!("{}: {}", segment.label, segment_value_text(segment))
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    class: segments
+                        .iter()
+                        .map(|segment| segment.level)
+                        .max()
+                        .unwrap_or(ThresholdLevel::Normal)
+                        .as_str(),
+                    percentage: self
+                        .percentage
+                        .value(snapshot)
+                        .filter(|value| value.is_finite())
+                        .map(|value| value.clamp(0.0, 100.0).round() as u8),
+                };
+                let line = serde_json::to_string(&status).unwrap_or_else(|_| "{}".to_string());
+                format!("{line}\n")
+            }
+        }
+    }
+}
+
+fn segment_value_text(segment: &TaskbarSegment) -> String {
+    match segment.extra.as_deref() {
+        Some(extra) if !extra.is_empty() => format!("{} {extra}", segment.value),
+        _ => segment.value.clone(),
+    }
+}
+
+fn segment_text(segment: &TaskbarSegment) -> String {
+    let value = segment_value_text(segment);
+    if value.is_empty() {
+        segment.label.clone()
+    } else {
+        format!("{} {value}", segment.label)
+    }
+}
+
+/// Accent and band colors as `#rrggbb`; normal and muted text keep the bar's own color.
+fn tone_hex(tone: SegmentTone) -> Option<String> {
+    let [r, g, b] = match tone {
+        SegmentTone::Normal | SegmentTone::Muted => return None,
+        SegmentTone::Cyan => [0, 242, 255],
+        SegmentTone::Pink => [188, 19, 254],
+        SegmentTone::Orange => [255, 165, 0],
+        SegmentTone::Red => [255, 59, 59],
+        SegmentTone::Custom(rgb) => rgb,
+    };
+    Some(format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+fn i3bar_block(segment: &TaskbarSegment) -> I3barBlock {
+    I3barBlock {
+        full_text: segment_text(segment),
+        short_text: format!("{} {}", segment.label, segment.value)
+            .trim()
+            .to_string(),
+        name: "pulsecore",
+        instance: segment.label.to_lowercase(),
+        color: tone_hex(segment.value_tone),
+        urgent: segment.level == ThresholdLevel::Critical,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{taskbar::TaskbarLayout, test_support},
+        types::NativeTaskbarConfig,
+    };
+
+    /// A critical CPU next to normal memory.
+    fn snapshot() -> TelemetrySnapshot {
+        let mut snapshot = test_support::snapshot();
+        snapshot.cpu.usage_pct = 93.4;
+        snapshot.cpu.temperature_c = Some(61.0);
+        snapshot
+    }
+
+    fn segments() -> Vec<TaskbarSegment> {
+        let mut config = NativeTaskbarConfig::default();
+        config
+            .segments
+            .retain(|segment| matches!(segment.kind.as_str(), "cpu" | "memory"));
+        TaskbarLayout::new(&config).build_segments(&snapshot())
+    }
+
+    #[test]
+    fn i3bar_blocks_carry_colors_and_urgency() {
+        let mut i3bar = StatusBarEncoder::new(StatusBarProtocol::I3bar, MetricPath::CpuUsage);
+        let first = i3bar.encode(&snapshot(), &segments());
+        let (header, rest) = first.split_once('\n').unwrap();
+        assert_eq!(header, r#"{"version":1}"#);
+        let blocks: serde_json::Value =
+            serde_json::from_str(rest.trim_start_matches("[\n")).unwrap();
+        assert_eq!(blocks[0]["full_text"], "CPU 93% 61°C");
+        assert_eq!(blocks[0]["color"], "#ff3b3b");
+        assert_eq!(blocks[0]["urgent"], true);
+        assert_eq!(blocks[1]["color"], "#00f2ff");
+        assert!(blocks[1].get("urgent").is_none());
+    }
+
+    #[test]
+    fn i3bar_header_is_sent_once_until_reset() {
+        let mut i3bar = StatusBarEncoder::new(StatusBarProtocol::I3bar, MetricPath::CpuUsage);
+        i3bar.encode(&snapshot(), &segments());
+        assert!(i3bar.encode(&snapshot(), &segments()).starts_with(",["));
+        i3bar.reset();
+        assert!(i3bar
+            .encode(&snapshot(), &segments())
+            .starts_with("{\"version\""));
+    }
+
+    #[test]
+    fn waybar_writes_one_status_line() {
+        let mut waybar = StatusBarEncoder::new(StatusBarProtocol::Waybar, MetricPath::CpuUsage);
+        let line = waybar.encode(&snapshot(), &segments());
+        assert!(line.ends_with('\n') && line.lines().count() == 1);
+        let status: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(status["text"], "CPU 93% 61°C  RAM 25%");
+        assert_eq!(status["tooltip"], "CPU: 93% 61°C\nRAM: 25%");
+        assert_eq!(status["class"], "critical");
+        assert_eq!(status["percentage"], 93);
+    }
+}
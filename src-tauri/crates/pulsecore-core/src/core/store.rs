@@ -0,0 +1,48 @@
+use std::fs;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::core::ports::PathProvider;
+
+/// Reads a JSON store from the data directory; missing or malformed files read as `None`.
+pub fn read_json<T: DeserializeOwned>(paths: &dyn PathProvider, file: &str) -> Option<T> {
+    let path = paths.data_file(file).ok()?;
+    let text = fs::read_to_string(path).ok()?;
+    serde_json::from_str::<T>(&text).ok()
+}
+
+/// Writes a user-editable store (pretty-printed).
+pub fn write_json<T: Serialize>(
+    paths: &dyn PathProvider,
+    file: &str,
+    value: &T,
+) -> Result<(), String> {
+    let path = paths.data_file(file)?;
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Writes internal state that nobody edits by hand (compact).
+pub fn write_json_compact<T: Serialize>(
+    paths: &dyn PathProvider,
+    file: &str,
+    value: &T,
+) -> Result<(), String> {
+    let path = paths.data_file(file)?;
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Writes a user-editable store (pretty-printed) through a temporary file and a rename, so a
+/// crash mid-write leaves the previous version in place.
+pub fn write_json_atomic<T: Serialize>(
+    paths: &dyn PathProvider,
+    file: &str,
+    value: &T,
+) -> Result<(), String> {
+    let path = paths.data_file(file)?;
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,815 @@
+use std::borrow::Cow;
+
+use crate::{
+    core::{
+        metric_path::{DiskField, MetricPath},
+        sparkline::SparklineSpec,
+        template::Template,
+        thresholds::{Rgb, ThresholdBands, ThresholdLevel},
+        units::{UnitFormatter, MIB},
+    },
+    types::{NativeTaskbarConfig, TaskbarSegmentConfig, TelemetrySnapshot},
+};
+
+/// Color class of a segment's value; each front end maps it to its own palette.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SegmentTone {
+    #[default]
+    Normal,
+    Muted,
+    Cyan,
+    Pink,
+    Orange,
+    Red,
+    /// A threshold band's own color.
+    Custom(Rgb),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskbarSegment {
+    pub label: String,
+    pub value: String,
+    pub extra: Option<String>,
+    pub value_tone: SegmentTone,
+    /// The worst threshold band among the segment's metrics.
+    pub level: ThresholdLevel,
+    pub sparkline: Option<SparklineSpec>,
+}
+
+/// What a [`TaskbarSegmentConfig`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    Cpu,
+    Gpu,
+    Memory,
+    App,
+    Down,
+    Up,
+    Latency,
+    GpuMemory,
+    Power,
+    Swap,
+    DiskUsage,
+    DiskRead,
+    DiskWrite,
+    Custom,
+}
+
+impl SegmentKind {
+    pub const ALL: [Self; 14] = [
+        Self::Cpu,
+        Self::Gpu,
+        Self::Memory,
+        Self::App,
+        Self::Down,
+        Self::Up,
+        Self::Latency,
+        Self::GpuMemory,
+        Self::Power,
+        Self::Swap,
+        Self::DiskUsage,
+        Self::DiskRead,
+        Self::DiskWrite,
+        Self::Custom,
+    ];
+
+    pub fn parse(kind: &str) -> Result<Self, String> {
+        let kind = kind.trim();
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str().eq_ignore_ascii_case(kind))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|kind| kind.as_str()).collect();
+                format!(
+                    "unknown segment kind: {kind} (expected one of {})",
+                    names.join(", ")
+                )
+            })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Gpu => "gpu",
+            Self::Memory => "memory",
+            Self::App => "app",
+            Self::Down => "down",
+            Self::Up => "up",
+            Self::Latency => "latency",
+            Self::GpuMemory => "gpuMemory",
+            Self::Power => "power",
+            Self::Swap => "swap",
+            Self::DiskUsage => "diskUsage",
+            Self::DiskRead => "diskRead",
+            Self::DiskWrite => "diskWrite",
+            Self::Custom => "custom",
+        }
+    }
+
+    /// Disk kinds need `disk` to pick a drive or mount.
+    pub fn is_disk(self) -> bool {
+        matches!(self, Self::DiskUsage | Self::DiskRead | Self::DiskWrite)
+    }
+
+    /// The metric the kind's value shows, which sparklines plot by default.
+    fn primary_metric(self, disk: Option<&str>) -> Option<MetricPath> {
+        let disk = |field| {
+            disk.map(|id| MetricPath::Disk {
+                id: id.to_string(),
+                field,
+            })
+        };
+        match self {
+            Self::Cpu => Some(MetricPath::CpuUsage),
+            Self::Gpu => Some(MetricPath::GpuUsage),
+            Self::Memory => Some(MetricPath::MemUsage),
+            Self::App => Some(MetricPath::AppCpu),
+            Self::Down => Some(MetricPath::NetDown),
+            Self::Up => Some(MetricPath::NetUp),
+            Self::Latency => Some(MetricPath::NetLatency),
+            Self::GpuMemory => Some(MetricPath::GpuVram),
+            Self::Power => Some(MetricPath::Power),
+            Self::Swap => Some(MetricPath::SwapUsage),
+            Self::DiskUsage => disk(DiskField::Usage),
+            Self::DiskRead => disk(DiskField::Read),
+            Self::DiskWrite => disk(DiskField::Write),
+            Self::Custom => None,
+        }
+    }
+}
+
+/// A validated [`TaskbarSegmentConfig`].
+#[derive(Debug, Clone)]
+pub struct SegmentSpec {
+    kind: SegmentKind,
+    label: Option<String>,
+    disk: Option<String>,
+    show_freq: bool,
+    show_temp: bool,
+    /// `custom` only.
+    value: Option<Template>,
+    extra: Option<Template>,
+    sparkline: Option<SparklineSpec>,
+}
+
+impl SegmentSpec {
+    pub fn parse(input: &TaskbarSegmentConfig) -> Result<Self, String> {
+        let kind = SegmentKind::parse(&input.kind)?;
+        let label = input
+            .label
+            .as_deref()
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(str::to_string);
+        let name = label.as_deref().unwrap_or(kind.as_str()).to_string();
+        let disk = input
+            .disk
+            .as_deref()
+            .map(str::trim)
+            .filter(|disk| !disk.is_empty())
+            .map(str::to_string);
+        if kind.is_disk() && disk.is_none() {
+            return Err(format!("segment `{name}`: {} needs a disk", kind.as_str()));
+        }
+        let template = |field: &str, value: Option<&str>| -> Result<Option<Template>, String> {
+            match value.map(str::trim) {
+                Some(value) if !value.is_empty() => Template::parse(value)
+                    .map(Some)
+                    .map_err(|e| format!("segment `{name}` {field}: {e}")),
+                _ => Ok(None),
+            }
+        };
+        let (value, extra) = if kind == SegmentKind::Custom {
+            let value = template("value", input.value.as_deref())?
+                .ok_or_else(|| format!("segment `{name}`: custom segments need a value"))?;
+            (Some(value), template("extra", input.extra.as_deref())?)
+        } else {
+            (None, None)
+        };
+        let fallback = match &value {
+            Some(value) => value.metrics().next().cloned(),
+            None => kind.primary_metric(disk.as_deref()),
+        };
+        let sparkline = input
+            .sparkline
+            .as_ref()
+            .map(|sparkline| SparklineSpec::parse(sparkline, fallback.as_ref()))
+            .transpose()
+            .map_err(|e| format!("segment `{name}` sparkline: {e}"))?;
+        Ok(Self {
+            kind,
+            label,
+            disk,
+            show_freq: input.show_freq,
+            show_temp: input.show_temp,
+            value,
+            extra,
+            sparkline,
+        })
+    }
+
+    fn render(
+        &self,
+        snapshot: &TelemetrySnapshot,
+        bands: &ThresholdBands,
+        units: &UnitFormatter,
+        language: &str,
+    ) -> TaskbarSegment {
+        let mut segment = match (&self.value, self.kind) {
+            (Some(value), _) => {
+                let base = value
+                    .metrics()
+                    .next()
+                    .map(base_tone)
+                    .unwrap_or(SegmentTone::Normal);
+                let metrics: Vec<&MetricPath> = value
+                    .metrics()
+                    .chain(self.extra.iter().flat_map(Template::metrics))
+                    .collect();
+                let (value_tone, level) = banded_tone(base, bands, metrics, snapshot);
+                TaskbarSegment {
+                    label: String::new(),
+                    value: value.render(snapshot, units),
+                    extra: self
+                        .extra
+                        .as_ref()
+                        .map(|extra| extra.render(snapshot, units)),
+                    value_tone,
+                    level,
+                    sparkline: None,
+                }
+            }
+            (None, kind) => self.render_builtin(kind, snapshot, bands, units, language),
+        };
+        if let Some(label) = &self.label {
+            segment.label = label.clone();
+        }
+        segment.sparkline = self.sparkline.clone();
+        segment
+    }
+
+    fn render_builtin(
+        &self,
+        kind: SegmentKind,
+        snapshot: &TelemetrySnapshot,
+        bands: &ThresholdBands,
+        units: &UnitFormatter,
+        language: &str,
+    ) -> TaskbarSegment {
+        let zh = language == "zh-CN";
+        let disk = self.disk.as_deref().unwrap_or_default();
+        let primary = kind.primary_metric(self.disk.as_deref());
+        let value_of =
+            |metric: &Option<MetricPath>| metric.as_ref().and_then(|metric| metric.value(snapshot));
+        let mut metrics: Vec<MetricPath> = primary.iter().cloned().collect();
+        let mut extras = Vec::new();
+        let (label, value, base) = match kind {
+            SegmentKind::Cpu => {
+                if self.show_freq {
+                    if let Some(freq) = snapshot.cpu.frequency_mhz {
+                        extras.push(format!("{}MHz", units.number(freq as f64, 0)));
+                    }
+                }
+                if self.show_temp {
+                    if let Some(temp) = snapshot.cpu.temperature_c {
+                        extras.push(units.temperature(temp, 0));
+                    }
+                    metrics.push(MetricPath::CpuTemp);
+                }
+                (
+                    "CPU".to_string(),
+                    units.percent(snapshot.cpu.usage_pct, 0),
+                    SegmentTone::Cyan,
+                )
+            }
+            SegmentKind::Gpu => {
+                if self.show_temp {
+                    if let Some(temp) = snapshot.gpu.temperature_c {
+                        extras.push(units.temperature(temp, 0));
+                    }
+                    metrics.push(MetricPath::GpuTemp);
+                }
+                (
+                    "GPU".to_string(),
+                    units.percent(snapshot.gpu.usage_pct.unwrap_or(0.0), 0),
+                    SegmentTone::Pink,
+                )
+            }
+            SegmentKind::Memory => (
+                "RAM".to_string(),
+                units.percent(snapshot.memory.usage_pct, 0),
+                SegmentTone::Cyan,
+            ),
+            SegmentKind::App => {
+                if let Some(mem) = snapshot.app_memory_mb {
+                    extras.push(units.bytes(mem * MIB, 0));
+                }
+                metrics.push(MetricPath::AppMem);
+                (
+                    "APP".to_string(),
+                    units.percent(snapshot.app_cpu_usage_pct.unwrap_or(0.0), 1),
+                    SegmentTone::Cyan,
+                )
+            }
+            SegmentKind::Down => (
+                "↓".to_string(),
+                units.rate(snapshot.network.download_bytes_per_sec, 1),
+                SegmentTone::Normal,
+            ),
+            SegmentKind::Up => (
+                "↑".to_string(),
+                units.rate(snapshot.network.upload_bytes_per_sec, 1),
+                SegmentTone::Normal,
+            ),
+            SegmentKind::Latency => (
+                if zh { "延迟" } else { "LAT" }.to_string(),
+                format_latency(snapshot.network.latency_ms, units, language),
+                SegmentTone::Normal,
+            ),
+            SegmentKind::GpuMemory => (
+                if zh { "显存" } else { "VRAM" }.to_string(),
+                value_of(&primary)
+                    .map(|mb| units.bytes(mb * MIB, 1))
+                    .unwrap_or_else(|| unavailable(language)),
+                SegmentTone::Pink,
+            ),
+            SegmentKind::Power => (
+                if zh { "功耗" } else { "PWR" }.to_string(),
+                value_of(&primary)
+                    .map(|watts| format!("{}W", units.number(watts, 0)))
+                    .unwrap_or_else(|| unavailable(language)),
+                SegmentTone::Normal,
+            ),
+            SegmentKind::Swap => (
+                if zh { "交换" } else { "SWAP" }.to_string(),
+                value_of(&primary)
+                    .map(|pct| units.percent(pct, 0))
+                    .unwrap_or_else(|| unavailable(language)),
+                SegmentTone::Cyan,
+            ),
+            SegmentKind::DiskUsage => (
+                disk.to_string(),
+                value_of(&primary)
+                    .map(|pct| units.percent(pct, 0))
+                    .unwrap_or_else(|| unavailable(language)),
+                SegmentTone::Cyan,
+            ),
+            SegmentKind::DiskRead | SegmentKind::DiskWrite => {
+                let suffix = match (kind == SegmentKind::DiskRead, zh) {
+                    (true, true) => "读",
+                    (true, false) => "R",
+                    (false, true) => "写",
+                    (false, false) => "W",
+                };
+                (
+                    format!("{disk} {suffix}"),
+                    value_of(&primary)
+                        .map(|rate| units.rate(rate, 1))
+                        .unwrap_or_else(|| unavailable(language)),
+                    SegmentTone::Normal,
+                )
+            }
+            SegmentKind::Custom => (String::new(), String::new(), SegmentTone::Normal),
+        };
+        let (value_tone, level) = banded_tone(base, bands, &metrics, snapshot);
+        TaskbarSegment {
+            label,
+            value,
+            extra: (!extras.is_empty()).then(|| extras.join(" ")),
+            value_tone,
+            level,
+            sparkline: None,
+        }
+    }
+}
+
+/// The configured segments, or for a config from before `segments` existed, the ones its
+/// legacy templates or `show*` toggles describe.
+pub fn segment_configs(config: &NativeTaskbarConfig) -> Cow<'_, [TaskbarSegmentConfig]> {
+    if !config.segments.is_empty() {
+        return Cow::Borrowed(&config.segments);
+    }
+    if !config.segment_templates.is_empty() {
+        return Cow::Owned(
+            config
+                .segment_templates
+                .iter()
+                .map(|template| TaskbarSegmentConfig {
+                    label: Some(template.label.clone()),
+                    value: Some(template.value.clone()),
+                    extra: template.extra.clone(),
+                    sparkline: template.sparkline.clone(),
+                    ..TaskbarSegmentConfig::new("custom")
+                })
+                .collect(),
+        );
+    }
+    let toggle = |kind: &str, enabled: bool| TaskbarSegmentConfig {
+        enabled,
+        ..TaskbarSegmentConfig::new(kind)
+    };
+    Cow::Owned(vec![
+        TaskbarSegmentConfig {
+            show_freq: config.show_cpu_freq,
+            show_temp: config.show_cpu_temp,
+            ..toggle("cpu", config.show_cpu)
+        },
+        TaskbarSegmentConfig {
+            show_temp: config.show_gpu_temp,
+            ..toggle("gpu", config.show_gpu)
+        },
+        toggle("memory", config.show_memory),
+        toggle("app", config.show_app),
+        toggle("down", config.show_down),
+        toggle("up", config.show_up),
+        toggle("latency", config.show_latency),
+    ])
+}
+
+/// Moves a legacy config onto `segments` and mirrors the result back into the `show*`
+/// toggles, which the webview taskbar still reads.
+pub fn migrate_segments(config: &mut NativeTaskbarConfig) {
+    if config.segments.is_empty() {
+        config.segments = segment_configs(config).into_owned();
+    }
+    config.segment_templates.clear();
+    sync_legacy_toggles(config);
+}
+
+/// Applies the `show*` toggles to the first segment of each kind, adding segments that are
+/// switched on but missing; the reverse of [`sync_legacy_toggles`] for front ends that only
+/// know the toggles.
+pub fn apply_legacy_toggles(config: &mut NativeTaskbarConfig) {
+    let toggles = [
+        (SegmentKind::Cpu, config.show_cpu),
+        (SegmentKind::Gpu, config.show_gpu),
+        (SegmentKind::Memory, config.show_memory),
+        (SegmentKind::App, config.show_app),
+        (SegmentKind::Down, config.show_down),
+        (SegmentKind::Up, config.show_up),
+        (SegmentKind::Latency, config.show_latency),
+    ];
+    for (kind, enabled) in toggles {
+        let index = match config
+            .segments
+            .iter()
+            .position(|segment| SegmentKind::parse(&segment.kind).ok() == Some(kind))
+        {
+            Some(index) => index,
+            None if enabled => {
+                config
+                    .segments
+                    .push(TaskbarSegmentConfig::new(kind.as_str()));
+                config.segments.len() - 1
+            }
+            None => continue,
+        };
+        let segment = &mut config.segments[index];
+        segment.enabled = enabled;
+        match kind {
+            SegmentKind::Cpu => {
+                segment.show_freq = config.show_cpu_freq;
+                segment.show_temp = config.show_cpu_temp;
+            }
+            SegmentKind::Gpu => segment.show_temp = config.show_gpu_temp,
+            _ => {}
+        }
+    }
+}
+
+/// Sets each `show*` toggle from the first segment of its kind.
+pub fn sync_legacy_toggles(config: &mut NativeTaskbarConfig) {
+    let find = |kind: SegmentKind| {
+        config
+            .segments
+            .iter()
+            .find(|segment| SegmentKind::parse(&segment.kind).ok() == Some(kind))
+            .filter(|segment| segment.enabled)
+    };
+    let cpu = find(SegmentKind::Cpu);
+    let gpu = find(SegmentKind::Gpu);
+    let (show_cpu, show_cpu_freq, show_cpu_temp) = (
+        cpu.is_some(),
+        cpu.is_some_and(|cpu| cpu.show_freq),
+        cpu.is_some_and(|cpu| cpu.show_temp),
+    );
+    let (show_gpu, show_gpu_temp) = (gpu.is_some(), gpu.is_some_and(|gpu| gpu.show_temp));
+    let show_memory = find(SegmentKind::Memory).is_some();
+    let show_app = find(SegmentKind::App).is_some();
+    let show_down = find(SegmentKind::Down).is_some();
+    let show_up = find(SegmentKind::Up).is_some();
+    let show_latency = find(SegmentKind::Latency).is_some();
+    config.show_cpu = show_cpu;
+    config.show_cpu_freq = show_cpu_freq;
+    config.show_cpu_temp = show_cpu_temp;
+    config.show_gpu = show_gpu;
+    config.show_gpu_temp = show_gpu_temp;
+    config.show_memory = show_memory;
+    config.show_app = show_app;
+    config.show_down = show_down;
+    config.show_up = show_up;
+    config.show_latency = show_latency;
+}
+
+/// Checks every segment, so a bad one is rejected when it is saved rather than silently
+/// dropped on every refresh.
+pub fn validate_segments(segments: &[TaskbarSegmentConfig]) -> Result<(), String> {
+    segments
+        .iter()
+        .try_for_each(|segment| SegmentSpec::parse(segment).map(|_| ()))
+}
+
+/// A metric's color while it is inside its normal band.
+fn base_tone(metric: &MetricPath) -> SegmentTone {
+    match metric {
+        MetricPath::CpuUsage | MetricPath::MemUsage | MetricPath::AppCpu | MetricPath::AppMem => {
+            SegmentTone::Cyan
+        }
+        MetricPath::GpuUsage => SegmentTone::Pink,
+        _ => SegmentTone::Normal,
+    }
+}
+
+/// `base` while every metric is normal, otherwise the worst band's color (orange for warn and
+/// red for critical unless the band sets its own).
+fn banded_tone<'a>(
+    base: SegmentTone,
+    bands: &ThresholdBands,
+    metrics: impl IntoIterator<Item = &'a MetricPath>,
+    snapshot: &TelemetrySnapshot,
+) -> (SegmentTone, ThresholdLevel) {
+    let (level, color) = bands.worst(metrics, snapshot);
+    let tone = match level {
+        ThresholdLevel::Normal => base,
+        ThresholdLevel::Warn => color.map_or(SegmentTone::Orange, SegmentTone::Custom),
+        ThresholdLevel::Critical => color.map_or(SegmentTone::Red, SegmentTone::Custom),
+    };
+    (tone, level)
+}
+
+fn unavailable(language: &str) -> String {
+    if language == "zh-CN" {
+        "暂无".to_string()
+    } else {
+        "N/A".to_string()
+    }
+}
+
+fn format_latency(latency_ms: Option<f64>, units: &UnitFormatter, language: &str) -> String {
+    match latency_ms {
+        Some(value) if value.is_finite() => format!("{}ms", units.number(value, 0)),
+        _ => unavailable(language),
+    }
+}
+
+/// A [`NativeTaskbarConfig`] parsed for rendering: the shown segments with their templates,
+/// the threshold bands and the unit style. Build it when the config changes and reuse it on
+/// every refresh; invalid segments are dropped here.
+#[derive(Debug, Clone)]
+pub struct TaskbarLayout {
+    segments: Vec<SegmentSpec>,
+    bands: ThresholdBands,
+    units: UnitFormatter,
+    language: String,
+}
+
+impl TaskbarLayout {
+    pub fn new(config: &NativeTaskbarConfig) -> Self {
+        Self {
+            segments: segment_configs(config)
+                .iter()
+                .filter(|segment| segment.enabled)
+                .filter_map(|segment| SegmentSpec::parse(segment).ok())
+                .collect(),
+            bands: ThresholdBands::lenient(&config.thresholds),
+            units: UnitFormatter::lenient(&config.units),
+            language: config.language.clone(),
+        }
+    }
+
+    /// The segments the taskbar shows for `snapshot`, in the configured order.
+    pub fn build_segments(&self, snapshot: &TelemetrySnapshot) -> Vec<TaskbarSegment> {
+        let mut parts: Vec<TaskbarSegment> = self
+            .segments
+            .iter()
+            .map(|spec| spec.render(snapshot, &self.bands, &self.units, &self.language))
+            .collect();
+        if parts.is_empty() {
+            parts.push(TaskbarSegment {
+                label: "PulseCoreLite".to_string(),
+                value: String::new(),
+                extra: None,
+                value_tone: SegmentTone::Muted,
+                level: ThresholdLevel::Normal,
+                sparkline: None,
+            });
+        }
+        parts
+    }
+
+    /// The sparklines the shown segments draw, so their metrics' history can be kept.
+    pub fn sparkline_specs(&self) -> Vec<SparklineSpec> {
+        self.segments
+            .iter()
+            .filter_map(|spec| spec.sparkline.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{sparkline::SparklineStyle, test_support},
+        types::{MetricThreshold, SparklineConfig, TaskbarSegmentTemplate},
+    };
+
+    fn snapshot() -> TelemetrySnapshot {
+        let mut snapshot = test_support::snapshot();
+        snapshot.cpu.usage_pct = 93.0;
+        snapshot.cpu.temperature_c = Some(71.0);
+        snapshot.gpu.memory_used_mb = Some(3276.8);
+        snapshot.gpu.memory_total_mb = Some(8192.0);
+        snapshot.memory.swap_used_mb = Some(512.0);
+        snapshot.memory.swap_total_mb = Some(2048.0);
+        let mut disk = test_support::disk("C:\\", 100.0, 200.0);
+        disk.label = "System".to_string();
+        disk.read_bytes_per_sec = Some(1_048_576.0);
+        snapshot.disks.push(disk);
+        snapshot.network.download_bytes_per_sec = 2048.0;
+        snapshot.power_watts = Some(42.4);
+        snapshot
+    }
+
+    fn build_segments(
+        snapshot: &TelemetrySnapshot,
+        config: &NativeTaskbarConfig,
+    ) -> Vec<TaskbarSegment> {
+        TaskbarLayout::new(config).build_segments(snapshot)
+    }
+
+    fn segment(kind: &str, disk: Option<&str>) -> TaskbarSegmentConfig {
+        TaskbarSegmentConfig {
+            disk: disk.map(str::to_string),
+            ..TaskbarSegmentConfig::new(kind)
+        }
+    }
+
+    /// A config saved before the segment list existed.
+    fn legacy_config() -> NativeTaskbarConfig {
+        serde_json::from_value(serde_json::json!({
+            "showCpu": true, "showCpuFreq": false, "showCpuTemp": true, "showGpu": false,
+            "showGpuTemp": true, "showMemory": true, "showApp": false, "showDown": true,
+            "showUp": true, "showLatency": false
+        }))
+        .unwrap()
+    }
+
+    fn migrated_legacy_config() -> NativeTaskbarConfig {
+        let mut config = legacy_config();
+        migrate_segments(&mut config);
+        config
+    }
+
+    #[test]
+    fn legacy_toggles_render_before_migration() {
+        let config = legacy_config();
+        assert!(config.segments.is_empty());
+        assert_eq!(build_segments(&snapshot(), &config).len(), 4);
+    }
+
+    #[test]
+    fn legacy_toggles_migrate_to_segments() {
+        let config = migrated_legacy_config();
+        let kinds: Vec<(&str, bool)> = config
+            .segments
+            .iter()
+            .map(|segment| (segment.kind.as_str(), segment.enabled))
+            .collect();
+        assert_eq!(kinds[0], ("cpu", true));
+        assert_eq!(kinds[1], ("gpu", false));
+        assert!(config.segments[0].show_temp && !config.segments[0].show_freq);
+    }
+
+    #[test]
+    fn legacy_toggles_follow_the_segment_list() {
+        let mut config = migrated_legacy_config();
+        config.segments[3].enabled = true;
+        config.segments.swap(0, 2);
+        sync_legacy_toggles(&mut config);
+        assert!(config.show_app && config.show_cpu && config.show_cpu_temp);
+
+        let segments = build_segments(&snapshot(), &config);
+        assert_eq!(segments[0].label, "RAM");
+        assert_eq!(segments[1].extra.as_deref(), Some("71°C"));
+    }
+
+    #[test]
+    fn toggles_from_the_front_end_keep_the_segment_order() {
+        let mut config = migrated_legacy_config();
+        config.segments.swap(0, 2);
+        config.show_memory = false;
+        config.show_latency = true;
+        apply_legacy_toggles(&mut config);
+        assert_eq!(config.segments[0].kind, "memory");
+        assert!(!config.segments[0].enabled);
+        assert!(config.segments[6].enabled);
+    }
+
+    #[test]
+    fn segment_templates_migrate_to_custom_segments() {
+        let mut config = NativeTaskbarConfig {
+            segments: Vec::new(),
+            segment_templates: vec![TaskbarSegmentTemplate {
+                label: "CPU".to_string(),
+                value: "{cpu.usage:.0}%".to_string(),
+                extra: None,
+                sparkline: Some(SparklineConfig::default()),
+            }],
+            ..NativeTaskbarConfig::default()
+        };
+        migrate_segments(&mut config);
+        assert!(config.segment_templates.is_empty());
+        assert_eq!(config.segments[0].kind, "custom");
+
+        let spec = &TaskbarLayout::new(&config).sparkline_specs()[0];
+        assert_eq!(spec.metric, MetricPath::CpuUsage);
+        assert_eq!(spec.style, SparklineStyle::Line);
+    }
+
+    #[test]
+    fn validation_rejects_incomplete_and_unknown_segments() {
+        assert!(validate_segments(&[segment("diskRead", Some("C:"))]).is_ok());
+        assert!(validate_segments(&[segment("diskRead", None)]).is_err());
+        assert!(validate_segments(&[segment("custom", None)]).is_err());
+        assert!(validate_segments(&[segment("fans", None)]).is_err());
+    }
+
+    fn all_kinds_config() -> NativeTaskbarConfig {
+        NativeTaskbarConfig {
+            segments: vec![
+                TaskbarSegmentConfig {
+                    show_temp: true,
+                    ..segment("cpu", None)
+                },
+                segment("gpuMemory", None),
+                segment("power", None),
+                segment("swap", None),
+                segment("diskUsage", Some("C:")),
+                segment("diskRead", Some("C:")),
+                segment("diskWrite", Some("C:")),
+                TaskbarSegmentConfig {
+                    label: Some("NET".to_string()),
+                    value: Some("{net.down|speed}".to_string()),
+                    ..segment("custom", None)
+                },
+            ],
+            ..NativeTaskbarConfig::default()
+        }
+    }
+
+    #[test]
+    fn segment_kinds_render_labels_and_values() {
+        let config = all_kinds_config();
+        validate_segments(&config.segments).unwrap();
+
+        let segments = build_segments(&snapshot(), &config);
+        let rendered: Vec<(&str, &str)> = segments
+            .iter()
+            .map(|segment| (segment.label.as_str(), segment.value.as_str()))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                ("CPU", "93%"),
+                ("VRAM", "3.2 GB"),
+                ("PWR", "42W"),
+                ("SWAP", "25%"),
+                ("C:", "50%"),
+                ("C: R", "1.0 MB/s"),
+                ("C: W", "N/A"),
+                ("NET", "2.0 KB/s"),
+            ]
+        );
+    }
+
+    #[test]
+    fn segment_kinds_render_with_threshold_bands() {
+        let mut config = all_kinds_config();
+        let segments = build_segments(&snapshot(), &config);
+        assert_eq!(segments.len(), config.segments.len());
+        assert_eq!(segments[0].value_tone, SegmentTone::Red);
+        assert_eq!(segments[7].value_tone, SegmentTone::Normal);
+
+        config.thresholds.push(MetricThreshold {
+            metric: "net.down".to_string(),
+            warn: 1024.0,
+            critical: 1024.0 * 1024.0,
+            warn_color: Some("#00ff00".to_string()),
+            critical_color: None,
+        });
+        let segments = build_segments(&snapshot(), &config);
+        assert_eq!(segments[7].level, ThresholdLevel::Warn);
+        assert_eq!(segments[7].value_tone, SegmentTone::Custom([0, 255, 0]));
+    }
+}
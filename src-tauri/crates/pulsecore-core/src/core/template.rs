@@ -0,0 +1,292 @@
+use crate::{
+    core::{metric_path::MetricPath, units::UnitFormatter},
+    types::TelemetrySnapshot,
+};
+
+/// The main window title when the user has not configured one.
+pub const DEFAULT_WINDOW_TITLE_TEMPLATE: &str =
+    "PulseCore | CPU {cpu.usage:.0}% | RAM {mem.usage:.0}% | Down {net.down|speed}";
+
+/// Rendered in place of a metric the snapshot does not have (no GPU, no sensor, ...).
+const MISSING_VALUE: &str = "N/A";
+const MAX_PRECISION: usize = 6;
+const FILTER_NAMES: &str = "kb, mb, gb, ghz, speed or temp";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Filter {
+    /// Divides the value: `kb`/`mb`/`gb` from bytes, `ghz` from MHz.
+    Scale(f64),
+    /// Bytes per second with an automatic unit (`B/s` .. `GB/s`); always the last filter.
+    Speed,
+    /// Celsius in the configured temperature scale, with its `°C`/`°F`; always the last filter.
+    Temp,
+}
+
+impl Filter {
+    fn parse(name: &str) -> Option<Self> {
+        let filter = match name.trim().to_ascii_lowercase().as_str() {
+            "kb" => Self::Scale(1024.0),
+            "mb" => Self::Scale(1024.0 * 1024.0),
+            "gb" => Self::Scale(1024.0 * 1024.0 * 1024.0),
+            "ghz" => Self::Scale(1000.0),
+            "speed" => Self::Speed,
+            "temp" => Self::Temp,
+            _ => return None,
+        };
+        Some(filter)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Placeholder {
+    metric: MetricPath,
+    filters: Vec<Filter>,
+    precision: Option<usize>,
+}
+
+impl Placeholder {
+    /// `path(|filter)*(:.N)?`; `column` is where the body starts, for error messages.
+    fn parse(body: &str, column: usize) -> Result<Self, String> {
+        let at = |message: String| format!("template column {column}: {message}");
+        // Disk ids may contain `:` (`disk.C:.usage`), so only a trailing `:.<digits>` is a spec.
+        let spec = body.rsplit_once(':').and_then(|(head, spec)| {
+            let digits = spec.trim().strip_prefix('.')?;
+            (!digits.is_empty() && digits.chars().all(|ch| ch.is_ascii_digit()))
+                .then_some((head, digits))
+        });
+        let (head, precision) = match spec {
+            Some((head, digits)) => {
+                let precision = digits
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|precision| *precision <= MAX_PRECISION)
+                    .ok_or_else(|| {
+                        at(format!(
+                            "invalid precision `:.{digits}` (expected :.0 to :.{MAX_PRECISION})"
+                        ))
+                    })?;
+                (head, Some(precision))
+            }
+            None => (body, None),
+        };
+
+        let mut pieces = head.split('|');
+        let path = pieces.next().unwrap_or_default().trim();
+        if path.is_empty() {
+            return Err(at("empty placeholder".to_string()));
+        }
+        let metric = MetricPath::parse(path).map_err(at)?;
+        let mut filters = Vec::new();
+        let mut previous = "";
+        for name in pieces {
+            if matches!(filters.last(), Some(Filter::Speed | Filter::Temp)) {
+                return Err(at(format!("`{previous}` must be the last filter")));
+            }
+            let filter = Filter::parse(name).ok_or_else(|| {
+                at(format!(
+                    "unknown filter `{}` (expected {FILTER_NAMES})",
+                    name.trim()
+                ))
+            })?;
+            filters.push(filter);
+            previous = name.trim();
+        }
+        Ok(Self {
+            metric,
+            filters,
+            precision,
+        })
+    }
+
+    fn render(&self, snapshot: &TelemetrySnapshot, units: &UnitFormatter) -> String {
+        let Some(mut value) = self.metric.value(snapshot) else {
+            return MISSING_VALUE.to_string();
+        };
+        let units = match self.precision {
+            Some(precision) => units.with_precision(precision),
+            None => *units,
+        };
+        for filter in &self.filters {
+            match filter {
+                Filter::Scale(divisor) => value /= divisor,
+                Filter::Speed => return units.rate(value, 1),
+                Filter::Temp => return units.temperature(value, 1),
+            }
+        }
+        match self.precision {
+            // Integers (frequencies, counts) print bare; everything else gets one decimal.
+            None if value.fract() == 0.0 => units.with_precision(0).number(value, 0),
+            _ => units.number(value, 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Text(String),
+    Value(Placeholder),
+}
+
+/// A parsed display template such as `{cpu.usage:.0}% {cpu.temp}°C` or `{net.down|speed}`.
+///
+/// Placeholders hold a metric path (see [`crate::core::metric_path::METRIC_PATHS`]), optional
+/// `|filter`s and an optional `:.N` precision; `{{` and `}}` are literal braces.
+#[derive(Debug, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.chars().enumerate().peekable();
+        while let Some((index, ch)) = chars.next() {
+            let column = index + 1;
+            match ch {
+                '{' if chars.peek().map(|(_, next)| *next) == Some('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek().map(|(_, next)| *next) == Some('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => {
+                    return Err(format!(
+                    "template column {column}: unmatched `}}` (write `}}}}` for a literal brace)"
+                ))
+                }
+                '{' => {
+                    let mut body = String::new();
+                    let mut closed = false;
+                    for (_, ch) in chars.by_ref() {
+                        match ch {
+                            '}' => {
+                                closed = true;
+                                break;
+                            }
+                            '{' => {
+                                return Err(format!(
+                                    "template column {column}: placeholders cannot be nested"
+                                ))
+                            }
+                            _ => body.push(ch),
+                        }
+                    }
+                    if !closed {
+                        return Err(format!("template column {column}: unclosed `{{`"));
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Value(Placeholder::parse(&body, column + 1)?));
+                }
+                _ => text.push(ch),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Self { parts })
+    }
+
+    pub fn render(&self, snapshot: &TelemetrySnapshot, units: &UnitFormatter) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Value(placeholder) => placeholder.render(snapshot, units),
+            })
+            .collect()
+    }
+
+    /// The metrics the template shows, in order; the first decides a taskbar segment's color.
+    pub fn metrics(&self) -> impl Iterator<Item = &MetricPath> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Value(placeholder) => Some(&placeholder.metric),
+            Part::Text(_) => None,
+        })
+    }
+}
+
+/// The main window title template: the configured one, or the default when none is set or the
+/// stored one no longer parses. Parse it when the setting changes rather than on every render.
+pub fn window_title_template(template: Option<&str>) -> Template {
+    template
+        .map(str::trim)
+        .filter(|template| !template.is_empty())
+        .and_then(|template| Template::parse(template).ok())
+        .unwrap_or_else(|| {
+            Template::parse(DEFAULT_WINDOW_TITLE_TEMPLATE).expect("default title template parses")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support;
+
+    fn snapshot() -> TelemetrySnapshot {
+        let mut snapshot = test_support::snapshot();
+        snapshot.cpu.usage_pct = 12.44;
+        snapshot.cpu.frequency_mhz = Some(3600);
+        snapshot.cpu.temperature_c = Some(54.6);
+        snapshot.network.download_bytes_per_sec = 3_145_728.0;
+        snapshot.network.upload_bytes_per_sec = 512.0;
+        snapshot
+    }
+
+    fn render(source: &str) -> String {
+        Template::parse(source)
+            .unwrap()
+            .render(&snapshot(), &UnitFormatter::default())
+    }
+
+    #[test]
+    fn renders_placeholders_with_precision() {
+        assert_eq!(render("{cpu.usage:.0}% {cpu.temp}°C"), "12% 54.6°C");
+        assert_eq!(render("{mem.used} MB"), "4096 MB");
+        assert_eq!(render("GPU {gpu.temp}"), "GPU N/A");
+    }
+
+    #[test]
+    fn renders_filters() {
+        assert_eq!(
+            render("{net.down|speed} / {net.up|speed}"),
+            "3.0 MB/s / 512 B/s"
+        );
+        assert_eq!(render("{cpu.freq|ghz:.2} GHz"), "3.60 GHz");
+        assert_eq!(render("{cpu.temp|temp:.0}"), "55°C");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(render("{{cpu.usage}}"), "{cpu.usage}");
+    }
+
+    #[test]
+    fn window_title_falls_back_to_the_default_template() {
+        let title = |template| {
+            window_title_template(template).render(&snapshot(), &UnitFormatter::default())
+        };
+        let default = "PulseCore | CPU 12% | RAM 25% | Down 3.0 MB/s";
+        assert_eq!(title(None), default);
+        assert_eq!(title(Some("  ")), default);
+        assert_eq!(title(Some("{cpu.bogus}")), default);
+        assert_eq!(title(Some("CPU {cpu.usage:.1}")), "CPU 12.4");
+    }
+
+    #[test]
+    fn reports_errors_with_columns() {
+        let error = |source: &str| Template::parse(source).unwrap_err();
+        assert!(error("CPU {cpu.usage").contains("column 5: unclosed"));
+        assert!(error("a } b").contains("column 3: unmatched"));
+        assert!(error("{cpu.bogus}").contains("unknown metric path"));
+        assert!(error("{net.down|kbps}").contains("unknown filter `kbps`"));
+        assert!(error("{net.down|speed|mb}").contains("`speed` must be the last"));
+        assert!(error("{cpu.usage:.9}").contains("invalid precision"));
+        assert!(error("{ }").contains("empty placeholder"));
+        assert!(Template::parse("{disk.C:.usage:.1}").is_ok());
+    }
+}
@@ -0,0 +1,102 @@
+//! Shared fixtures for tests in this crate and its dependents (the `test-support` feature).
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    core::profile::{classify_process, ProcessSample, ProfileSample},
+    types::{
+        CpuMetrics, DiskMetrics, GpuMetrics, MemoryMetrics, NetworkMetrics, TelemetrySnapshot,
+    },
+};
+
+/// An idle machine at `2026-01-01T00:00:00Z`: 10% CPU, 4 of 16 GB memory in use and nothing
+/// else reported. Tests set the fields they look at.
+pub fn snapshot() -> TelemetrySnapshot {
+    TelemetrySnapshot {
+        timestamp: epoch(),
+        cpu: CpuMetrics {
+            usage_pct: 10.0,
+            frequency_mhz: None,
+            temperature_c: None,
+        },
+        gpu: GpuMetrics {
+            usage_pct: None,
+            temperature_c: None,
+            memory_used_mb: None,
+            memory_total_mb: None,
+            frequency_mhz: None,
+        },
+        memory: MemoryMetrics {
+            used_mb: 4096.0,
+            total_mb: 16384.0,
+            usage_pct: 25.0,
+            swap_used_mb: None,
+            swap_total_mb: None,
+        },
+        disks: Vec::new(),
+        network: NetworkMetrics {
+            download_bytes_per_sec: 0.0,
+            upload_bytes_per_sec: 0.0,
+            latency_ms: None,
+        },
+        app_cpu_usage_pct: None,
+        app_memory_mb: None,
+        power_watts: None,
+    }
+}
+
+/// [`snapshot`] taken `secs` seconds later, with the given CPU usage.
+pub fn snapshot_at(secs: i64, cpu_usage_pct: f64) -> TelemetrySnapshot {
+    let mut snapshot = snapshot();
+    snapshot.timestamp = epoch() + Duration::seconds(secs);
+    snapshot.cpu.usage_pct = cpu_usage_pct;
+    snapshot
+}
+
+/// A disk with no I/O reported.
+pub fn disk(name: &str, used_gb: f64, total_gb: f64) -> DiskMetrics {
+    DiskMetrics {
+        name: name.to_string(),
+        label: String::new(),
+        used_gb,
+        total_gb,
+        usage_pct: used_gb / total_gb * 100.0,
+        read_bytes_per_sec: None,
+        write_bytes_per_sec: None,
+    }
+}
+
+/// A profile line for `snapshot`, sampled every second by an app running as pid 1, with no
+/// processes or windows recorded.
+pub fn profile_sample(snapshot: TelemetrySnapshot) -> ProfileSample {
+    ProfileSample {
+        timestamp: snapshot.timestamp,
+        app_pid: 1,
+        refresh_rate_ms: 1000,
+        snapshot,
+        processes: Vec::new(),
+        windows: Vec::new(),
+    }
+}
+
+/// A child of pid 1 at 1% CPU, classified by name, with nothing else reported.
+pub fn process(pid: u32, name: &str, memory_mb: f64) -> ProcessSample {
+    ProcessSample {
+        pid,
+        name: name.to_string(),
+        parent_pid: Some(1),
+        memory_mb,
+        cpu_pct: 1.0,
+        kind: classify_process(name),
+        thread_count: None,
+        handle_count: None,
+        disk_read_bytes_per_sec: None,
+        disk_write_bytes_per_sec: None,
+        start_time: None,
+        command_line: None,
+    }
+}
+
+fn epoch() -> DateTime<Utc> {
+    "2026-01-01T00:00:00Z".parse().expect("valid timestamp")
+}
@@ -0,0 +1,232 @@
+use crate::{
+    core::metric_path::MetricPath,
+    types::{MetricThreshold, TelemetrySnapshot},
+};
+
+pub type Rgb = [u8; 3];
+
+/// How far past its bands a metric is; ordered so the worst of several is the max.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThresholdLevel {
+    #[default]
+    Normal,
+    Warn,
+    Critical,
+}
+
+impl ThresholdLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Warn => "warn",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// Parses `#rgb` or `#rrggbb`.
+pub fn parse_color(value: &str) -> Result<Rgb, String> {
+    let invalid = || format!("invalid color: {value} (expected #rgb or #rrggbb)");
+    let hex = value.trim().strip_prefix('#').ok_or_else(invalid)?;
+    if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+    match hex.len() {
+        3 => {
+            let mut rgb = [0u8; 3];
+            for (slot, digit) in rgb.iter_mut().zip(hex.chars()) {
+                *slot = channel(&digit.to_string())? * 17;
+            }
+            Ok(rgb)
+        }
+        6 => Ok([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        _ => Err(invalid()),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Band {
+    metric: MetricPath,
+    warn: f64,
+    critical: f64,
+    warn_color: Option<Rgb>,
+    critical_color: Option<Rgb>,
+}
+
+impl Band {
+    fn parse(input: &MetricThreshold) -> Result<Self, String> {
+        let metric = MetricPath::parse(&input.metric)?;
+        let label = input.metric.trim();
+        if !input.warn.is_finite() || !input.critical.is_finite() {
+            return Err(format!(
+                "threshold {label}: warn and critical must be numbers"
+            ));
+        }
+        if input.critical < input.warn {
+            return Err(format!(
+                "threshold {label}: critical ({}) is below warn ({})",
+                input.critical, input.warn
+            ));
+        }
+        let color = |value: &Option<String>| -> Result<Option<Rgb>, String> {
+            match value.as_deref().map(str::trim) {
+                Some(value) if !value.is_empty() => parse_color(value)
+                    .map(Some)
+                    .map_err(|e| format!("threshold {label}: {e}")),
+                _ => Ok(None),
+            }
+        };
+        Ok(Self {
+            metric,
+            warn: input.warn,
+            critical: input.critical,
+            warn_color: color(&input.warn_color)?,
+            critical_color: color(&input.critical_color)?,
+        })
+    }
+}
+
+/// Validated warn/critical bands, shared by the native taskbar, status bar output and (via
+/// `AppSettings::metric_thresholds`) the overlay. Values at or above a bound are in its band.
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdBands {
+    bands: Vec<Band>,
+}
+
+impl ThresholdBands {
+    pub fn parse(thresholds: &[MetricThreshold]) -> Result<Self, String> {
+        let mut bands: Vec<Band> = Vec::with_capacity(thresholds.len());
+        for threshold in thresholds {
+            let band = Band::parse(threshold)?;
+            if bands.iter().any(|other| other.metric == band.metric) {
+                return Err(format!(
+                    "threshold {}: metric is listed twice",
+                    threshold.metric.trim()
+                ));
+            }
+            bands.push(band);
+        }
+        Ok(Self { bands })
+    }
+
+    /// Like [`Self::parse`] but drops invalid entries; for configs validated when saved.
+    pub fn lenient(thresholds: &[MetricThreshold]) -> Self {
+        let mut bands: Vec<Band> = Vec::with_capacity(thresholds.len());
+        for band in thresholds.iter().filter_map(|t| Band::parse(t).ok()) {
+            if !bands.iter().any(|other| other.metric == band.metric) {
+                bands.push(band);
+            }
+        }
+        Self { bands }
+    }
+
+    /// The level of `metric` in `snapshot` and the custom color of that band, if any.
+    pub fn classify(
+        &self,
+        metric: &MetricPath,
+        snapshot: &TelemetrySnapshot,
+    ) -> (ThresholdLevel, Option<Rgb>) {
+        let Some(band) = self.bands.iter().find(|band| &band.metric == metric) else {
+            return (ThresholdLevel::Normal, None);
+        };
+        match metric.value(snapshot) {
+            Some(value) if value >= band.critical => {
+                (ThresholdLevel::Critical, band.critical_color)
+            }
+            Some(value) if value >= band.warn => (ThresholdLevel::Warn, band.warn_color),
+            _ => (ThresholdLevel::Normal, None),
+        }
+    }
+
+    /// The worst band among `metrics`; ties keep the first metric's color.
+    pub fn worst<'a>(
+        &self,
+        metrics: impl IntoIterator<Item = &'a MetricPath>,
+        snapshot: &TelemetrySnapshot,
+    ) -> (ThresholdLevel, Option<Rgb>) {
+        metrics
+            .into_iter()
+            .map(|metric| self.classify(metric, snapshot))
+            .fold((ThresholdLevel::Normal, None), |worst, next| {
+                if next.0 > worst.0 {
+                    next
+                } else {
+                    worst
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::test_support, types::default_metric_thresholds};
+
+    fn snapshot(cpu: f64, temp: f64) -> TelemetrySnapshot {
+        let mut snapshot = test_support::snapshot();
+        snapshot.cpu.usage_pct = cpu;
+        snapshot.cpu.temperature_c = Some(temp);
+        snapshot
+    }
+
+    #[test]
+    fn classifies_into_bands_with_colors() {
+        let mut thresholds = default_metric_thresholds();
+        thresholds[0].critical_color = Some("#f0f".to_string());
+        let bands = ThresholdBands::parse(&thresholds).unwrap();
+        let usage = MetricPath::CpuUsage;
+
+        assert_eq!(
+            bands.classify(&usage, &snapshot(40.0, 50.0)),
+            (ThresholdLevel::Normal, None)
+        );
+        assert_eq!(
+            bands.classify(&usage, &snapshot(80.0, 50.0)).0,
+            ThresholdLevel::Warn
+        );
+        assert_eq!(
+            bands.classify(&usage, &snapshot(95.0, 50.0)),
+            (ThresholdLevel::Critical, Some([255, 0, 255]))
+        );
+    }
+
+    #[test]
+    fn worst_picks_the_highest_level() {
+        let bands = ThresholdBands::parse(&default_metric_thresholds()).unwrap();
+        assert_eq!(
+            bands
+                .worst(
+                    [&MetricPath::CpuUsage, &MetricPath::CpuTemp],
+                    &snapshot(40.0, 99.0)
+                )
+                .0,
+            ThresholdLevel::Critical
+        );
+    }
+
+    #[test]
+    fn rejects_inverted_and_duplicate_bands() {
+        let mut inverted = default_metric_thresholds();
+        inverted[0].critical = inverted[0].warn - 1.0;
+        assert!(ThresholdBands::parse(&inverted).is_err());
+
+        let mut duplicated = default_metric_thresholds();
+        duplicated.push(duplicated[0].clone());
+        assert!(ThresholdBands::parse(&duplicated).is_err());
+        assert_eq!(
+            ThresholdBands::lenient(&duplicated).bands.len(),
+            duplicated.len() - 1
+        );
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert!(parse_color("#12345").is_err());
+        assert_eq!(parse_color("#ff8800").unwrap(), [255, 136, 0]);
+    }
+}
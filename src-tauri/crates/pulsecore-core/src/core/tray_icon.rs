@@ -0,0 +1,355 @@
+use std::{collections::HashMap, sync::Arc};
+
+use image::{Rgba, RgbaImage};
+
+use crate::{
+    core::{
+        metric_path::MetricPath,
+        thresholds::{Rgb, ThresholdBands, ThresholdLevel},
+        units::UnitFormatter,
+    },
+    types::{TelemetrySnapshot, TrayIconConfig},
+};
+
+/// Icons are drawn at 32x32; the shell scales them to the tray's size.
+pub const TRAY_ICON_SIZE: u32 = 32;
+/// Distinct icons kept before the cache starts over; a percentage needs at most ~300.
+const MAX_CACHED_ICONS: usize = 512;
+
+const WARN_COLOR: Rgb = [255, 165, 0];
+const CRITICAL_COLOR: Rgb = [255, 59, 59];
+
+/// 3x5 glyphs, one row per byte with the low three bits used; index 10 is `-`.
+const GLYPHS: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b000, 0b111, 0b000, 0b000],
+];
+const DASH: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrayIconStyle {
+    /// The rounded value as digits.
+    Value,
+    /// A vertical bar filled to value / max.
+    Bar,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrayTheme {
+    /// `None` leaves the icon transparent behind the digits or bar.
+    background: Option<Rgb>,
+    foreground: Rgb,
+    track: Rgb,
+    accent: Rgb,
+}
+
+impl TrayTheme {
+    fn parse(name: &str) -> Result<Self, String> {
+        let theme = match name.trim().to_ascii_lowercase().as_str() {
+            "dark" | "" => Self {
+                background: Some([24, 29, 38]),
+                foreground: [240, 243, 246],
+                track: [60, 68, 78],
+                accent: [0, 242, 255],
+            },
+            "light" => Self {
+                background: Some([245, 247, 250]),
+                foreground: [24, 29, 38],
+                track: [200, 206, 214],
+                accent: [0, 140, 200],
+            },
+            "transparent" => Self {
+                background: None,
+                foreground: [255, 255, 255],
+                track: [120, 128, 138],
+                accent: [0, 242, 255],
+            },
+            other => {
+                return Err(format!(
+                    "unknown tray icon theme: {other} (expected dark, light or transparent)"
+                ))
+            }
+        };
+        Ok(theme)
+    }
+}
+
+/// What an icon shows; two snapshots with the same key share one cached image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct IconKey {
+    /// The value as shown, in the preferred unit and rounded to a whole number (whole percent
+    /// of `max` for bars).
+    value: Option<i64>,
+    color: Option<Rgb>,
+}
+
+/// Renders the tray icon for a metric and hands out a new image only when it changes.
+#[derive(Debug)]
+pub struct TrayIconRenderer {
+    path: String,
+    metric: MetricPath,
+    style: TrayIconStyle,
+    theme: TrayTheme,
+    max: f64,
+    cache: HashMap<IconKey, Arc<RgbaImage>>,
+    last: Option<IconKey>,
+}
+
+impl TrayIconRenderer {
+    pub fn parse(config: &TrayIconConfig) -> Result<Self, String> {
+        let metric = MetricPath::parse(&config.metric)?;
+        let style = match config.style.trim().to_ascii_lowercase().as_str() {
+            "value" | "" => TrayIconStyle::Value,
+            "bar" => TrayIconStyle::Bar,
+            other => {
+                return Err(format!(
+                    "unknown tray icon style: {other} (expected value or bar)"
+                ))
+            }
+        };
+        Ok(Self {
+            path: config.metric.trim().to_string(),
+            metric,
+            style,
+            theme: TrayTheme::parse(&config.theme)?,
+            max: config
+                .max
+                .filter(|max| max.is_finite() && *max > 0.0)
+                .unwrap_or(100.0),
+            cache: HashMap::new(),
+            last: None,
+        })
+    }
+
+    /// The icon for `snapshot`, or `None` when it would look the same as the last one.
+    pub fn next_icon(
+        &mut self,
+        snapshot: &TelemetrySnapshot,
+        bands: &ThresholdBands,
+        units: &UnitFormatter,
+    ) -> Option<Arc<RgbaImage>> {
+        let value = self.metric.value(snapshot);
+        let (level, band_color) = bands.classify(&self.metric, snapshot);
+        let color = match level {
+            ThresholdLevel::Normal => None,
+            ThresholdLevel::Warn => Some(band_color.unwrap_or(WARN_COLOR)),
+            ThresholdLevel::Critical => Some(band_color.unwrap_or(CRITICAL_COLOR)),
+        };
+        let key = IconKey {
+            value: value.map(|value| match self.style {
+                TrayIconStyle::Value => {
+                    leading_number(&units.with_precision(0).metric(&self.path, value))
+                }
+                TrayIconStyle::Bar => (value / self.max * 100.0).round() as i64,
+            }),
+            color,
+        };
+        if self.last == Some(key) {
+            return None;
+        }
+        self.last = Some(key);
+        if self.cache.len() >= MAX_CACHED_ICONS && !self.cache.contains_key(&key) {
+            self.cache.clear();
+        }
+        let icon = self
+            .cache
+            .entry(key)
+            .or_insert_with(|| Arc::new(draw_icon(self.style, &self.theme, key)))
+            .clone();
+        Some(icon)
+    }
+}
+
+/// The whole number a formatted value starts with, e.g. `129` from `129°F` or `1234` from
+/// `1.234 MB`; with no decimals every separator inside the digits is digit grouping.
+fn leading_number(text: &str) -> i64 {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text),
+    };
+    let number: String = digits
+        .chars()
+        .take_while(|ch| ch.is_ascii_digit() || matches!(ch, ',' | '.' | '\u{a0}'))
+        .filter(char::is_ascii_digit)
+        .collect();
+    sign * number.parse::<i64>().unwrap_or(0)
+}
+
+fn rgba([r, g, b]: Rgb) -> Rgba<u8> {
+    Rgba([r, g, b, 255])
+}
+
+fn fill_rect(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn draw_icon(style: TrayIconStyle, theme: &TrayTheme, key: IconKey) -> RgbaImage {
+    let size = TRAY_ICON_SIZE;
+    let background = theme.background.map(rgba).unwrap_or(Rgba([0, 0, 0, 0]));
+    let mut image = RgbaImage::from_pixel(size, size, background);
+    if theme.background.is_some() {
+        // Knock out the corners for a slightly rounded tile.
+        for (x, y) in [(0, 0), (size - 1, 0), (0, size - 1), (size - 1, size - 1)] {
+            image.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+        }
+    }
+
+    match style {
+        TrayIconStyle::Value => {
+            let color = rgba(key.color.unwrap_or(theme.foreground));
+            let glyphs: Vec<usize> = match key.value {
+                Some(value) => value
+                    .clamp(0, 999)
+                    .to_string()
+                    .bytes()
+                    .map(|digit| (digit - b'0') as usize)
+                    .collect(),
+                None => vec![DASH, DASH],
+            };
+            let count = glyphs.len() as u32;
+            // Each glyph is 3 cells wide with a 1-cell gap; pick the largest cell that fits.
+            let cell = ((size - 2) / (count * 4 - 1)).clamp(1, (size - 2) / 5);
+            let text_width = (count * 4 - 1) * cell;
+            let left = (size - text_width) / 2;
+            let top = (size - 5 * cell) / 2;
+            for (index, glyph) in glyphs.iter().enumerate() {
+                let x0 = left + index as u32 * 4 * cell;
+                for (row, bits) in GLYPHS[*glyph].iter().enumerate() {
+                    for column in 0..3u32 {
+                        if bits & (0b100 >> column) != 0 {
+                            fill_rect(
+                                &mut image,
+                                x0 + column * cell,
+                                top + row as u32 * cell,
+                                cell,
+                                cell,
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        TrayIconStyle::Bar => {
+            let (x, width, top, height) = (9, size - 18, 3, size - 6);
+            fill_rect(&mut image, x, top, width, height, rgba(theme.track));
+            let percent = key.value.unwrap_or(0).clamp(0, 100) as u32;
+            let filled = (height * percent + 50) / 100;
+            fill_rect(
+                &mut image,
+                x,
+                top + height - filled,
+                width,
+                filled,
+                rgba(key.color.unwrap_or(theme.accent)),
+            );
+        }
+    }
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::test_support,
+        types::{default_metric_thresholds, UnitPreferences},
+    };
+
+    fn snapshot(cpu: f64) -> TelemetrySnapshot {
+        test_support::snapshot_at(0, cpu)
+    }
+
+    fn bands() -> ThresholdBands {
+        ThresholdBands::parse(&default_metric_thresholds()).unwrap()
+    }
+
+    #[test]
+    fn caches_icons_by_rounded_value_and_band() {
+        let bands = bands();
+        let units = UnitFormatter::default();
+        let mut renderer = TrayIconRenderer::parse(&TrayIconConfig::default()).unwrap();
+
+        let first = renderer.next_icon(&snapshot(42.2), &bands, &units).unwrap();
+        assert_eq!(first.dimensions(), (TRAY_ICON_SIZE, TRAY_ICON_SIZE));
+        assert!(renderer
+            .next_icon(&snapshot(41.8), &bands, &units)
+            .is_none());
+        let critical = renderer.next_icon(&snapshot(95.0), &bands, &units).unwrap();
+        assert!(critical
+            .pixels()
+            .any(|pixel| *pixel == rgba(CRITICAL_COLOR)));
+        let again = renderer.next_icon(&snapshot(42.0), &bands, &units).unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(renderer.cache.len(), 2);
+    }
+
+    #[test]
+    fn bar_style_fills_to_the_value_on_a_transparent_theme() {
+        let mut bar = TrayIconRenderer::parse(&TrayIconConfig {
+            style: "bar".to_string(),
+            theme: "transparent".to_string(),
+            ..TrayIconConfig::default()
+        })
+        .unwrap();
+        let half = bar
+            .next_icon(&snapshot(50.0), &bands(), &UnitFormatter::default())
+            .unwrap();
+        assert_eq!(half.get_pixel(0, 0)[3], 0);
+        assert_eq!(*half.get_pixel(16, 28), rgba([0, 242, 255]));
+        assert_eq!(*half.get_pixel(16, 4), rgba([120, 128, 138]));
+    }
+
+    #[test]
+    fn value_style_follows_the_temperature_unit() {
+        let config = TrayIconConfig {
+            metric: "cpu.temp".to_string(),
+            ..TrayIconConfig::default()
+        };
+        let fahrenheit = UnitFormatter::parse(&UnitPreferences {
+            temperature: "fahrenheit".to_string(),
+            ..UnitPreferences::default()
+        })
+        .unwrap();
+        let mut hot = snapshot(10.0);
+        hot.cpu.temperature_c = Some(50.0);
+
+        let mut renderer = TrayIconRenderer::parse(&config).unwrap();
+        renderer.next_icon(&hot, &bands(), &fahrenheit).unwrap();
+        assert_eq!(renderer.last.unwrap().value, Some(122));
+        renderer
+            .next_icon(&hot, &bands(), &UnitFormatter::default())
+            .unwrap();
+        assert_eq!(renderer.last.unwrap().value, Some(50));
+    }
+
+    #[test]
+    fn leading_numbers_skip_grouping_and_units() {
+        assert_eq!(leading_number("129°F"), 129);
+        assert_eq!(leading_number("1.234 MB"), 1234);
+        assert_eq!(leading_number("1\u{a0}234 MB/s"), 1234);
+        assert_eq!(leading_number("-3°C"), -3);
+    }
+
+    #[test]
+    fn rejects_unknown_themes() {
+        assert!(TrayIconRenderer::parse(&TrayIconConfig {
+            theme: "neon".to_string(),
+            ..TrayIconConfig::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use crate::{
+    core::metric_path::{DiskField, MetricPath},
+    types::UnitPreferences,
+};
+
+const MAX_PRECISION: u8 = 6;
+/// Bytes in the MB and GB the snapshot reports memory and disk sizes in.
+pub const MIB: f64 = 1024.0 * 1024.0;
+pub const GIB: f64 = 1024.0 * MIB;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prefix {
+    /// 1024 with the familiar `KB`/`MB` names.
+    Binary,
+    /// 1024 with `KiB`/`MiB`.
+    Iec,
+    /// 1000 with `kB`/`MB`.
+    Si,
+}
+
+impl Prefix {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "binary" => Ok(Self::Binary),
+            "iec" => Ok(Self::Iec),
+            "si" => Ok(Self::Si),
+            other => Err(format!(
+                "unknown unit prefix `{other}` (expected binary, iec or si)"
+            )),
+        }
+    }
+
+    fn base(self) -> f64 {
+        match self {
+            Self::Binary | Self::Iec => 1024.0,
+            Self::Si => 1000.0,
+        }
+    }
+
+    fn names(self) -> [&'static str; 5] {
+        match self {
+            Self::Binary => ["", "K", "M", "G", "T"],
+            Self::Iec => ["", "Ki", "Mi", "Gi", "Ti"],
+            Self::Si => ["", "k", "M", "G", "T"],
+        }
+    }
+}
+
+/// Renders numbers the way [`UnitPreferences`] asks, so every backend view agrees on units,
+/// precision and separators.
+///
+/// Each method takes the decimals its caller would use by default; a configured precision
+/// replaces them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitFormatter {
+    bits: bool,
+    prefix: Prefix,
+    fahrenheit: bool,
+    precision: Option<usize>,
+    decimal: char,
+    group: Option<char>,
+}
+
+impl Default for UnitFormatter {
+    fn default() -> Self {
+        Self::lenient(&UnitPreferences::default())
+    }
+}
+
+impl UnitFormatter {
+    pub fn parse(prefs: &UnitPreferences) -> Result<Self, String> {
+        let bits = match prefs.data_rate.trim().to_ascii_lowercase().as_str() {
+            "" | "bytes" => false,
+            "bits" => true,
+            other => {
+                return Err(format!(
+                    "unknown data rate unit `{other}` (expected bytes or bits)"
+                ))
+            }
+        };
+        let fahrenheit = match prefs.temperature.trim().to_ascii_lowercase().as_str() {
+            "" | "celsius" | "c" => false,
+            "fahrenheit" | "f" => true,
+            other => {
+                return Err(format!(
+                    "unknown temperature unit `{other}` (expected celsius or fahrenheit)"
+                ))
+            }
+        };
+        if prefs
+            .precision
+            .is_some_and(|precision| precision > MAX_PRECISION)
+        {
+            return Err(format!("precision must be 0 to {MAX_PRECISION}"));
+        }
+        let (decimal, group) = match prefs.number_locale.trim() {
+            "" | "plain" => ('.', None),
+            "en-US" | "zh-CN" => ('.', Some(',')),
+            "de-DE" => (',', Some('.')),
+            "fr-FR" => (',', Some('\u{a0}')),
+            other => {
+                return Err(format!(
+                    "unknown number locale `{other}` (expected plain, en-US, de-DE, fr-FR or zh-CN)"
+                ))
+            }
+        };
+        Ok(Self {
+            bits,
+            prefix: Prefix::parse(&prefs.prefix)?,
+            fahrenheit,
+            precision: prefs.precision.map(usize::from),
+            decimal,
+            group,
+        })
+    }
+
+    /// Like [`Self::parse`] but falls back to the defaults; for preferences validated when saved.
+    pub fn lenient(prefs: &UnitPreferences) -> Self {
+        Self::parse(prefs).unwrap_or(Self {
+            bits: false,
+            prefix: Prefix::Binary,
+            fahrenheit: false,
+            precision: None,
+            decimal: '.',
+            group: None,
+        })
+    }
+
+    /// The same formatter with a fixed precision, e.g. a template's `:.N`.
+    pub fn with_precision(self, precision: usize) -> Self {
+        Self {
+            precision: Some(precision),
+            ..self
+        }
+    }
+
+    fn decimals(&self, default: usize) -> usize {
+        self.precision.unwrap_or(default)
+    }
+
+    /// `value` with the configured decimal mark and digit grouping.
+    pub fn number(&self, value: f64, default: usize) -> String {
+        let decimals = self.decimals(default);
+        let plain = format!("{value:.decimals$}");
+        let (sign, digits) = match plain.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", plain.as_str()),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        let mut out = String::from(sign);
+        for (index, ch) in int.chars().enumerate() {
+            if index > 0 && (int.len() - index) % 3 == 0 {
+                if let Some(group) = self.group {
+                    out.push(group);
+                }
+            }
+            out.push(ch);
+        }
+        if !frac.is_empty() {
+            out.push(self.decimal);
+            out.push_str(frac);
+        }
+        out
+    }
+
+    pub fn percent(&self, value: f64, default: usize) -> String {
+        format!("{}%", self.number(value, default))
+    }
+
+    /// A Celsius reading in the configured scale, e.g. `54°C` or `129°F`.
+    pub fn temperature(&self, celsius: f64, default: usize) -> String {
+        if self.fahrenheit {
+            format!("{}°F", self.number(celsius * 9.0 / 5.0 + 32.0, default))
+        } else {
+            format!("{}°C", self.number(celsius, default))
+        }
+    }
+
+    /// Scales `value` to the largest prefix below it; whole base units never get decimals.
+    fn scaled(&self, value: f64, default: usize, unit: &str) -> String {
+        let names = self.prefix.names();
+        let base = self.prefix.base();
+        let mut value = value.max(0.0);
+        let mut index = 0;
+        while value >= base && index < names.len() - 1 {
+            value /= base;
+            index += 1;
+        }
+        let number = if index == 0 {
+            self.number(value, 0)
+        } else {
+            self.number(value, default)
+        };
+        format!("{number} {}{unit}", names[index])
+    }
+
+    /// A transfer rate in bytes per second, e.g. `3.0 MB/s` or `25.2 Mbit/s`.
+    pub fn rate(&self, bytes_per_sec: f64, default: usize) -> String {
+        if self.bits {
+            self.scaled(bytes_per_sec * 8.0, default, "bit/s")
+        } else {
+            self.scaled(bytes_per_sec, default, "B/s")
+        }
+    }
+
+    /// An amount of data, e.g. `3.00 GB`; sizes stay in bytes whatever the rate unit.
+    pub fn bytes(&self, bytes: f64, default: usize) -> String {
+        self.scaled(bytes, default, "B")
+    }
+
+    /// A raw value of the metric at `path` with its unit, falling back to a bare number for
+    /// paths that no longer parse.
+    pub fn metric(&self, path: &str, value: f64) -> String {
+        let Ok(metric) = MetricPath::parse(path) else {
+            return self.number(value, 2);
+        };
+        match metric {
+            MetricPath::CpuUsage
+            | MetricPath::GpuUsage
+            | MetricPath::MemUsage
+            | MetricPath::SwapUsage
+            | MetricPath::AppCpu => self.percent(value, 1),
+            MetricPath::CpuTemp | MetricPath::GpuTemp => self.temperature(value, 1),
+            MetricPath::CpuFreq | MetricPath::GpuFreq => format!("{} MHz", self.number(value, 0)),
+            MetricPath::GpuVram
+            | MetricPath::GpuVramTotal
+            | MetricPath::MemUsed
+            | MetricPath::MemTotal
+            | MetricPath::SwapUsed
+            | MetricPath::SwapTotal
+            | MetricPath::AppMem => self.bytes(value * MIB, 2),
+            MetricPath::NetDown | MetricPath::NetUp => self.rate(value, 1),
+            MetricPath::NetLatency => format!("{} ms", self.number(value, 0)),
+            MetricPath::Power => format!("{} W", self.number(value, 1)),
+            MetricPath::Disk { field, .. } => match field {
+                DiskField::Usage => self.percent(value, 1),
+                DiskField::Used | DiskField::Total => self.bytes(value * GIB, 2),
+                DiskField::Read | DiskField::Write => self.rate(value, 1),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_with_units_prefixes_and_separators() {
+        let default = UnitFormatter::default();
+        assert_eq!(default.rate(3.0 * MIB, 1), "3.0 MB/s");
+        assert_eq!(default.rate(512.0, 1), "512 B/s");
+        assert_eq!(default.temperature(54.6, 0), "55°C");
+        assert_eq!(default.number(12345.678, 2), "12345.68");
+        assert_eq!(default.metric("disk.C.used", 950.0), "950.00 GB");
+
+        let prefs = UnitPreferences {
+            data_rate: "bits".to_string(),
+            prefix: "si".to_string(),
+            temperature: "fahrenheit".to_string(),
+            precision: Some(2),
+            number_locale: "de-DE".to_string(),
+        };
+        let custom = UnitFormatter::parse(&prefs).unwrap();
+        assert_eq!(custom.rate(3_150_000.0, 1), "25,20 Mbit/s");
+        assert_eq!(custom.temperature(100.0, 0), "212,00°F");
+        assert_eq!(custom.number(-1234567.0, 0), "-1.234.567,00");
+        assert_eq!(custom.bytes(1500.0, 0), "1,50 kB");
+        assert_eq!(custom.with_precision(0).percent(93.4, 1), "93%");
+
+        let iec = UnitPreferences {
+            prefix: "iec".to_string(),
+            ..UnitPreferences::default()
+        };
+        assert_eq!(UnitFormatter::lenient(&iec).bytes(GIB, 1), "1.0 GiB");
+        let bad = UnitPreferences {
+            number_locale: "xx".to_string(),
+            ..UnitPreferences::default()
+        };
+        assert!(UnitFormatter::parse(&bad)
+            .unwrap_err()
+            .contains("number locale"));
+        assert_eq!(UnitFormatter::lenient(&bad), default);
+    }
+}
@@ -0,0 +1,4 @@
+//! Tauri-free pieces shared by the desktop app and the headless `pulsecore-cli` binary.
+
+pub mod core;
+pub mod types;
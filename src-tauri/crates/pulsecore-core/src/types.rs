@@ -0,0 +1,1535 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuMetrics {
+    pub usage_pct: f64,
+    pub frequency_mhz: Option<u64>,
+    pub temperature_c: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuMetrics {
+    pub usage_pct: Option<f64>,
+    pub temperature_c: Option<f64>,
+    pub memory_used_mb: Option<f64>,
+    pub memory_total_mb: Option<f64>,
+    pub frequency_mhz: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryMetrics {
+    pub used_mb: f64,
+    pub total_mb: f64,
+    pub usage_pct: f64,
+    /// Swap / page file; `None` when the system has none. Absent in older recordings.
+    #[serde(default)]
+    pub swap_used_mb: Option<f64>,
+    #[serde(default)]
+    pub swap_total_mb: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskMetrics {
+    pub name: String,
+    pub label: String,
+    pub used_gb: f64,
+    pub total_gb: f64,
+    pub usage_pct: f64,
+    pub read_bytes_per_sec: Option<f64>,
+    pub write_bytes_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkMetrics {
+    pub download_bytes_per_sec: f64,
+    pub upload_bytes_per_sec: f64,
+    pub latency_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetrySnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub cpu: CpuMetrics,
+    pub gpu: GpuMetrics,
+    pub memory: MemoryMetrics,
+    pub disks: Vec<DiskMetrics>,
+    pub network: NetworkMetrics,
+    #[serde(rename = "appCpuUsagePct")]
+    pub app_cpu_usage_pct: Option<f64>,
+    #[serde(rename = "appMemoryMb")]
+    pub app_memory_mb: Option<f64>,
+    pub power_watts: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareInfo {
+    pub cpu_model: String,
+    pub cpu_max_freq_mhz: Option<u64>,
+    pub gpu_model: String,
+    pub ram_spec: String,
+    pub disk_models: Vec<String>,
+    pub motherboard: String,
+    pub device_brand: String,
+}
+
+/// Fields missing from a stored `settings.json` (written before they existed) take their
+/// defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub language: String,
+    #[serde(rename = "closeToTray")]
+    pub close_to_tray: bool,
+    #[serde(rename = "autoStartEnabled")]
+    pub auto_start_enabled: bool,
+    #[serde(rename = "memoryTrimEnabled")]
+    pub memory_trim_enabled: bool,
+    #[serde(rename = "memoryTrimSystemEnabled")]
+    pub memory_trim_system_enabled: bool,
+    #[serde(rename = "memoryTrimTargets")]
+    pub memory_trim_targets: Vec<String>,
+    #[serde(rename = "memoryTrimIntervalMinutes")]
+    pub memory_trim_interval_minutes: u8,
+    #[serde(rename = "rememberOverlayPosition")]
+    pub remember_overlay_position: bool,
+    #[serde(rename = "overlayAlwaysOnTop")]
+    pub overlay_always_on_top: bool,
+    #[serde(rename = "taskbarMonitorEnabled")]
+    pub taskbar_monitor_enabled: bool,
+    #[serde(rename = "nativeTaskbarMonitorEnabled")]
+    pub native_taskbar_monitor_enabled: bool,
+    #[serde(rename = "taskbarAlwaysOnTop")]
+    pub taskbar_always_on_top: bool,
+    #[serde(rename = "taskbarAutoHideOnFullscreen")]
+    pub taskbar_auto_hide_on_fullscreen: bool,
+    #[serde(rename = "taskbarPositionLocked")]
+    pub taskbar_position_locked: bool,
+    #[serde(rename = "factoryResetHotkey")]
+    pub factory_reset_hotkey: Option<String>,
+    /// Display template for the main window title; `None` uses the built-in one.
+    #[serde(rename = "windowTitleTemplate", default)]
+    pub window_title_template: Option<String>,
+    /// Color bands shared by the overlay, the native taskbar and status bar output.
+    #[serde(rename = "metricThresholds", default = "default_metric_thresholds")]
+    pub metric_thresholds: Vec<MetricThreshold>,
+    /// Units, precision and number style for text the backend renders.
+    #[serde(default)]
+    pub units: UnitPreferences,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            language: "zh-CN".to_string(),
+            close_to_tray: false,
+            auto_start_enabled: false,
+            memory_trim_enabled: true,
+            memory_trim_system_enabled: false,
+            memory_trim_targets: vec!["app".to_string()],
+            memory_trim_interval_minutes: 5,
+            remember_overlay_position: true,
+            overlay_always_on_top: true,
+            taskbar_monitor_enabled: false,
+            native_taskbar_monitor_enabled: false,
+            taskbar_always_on_top: true,
+            taskbar_auto_hide_on_fullscreen: false,
+            taskbar_position_locked: false,
+            factory_reset_hotkey: None,
+            window_title_template: None,
+            metric_thresholds: default_metric_thresholds(),
+            units: UnitPreferences::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppBootstrap {
+    pub settings: AppSettings,
+    pub hardware_info: HardwareInfo,
+    pub latest_snapshot: TelemetrySnapshot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRuntimeInfo {
+    pub version: String,
+    pub installation_mode: String,
+    pub package_flavor: String,
+    pub can_switch_package_flavor: bool,
+}
+
+/// Fields left out of a saved config (e.g. one written by hand for `pulsecore-cli statusbar`)
+/// take their defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NativeTaskbarConfig {
+    pub language: String,
+    #[serde(rename = "alwaysOnTop")]
+    pub always_on_top: bool,
+    #[serde(rename = "autoHideOnFullscreen")]
+    pub auto_hide_on_fullscreen: bool,
+    #[serde(rename = "rememberPosition")]
+    pub remember_position: bool,
+    #[serde(rename = "positionLocked")]
+    pub position_locked: bool,
+    /// Segments in display order; empty is filled from the legacy toggles and templates below
+    /// (see `core::taskbar::migrate_segments`).
+    #[serde(default)]
+    pub segments: Vec<TaskbarSegmentConfig>,
+    /// Legacy toggles, read when `segments` is empty and kept in step with it for the webview
+    /// taskbar.
+    #[serde(rename = "showCpu")]
+    pub show_cpu: bool,
+    #[serde(rename = "showCpuFreq")]
+    pub show_cpu_freq: bool,
+    #[serde(rename = "showCpuTemp")]
+    pub show_cpu_temp: bool,
+    #[serde(rename = "showGpu")]
+    pub show_gpu: bool,
+    #[serde(rename = "showGpuTemp")]
+    pub show_gpu_temp: bool,
+    #[serde(rename = "showMemory")]
+    pub show_memory: bool,
+    #[serde(rename = "showApp")]
+    pub show_app: bool,
+    #[serde(rename = "showDown")]
+    pub show_down: bool,
+    #[serde(rename = "showUp")]
+    pub show_up: bool,
+    #[serde(rename = "showLatency")]
+    pub show_latency: bool,
+    #[serde(rename = "twoLineMode")]
+    pub two_line_mode: bool,
+    #[serde(rename = "backgroundMode")]
+    pub background_mode: String,
+    /// Legacy custom segments; migrated into `custom` entries of `segments`.
+    #[serde(rename = "segmentTemplates", default)]
+    pub segment_templates: Vec<TaskbarSegmentTemplate>,
+    /// Warn/critical color bands; empty takes `AppSettings::metric_thresholds`.
+    #[serde(default)]
+    pub thresholds: Vec<MetricThreshold>,
+    /// Copied from `AppSettings::units` when the taskbar is configured.
+    pub units: UnitPreferences,
+}
+
+impl Default for NativeTaskbarConfig {
+    fn default() -> Self {
+        Self {
+            language: "en-US".to_string(),
+            always_on_top: true,
+            auto_hide_on_fullscreen: false,
+            remember_position: true,
+            position_locked: false,
+            segments: default_taskbar_segments(),
+            show_cpu: true,
+            show_cpu_freq: true,
+            show_cpu_temp: true,
+            show_gpu: true,
+            show_gpu_temp: true,
+            show_memory: true,
+            show_app: true,
+            show_down: true,
+            show_up: true,
+            show_latency: false,
+            two_line_mode: false,
+            background_mode: "dark".to_string(),
+            segment_templates: Vec::new(),
+            thresholds: default_metric_thresholds(),
+            units: UnitPreferences::default(),
+        }
+    }
+}
+
+/// One native taskbar segment. `kind` is `cpu`, `gpu`, `memory`, `app`, `down`, `up`,
+/// `latency`, `gpuMemory`, `power`, `swap`, `diskUsage`, `diskRead`, `diskWrite` or `custom`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskbarSegmentConfig {
+    pub kind: String,
+    /// Hidden segments keep their place, so toggling one back on does not reorder the bar.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Disk name, label or index for the disk kinds, e.g. `C:` or `/home`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk: Option<String>,
+    /// CPU: append the clock speed.
+    #[serde(default)]
+    pub show_freq: bool,
+    /// CPU and GPU: append the temperature.
+    #[serde(default)]
+    pub show_temp: bool,
+    /// Replaces the kind's own label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// `custom` only: display templates, e.g. `{cpu.usage:.0}%` or `{net.down|speed}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra: Option<String>,
+    /// A tiny chart of recent values drawn after the segment's text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparkline: Option<SparklineConfig>,
+}
+
+impl TaskbarSegmentConfig {
+    pub fn new(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            enabled: true,
+            disk: None,
+            show_freq: false,
+            show_temp: false,
+            label: None,
+            value: None,
+            extra: None,
+            sparkline: None,
+        }
+    }
+}
+
+/// The segments a new taskbar shows: the former `show*` defaults in their old order.
+pub fn default_taskbar_segments() -> Vec<TaskbarSegmentConfig> {
+    let mut segments: Vec<TaskbarSegmentConfig> =
+        ["cpu", "gpu", "memory", "app", "down", "up", "latency"]
+            .into_iter()
+            .map(TaskbarSegmentConfig::new)
+            .collect();
+    segments[0].show_freq = true;
+    segments[0].show_temp = true;
+    segments[1].show_temp = true;
+    segments[6].enabled = false;
+    segments
+}
+
+/// Warn and critical bands for one metric path; a value at or above a bound is in that band.
+/// Colors are `#rgb`/`#rrggbb` and default to each view's orange and red.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricThreshold {
+    pub metric: String,
+    pub warn: f64,
+    pub critical: f64,
+    #[serde(default)]
+    pub warn_color: Option<String>,
+    #[serde(default)]
+    pub critical_color: Option<String>,
+}
+
+/// The bands every view starts with: the old 75/90 usage cutoffs plus temperatures and latency.
+pub fn default_metric_thresholds() -> Vec<MetricThreshold> {
+    [
+        ("cpu.usage", 75.0, 90.0),
+        ("gpu.usage", 75.0, 90.0),
+        ("mem.usage", 75.0, 90.0),
+        ("cpu.temp", 80.0, 90.0),
+        ("gpu.temp", 80.0, 90.0),
+        ("net.latency", 100.0, 250.0),
+    ]
+    .into_iter()
+    .map(|(metric, warn, critical)| MetricThreshold {
+        metric: metric.to_string(),
+        warn,
+        critical,
+        warn_color: None,
+        critical_color: None,
+    })
+    .collect()
+}
+
+/// How the backend writes numbers in the taskbar, status bars, window title and emails.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UnitPreferences {
+    /// `bytes` (MB/s) or `bits` (Mbit/s) for transfer rates.
+    pub data_rate: String,
+    /// `binary` (1024, `KB`), `iec` (1024, `KiB`) or `si` (1000, `kB`).
+    pub prefix: String,
+    /// `celsius` or `fahrenheit`.
+    pub temperature: String,
+    /// Decimal places for every value, overriding each view's own; `None` keeps those.
+    pub precision: Option<u8>,
+    /// Digit grouping and decimal mark: `plain` (`1234.5`), `en-US` (`1,234.5`),
+    /// `de-DE` (`1.234,5`), `fr-FR` (`1 234,5`) or `zh-CN` (`1,234.5`).
+    pub number_locale: String,
+}
+
+impl Default for UnitPreferences {
+    fn default() -> Self {
+        Self {
+            data_rate: "bytes".to_string(),
+            prefix: "binary".to_string(),
+            temperature: "celsius".to_string(),
+            precision: None,
+            number_locale: "plain".to_string(),
+        }
+    }
+}
+
+/// A user-defined taskbar segment from before `segments`; `value` and `extra` use the display
+/// template syntax.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskbarSegmentTemplate {
+    pub label: String,
+    pub value: String,
+    #[serde(default)]
+    pub extra: Option<String>,
+    /// A tiny chart of recent values drawn after the segment's text.
+    #[serde(default)]
+    pub sparkline: Option<SparklineConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SparklineConfig {
+    /// Metric path to plot; defaults to the segment's first placeholder.
+    #[serde(default)]
+    pub metric: Option<String>,
+    /// `line` or `bar`.
+    #[serde(default = "default_sparkline_style")]
+    pub style: String,
+    #[serde(default = "default_sparkline_seconds")]
+    pub seconds: u32,
+    /// Width in pixels (8-200).
+    #[serde(default = "default_sparkline_width")]
+    pub width: u32,
+    /// Top of the y axis; percentages default to 100, other metrics scale to their peak.
+    #[serde(default)]
+    pub max: Option<f64>,
+}
+
+impl Default for SparklineConfig {
+    fn default() -> Self {
+        Self {
+            metric: None,
+            style: default_sparkline_style(),
+            seconds: default_sparkline_seconds(),
+            width: default_sparkline_width(),
+            max: None,
+        }
+    }
+}
+
+fn default_sparkline_style() -> String {
+    "line".to_string()
+}
+
+fn default_sparkline_seconds() -> u32 {
+    60
+}
+
+fn default_sparkline_width() -> u32 {
+    40
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskbarInfo {
+    /// `ABE_LEFT`=0, `ABE_TOP`=1, `ABE_RIGHT`=2, `ABE_BOTTOM`=3.
+    pub edge: u32,
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+fn default_stream_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_stream_port() -> u16 {
+    39392
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryStreamConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Interface to bind. Keep `127.0.0.1` unless LAN dashboards need access.
+    #[serde(default = "default_stream_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_stream_port")]
+    pub port: u16,
+    /// Optional shared secret; clients pass it as `?token=`.
+    #[serde(default)]
+    pub token: String,
+    /// Web page origins (e.g. `http://localhost:3000`) allowed to connect from a browser;
+    /// requests carrying any other `Origin` are refused.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for TelemetryStreamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_stream_bind_address(),
+            port: default_stream_port(),
+            token: String::new(),
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryStreamStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    pub clients: usize,
+    pub error: Option<String>,
+}
+
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_mqtt_client_id() -> String {
+    "pulsecorelite".to_string()
+}
+
+fn default_mqtt_topic_prefix() -> String {
+    "pulsecorelite".to_string()
+}
+
+fn default_mqtt_publish_interval_ms() -> u64 {
+    5000
+}
+
+fn default_mqtt_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MqttConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub host: String,
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub use_tls: bool,
+    #[serde(default)]
+    pub username: String,
+    /// Stored in plain text in `mqtt.json` like the SMTP password; use a broker account that
+    /// can only publish under `topic_prefix`.
+    #[serde(default)]
+    pub password: String,
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+    /// State goes to `<prefix>/state`, availability/LWT to `<prefix>/availability`.
+    #[serde(default = "default_mqtt_topic_prefix")]
+    pub topic_prefix: String,
+    /// 0 | 1 | 2
+    #[serde(default)]
+    pub qos: u8,
+    #[serde(default = "default_mqtt_publish_interval_ms")]
+    pub publish_interval_ms: u64,
+    #[serde(default = "default_true")]
+    pub home_assistant_discovery: bool,
+    #[serde(default = "default_mqtt_discovery_prefix")]
+    pub discovery_prefix: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: default_mqtt_port(),
+            use_tls: false,
+            username: String::new(),
+            password: String::new(),
+            client_id: default_mqtt_client_id(),
+            topic_prefix: default_mqtt_topic_prefix(),
+            qos: 0,
+            publish_interval_ms: default_mqtt_publish_interval_ms(),
+            home_assistant_discovery: true,
+            discovery_prefix: default_mqtt_discovery_prefix(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MqttStatus {
+    pub running: bool,
+    pub connected: bool,
+    pub published: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    pub id: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub name: String,
+    /// Metric path such as `gpu.temp`, `mem.usage` or `disk.C.usage`
+    pub metric: String,
+    /// gt | gte | lt | lte
+    pub comparator: String,
+    pub threshold: f64,
+    /// How long the condition must hold before the alert fires
+    #[serde(default)]
+    pub sustain_secs: u64,
+    /// Value the metric has to cross back over before the alert clears; defaults to `threshold`
+    #[serde(default)]
+    pub clear_threshold: Option<f64>,
+    /// Minimum time between two firings of the same rule
+    #[serde(default)]
+    pub cooldown_secs: u64,
+    /// Recipient for alert mails sent through the reminder SMTP config; empty disables mail
+    #[serde(default)]
+    pub email: String,
+    /// Webhook endpoint notified on fire and clear; empty disables it
+    #[serde(default)]
+    pub webhook_id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleStore {
+    pub rules: Vec<AlertRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+    /// threshold | anomaly
+    pub kind: String,
+    pub rule_id: String,
+    pub name: String,
+    pub metric: String,
+    /// fired | cleared
+    pub state: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub timestamp: String,
+}
+
+fn default_anomaly_metrics() -> Vec<String> {
+    ["net.down", "net.up", "app.mem", "app.cpu", "cpu.usage", "mem.usage"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_anomaly_sigma() -> f64 {
+    3.0
+}
+
+fn default_anomaly_alpha() -> f64 {
+    0.02
+}
+
+fn default_anomaly_warmup_samples() -> u32 {
+    60
+}
+
+fn default_anomaly_sustain_samples() -> u32 {
+    3
+}
+
+fn default_anomaly_cooldown_secs() -> u64 {
+    600
+}
+
+/// Dynamic tray icon drawn from a metric, persisted in `tray-icon.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayIconConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Metric path to show, e.g. `cpu.usage` or `cpu.temp`.
+    #[serde(default = "default_tray_icon_metric")]
+    pub metric: String,
+    /// `value` (digits) or `bar`.
+    #[serde(default = "default_tray_icon_style")]
+    pub style: String,
+    /// `dark`, `light` or `transparent`.
+    #[serde(default = "default_tray_icon_theme")]
+    pub theme: String,
+    /// Value of a full bar; defaults to 100.
+    #[serde(default)]
+    pub max: Option<f64>,
+}
+
+impl Default for TrayIconConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            metric: default_tray_icon_metric(),
+            style: default_tray_icon_style(),
+            theme: default_tray_icon_theme(),
+            max: None,
+        }
+    }
+}
+
+fn default_tray_icon_metric() -> String {
+    "cpu.usage".to_string()
+}
+
+fn default_tray_icon_style() -> String {
+    "value".to_string()
+}
+
+fn default_tray_icon_theme() -> String {
+    "dark".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnomalyDetectionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Metric paths with their own baselines, same syntax as alert rules
+    #[serde(default = "default_anomaly_metrics")]
+    pub metrics: Vec<String>,
+    /// Deviation from the hour-of-day baseline, in standard deviations, that counts as anomalous
+    #[serde(default = "default_anomaly_sigma")]
+    pub sigma: f64,
+    /// EWMA smoothing factor applied per baseline sample
+    #[serde(default = "default_anomaly_alpha")]
+    pub alpha: f64,
+    /// Samples an hour bucket needs before it is trusted
+    #[serde(default = "default_anomaly_warmup_samples")]
+    pub warmup_samples: u32,
+    /// Consecutive anomalous samples before an event fires
+    #[serde(default = "default_anomaly_sustain_samples")]
+    pub sustain_samples: u32,
+    #[serde(default = "default_anomaly_cooldown_secs")]
+    pub cooldown_secs: u64,
+    #[serde(default)]
+    pub email: String,
+    #[serde(default)]
+    pub webhook_id: String,
+}
+
+impl Default for AnomalyDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            metrics: default_anomaly_metrics(),
+            sigma: default_anomaly_sigma(),
+            alpha: default_anomaly_alpha(),
+            warmup_samples: default_anomaly_warmup_samples(),
+            sustain_samples: default_anomaly_sustain_samples(),
+            cooldown_secs: default_anomaly_cooldown_secs(),
+            email: String::new(),
+            webhook_id: String::new(),
+        }
+    }
+}
+
+fn default_digest_frequency() -> String {
+    "daily".to_string()
+}
+
+fn default_digest_time() -> String {
+    "09:00".to_string()
+}
+
+fn default_digest_weekday() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthDigestConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// daily | weekly
+    #[serde(default = "default_digest_frequency")]
+    pub frequency: String,
+    /// HH:mm local time
+    #[serde(default = "default_digest_time")]
+    pub time: String,
+    /// 1=Monday ... 7=Sunday, used by weekly digests
+    #[serde(default = "default_digest_weekday")]
+    pub weekday: u8,
+    /// Recipient; empty falls back to the SMTP from address like reminders do
+    #[serde(default)]
+    pub email: String,
+}
+
+impl Default for HealthDigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frequency: default_digest_frequency(),
+            time: default_digest_time(),
+            weekday: default_digest_weekday(),
+            email: String::new(),
+        }
+    }
+}
+
+fn default_sampling_idle_interval_ms() -> u64 {
+    5000
+}
+
+fn default_sampling_background_interval_ms() -> u64 {
+    2000
+}
+
+fn default_sampling_power_saver_interval_ms() -> u64 {
+    3000
+}
+
+fn default_sampling_app_cpu_budget_pct() -> f64 {
+    3.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveSamplingConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Cadence when no window, taskbar or background service consumes snapshots
+    #[serde(default = "default_sampling_idle_interval_ms")]
+    pub idle_interval_ms: u64,
+    /// Cadence when only background services (alerts, MQTT, stream, digest) consume snapshots
+    #[serde(default = "default_sampling_background_interval_ms")]
+    pub background_interval_ms: u64,
+    #[serde(default = "default_true")]
+    pub power_saver_on_battery: bool,
+    #[serde(default = "default_sampling_power_saver_interval_ms")]
+    pub power_saver_interval_ms: u64,
+    /// Back off when the app's own CPU use stays above this; 0 disables the budget
+    #[serde(default = "default_sampling_app_cpu_budget_pct")]
+    pub app_cpu_budget_pct: f64,
+}
+
+impl Default for AdaptiveSamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_interval_ms: default_sampling_idle_interval_ms(),
+            background_interval_ms: default_sampling_background_interval_ms(),
+            power_saver_on_battery: true,
+            power_saver_interval_ms: default_sampling_power_saver_interval_ms(),
+            app_cpu_budget_pct: default_sampling_app_cpu_budget_pct(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SamplingStatus {
+    pub enabled: bool,
+    pub configured_interval_ms: u64,
+    pub effective_interval_ms: u64,
+    /// interactive | background | idle
+    pub consumers: String,
+    /// fixed | interactive | background | idle | battery | cpu-budget
+    pub reason: String,
+    pub on_battery: Option<bool>,
+    pub app_cpu_usage_pct: Option<f64>,
+    pub backoff_factor: u32,
+    pub expensive_sources_paused: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayStatus {
+    pub active: bool,
+    pub paused: bool,
+    pub path: Option<String>,
+    pub speed: f64,
+    /// Offset of the last published sample from the start of the capture
+    pub position_ms: u64,
+    pub duration_ms: u64,
+    pub sample_index: usize,
+    pub total_samples: usize,
+    /// Lines that could not be parsed and were left out
+    pub skipped_lines: u64,
+    /// True once the last sample was published (as opposed to stopped early)
+    pub finished: bool,
+}
+
+/// One named profile capture, running or finished.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStatus {
+    pub name: String,
+    pub active: bool,
+    pub path: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub interval_ms: u64,
+    /// Planned capture length; `None` runs until stopped
+    pub duration_ms: Option<u64>,
+    pub samples: u64,
+    /// Segment files started so far, including ones deleted by the size cap
+    pub segments: u64,
+    /// Bytes on disk across the segments still kept
+    pub bytes_written: u64,
+    /// Time left of a timed capture
+    pub eta_ms: Option<u64>,
+    /// Why the capture ended early (e.g. a failed write on a full disk)
+    pub error: Option<String>,
+}
+
+fn default_webhook_max_retries() -> u8 {
+    3
+}
+
+fn default_shutdown_warning_minutes() -> u32 {
+    5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEndpoint {
+    pub id: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub name: String,
+    /// generic | discord | slack | feishu | dingtalk
+    pub kind: String,
+    pub url: String,
+    /// Signing secret for Feishu / DingTalk custom bots; empty sends unsigned
+    #[serde(default)]
+    pub secret: String,
+    /// JSON body for `generic`, with `{{title}}`, `{{text}}`, `{{event}}` and `{{timestamp}}`
+    #[serde(default)]
+    pub body_template: String,
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookStore {
+    pub endpoints: Vec<WebhookEndpoint>,
+    /// Endpoint warned ahead of a scheduled shutdown; empty disables the warning
+    #[serde(default)]
+    pub shutdown_warning_webhook_id: String,
+    #[serde(default = "default_shutdown_warning_minutes")]
+    pub shutdown_warning_minutes: u32,
+}
+
+impl Default for WebhookStore {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            shutdown_warning_webhook_id: String::new(),
+            shutdown_warning_minutes: default_shutdown_warning_minutes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownPlan {
+    /// countdown | once | daily | weekly | monthly
+    pub mode: String,
+    pub created_at: DateTime<Utc>,
+    pub execute_at: Option<DateTime<Utc>>,
+    pub countdown_seconds: Option<u64>,
+    /// HH:mm format for repeat plans.
+    pub time: Option<String>,
+    /// 1=Monday ... 7=Sunday
+    pub weekday: Option<u8>,
+    /// 1..31
+    pub day_of_month: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleShutdownRequest {
+    /// countdown | once | daily | weekly | monthly
+    pub mode: String,
+    pub delay_seconds: Option<u64>,
+    pub execute_at: Option<DateTime<Utc>>,
+    /// HH:mm format for repeat plans.
+    pub time: Option<String>,
+    /// 1=Monday ... 7=Sunday
+    pub weekday: Option<u8>,
+    /// 1..31
+    pub day_of_month: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmtpEmailConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub from_email: String,
+    pub from_name: String,
+    /// none | starttls | tls
+    pub security: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendReminderEmailRequest {
+    pub smtp: SmtpEmailConfig,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyReminderSlot {
+    /// 1=Monday ... 7=Sunday
+    pub weekday: u8,
+    /// HH:mm
+    pub time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyReminderSlot {
+    /// 1..31
+    pub day: u8,
+    /// HH:mm
+    pub time: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_background_type() -> String {
+    "image".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderAdvancedSettings {
+    #[serde(default = "default_background_type")]
+    pub background_type: String,
+    #[serde(default)]
+    pub background_image: String,
+    #[serde(default)]
+    pub background_color: String,
+    #[serde(default = "default_true")]
+    pub allow_close: bool,
+    #[serde(default)]
+    pub block_all_keys: bool,
+    #[serde(default)]
+    pub require_close_password: bool,
+    #[serde(default)]
+    pub close_password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskReminder {
+    pub id: String,
+    pub enabled: bool,
+    pub title: String,
+    /// email | fullscreen | webhook
+    pub channel: String,
+    pub email: String,
+    /// Endpoint id from the webhook store, used by the `webhook` channel
+    #[serde(default)]
+    pub webhook_id: String,
+    pub daily_times: Vec<String>,
+    pub weekly_slots: Vec<WeeklyReminderSlot>,
+    pub monthly_slots: Vec<MonthlyReminderSlot>,
+    /// text | markdown | web | image
+    pub content_type: String,
+    pub content: String,
+    #[serde(default)]
+    pub advanced_settings: Option<ReminderAdvancedSettings>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskReminderStore {
+    pub reminders: Vec<TaskReminder>,
+    pub smtp_config: Option<SmtpEmailConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderScreenEventPayload {
+    pub title: String,
+    pub content: String,
+    pub content_type: String,
+    #[serde(default)]
+    pub advanced_settings: Option<ReminderAdvancedSettings>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAiStatus {
+    pub ready: bool,
+    pub running: bool,
+    pub model_name: String,
+    pub launch_mode: String,
+    pub launch_backend: String,
+    pub selected_model_dir: Option<String>,
+    pub selected_launcher_dir: Option<String>,
+    pub model_path: Option<String>,
+    pub server_path: Option<String>,
+    pub server_url: String,
+    pub vision_enabled: bool,
+    pub launcher_needs_selection: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAiAttachment {
+    pub name: String,
+    pub media_type: String,
+    pub size: u64,
+    pub text_content: Option<String>,
+    pub data_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAiChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAiChatRequest {
+    pub prompt: String,
+    #[serde(default)]
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub enable_thinking: Option<bool>,
+    #[serde(default)]
+    pub history: Vec<LocalAiChatMessage>,
+    #[serde(default)]
+    pub attachments: Vec<LocalAiAttachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAiTokenUsage {
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+    pub total_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAiChatResponse {
+    pub reply: String,
+    pub reasoning: Option<String>,
+    pub model: String,
+    pub status: LocalAiStatus,
+    pub usage: Option<LocalAiTokenUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAiStreamEvent {
+    pub request_id: String,
+    pub channel: String,
+    pub delta: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamShortcutAccount {
+    pub id: String,
+    pub label: String,
+    pub shortcuts_path: String,
+    pub grid_dir: String,
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpicSteamGameSyncStatus {
+    pub present_in_steam: bool,
+    pub managed_by_pulse: bool,
+    pub app_id: Option<u32>,
+    /// new | managed | existing
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpicInstalledGame {
+    pub id: String,
+    pub title: String,
+    pub install_dir: String,
+    pub launch_executable: Option<String>,
+    pub launch_command: Option<String>,
+    pub epic_app_name: Option<String>,
+    pub icon_path: Option<String>,
+    pub sync_status: EpicSteamGameSyncStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpicSteamScanResult {
+    pub steam_path: Option<String>,
+    pub steam_running: bool,
+    pub selected_steam_user_id: Option<String>,
+    pub accounts: Vec<SteamShortcutAccount>,
+    pub epic_games: Vec<EpicInstalledGame>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEpicGamesRequest {
+    pub steam_user_id: String,
+    pub game_ids: Vec<String>,
+    #[serde(default = "default_true")]
+    pub remove_missing_managed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEpicGamesResult {
+    pub shortcuts_path: String,
+    pub grid_dir: String,
+    pub launcher_dir: String,
+    pub backup_path: Option<String>,
+    pub created_count: usize,
+    pub updated_count: usize,
+    pub removed_count: usize,
+    pub synced_game_ids: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupItem {
+    pub id: String,
+    pub name: String,
+    pub command: String,
+    pub source: String,
+    pub enabled: bool,
+    pub writable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemToolsStatus {
+    pub context_menu_style: String,
+    pub windows_update_disabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSessionStatus {
+    pub logged_in: bool,
+    pub steam_id: Option<String>,
+    pub persona_name: Option<String>,
+    pub currency_label: Option<String>,
+    pub saved_at: Option<String>,
+    pub login_window_open: bool,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSessionInput {
+    pub session_id: String,
+    pub steam_login_secure: String,
+    pub steam_country: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketItem {
+    pub id: String,
+    pub asset_id: String,
+    pub class_id: String,
+    pub instance_id: String,
+    pub app_id: u32,
+    pub context_id: String,
+    pub name: String,
+    pub market_hash_name: String,
+    pub icon_url: Option<String>,
+    pub amount: u32,
+    pub marketable: bool,
+    pub tradable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketInventoryGroup {
+    pub app_id: u32,
+    pub context_id: String,
+    pub app_label: String,
+    pub items: Vec<SteamMarketItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketInventoryScanResult {
+    pub steam_id: String,
+    pub groups: Vec<SteamMarketInventoryGroup>,
+    pub total_marketable: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketPriceQuote {
+    pub item_id: String,
+    pub market_hash_name: String,
+    pub lowest_sell_cents: Option<u64>,
+    pub highest_buy_cents: Option<u64>,
+    pub median_sell_cents: Option<u64>,
+    pub currency_label: String,
+    pub volume: Option<u64>,
+    pub stale: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSellStrategy {
+    pub mode: String,
+    pub discount_cents: Option<u64>,
+    pub discount_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSellPlanItem {
+    pub item: SteamMarketItem,
+    pub reference_mode: String,
+    pub reference_cents: Option<u64>,
+    pub seller_receives_cents: u64,
+    pub buyer_pays_cents: Option<u64>,
+    pub skipped: bool,
+    pub skip_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSellPlanResult {
+    pub items: Vec<SteamMarketSellPlanItem>,
+    pub total_seller_receives_cents: u64,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSellItemRequest {
+    pub asset_id: String,
+    pub app_id: u32,
+    pub context_id: String,
+    pub amount: u32,
+    pub seller_receives_cents: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSellBatchRequest {
+    pub items: Vec<SteamMarketSellItemRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSellItemResult {
+    pub asset_id: String,
+    pub success: bool,
+    pub message: String,
+    pub requires_confirmation: bool,
+    pub listing_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketSellBatchResult {
+    pub results: Vec<SteamMarketSellItemResult>,
+    pub success_count: usize,
+    pub failure_count: usize,
+    pub confirmation_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketListingItem {
+    pub listing_id: String,
+    pub name: String,
+    pub price_cents: u64,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamMarketListingsResult {
+    pub items: Vec<SteamMarketListingItem>,
+    pub total: usize,
+}
+
+fn default_profile_compression() -> String {
+    "gzip".to_string()
+}
+
+fn default_profile_segment_max_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+fn default_profile_max_total_bytes() -> u64 {
+    1024 * 1024 * 1024
+}
+
+/// How a profile capture is written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStorageOptions {
+    /// gzip | none
+    #[serde(default = "default_profile_compression")]
+    pub compression: String,
+    /// Start a new numbered segment once the current one holds this many bytes; 0 disables
+    #[serde(default = "default_profile_segment_max_bytes")]
+    pub segment_max_bytes: u64,
+    /// Start a new numbered segment after this many minutes; 0 disables
+    #[serde(default)]
+    pub segment_max_minutes: u64,
+    /// Delete the oldest segments to stay under this many bytes; 0 keeps everything
+    #[serde(default = "default_profile_max_total_bytes")]
+    pub max_total_bytes: u64,
+}
+
+impl Default for ProfileStorageOptions {
+    fn default() -> Self {
+        Self {
+            compression: default_profile_compression(),
+            segment_max_bytes: default_profile_segment_max_bytes(),
+            segment_max_minutes: 0,
+            max_total_bytes: default_profile_max_total_bytes(),
+        }
+    }
+}
+
+fn default_profile_scope_mode() -> String {
+    "app".to_string()
+}
+
+fn default_profile_scope_top_n() -> usize {
+    10
+}
+
+fn default_profile_scope_top_by() -> String {
+    "memory".to_string()
+}
+
+/// Which processes a profile capture records.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileScope {
+    /// app (the app and everything it spawned) | top (busiest system processes) | match
+    #[serde(default = "default_profile_scope_mode")]
+    pub mode: String,
+    /// Processes kept in `top` mode
+    #[serde(default = "default_profile_scope_top_n")]
+    pub top_n: usize,
+    /// memory | cpu; ranking for `top` mode
+    #[serde(default = "default_profile_scope_top_by")]
+    pub top_by: String,
+    /// Process names for `match` mode; case-insensitive, `.exe` optional
+    #[serde(default)]
+    pub names: Vec<String>,
+    /// Regex for `match` mode, tested against the name and the command line
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Keep the app's own process tree alongside `top`/`match` results
+    #[serde(default = "default_true")]
+    pub include_app: bool,
+    /// Record each process's command line; opt-in, since command lines can carry secrets
+    #[serde(default)]
+    pub include_command_line: bool,
+}
+
+impl Default for ProfileScope {
+    fn default() -> Self {
+        Self {
+            mode: default_profile_scope_mode(),
+            top_n: default_profile_scope_top_n(),
+            top_by: default_profile_scope_top_by(),
+            names: Vec::new(),
+            pattern: None,
+            include_app: true,
+            include_command_line: false,
+        }
+    }
+}
+
+fn default_flight_buffer_minutes() -> u64 {
+    5
+}
+
+fn default_flight_interval_ms() -> u64 {
+    1000
+}
+
+fn default_flight_post_trigger_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightRecorderConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes of samples kept in memory before a trigger
+    #[serde(default = "default_flight_buffer_minutes")]
+    pub buffer_minutes: u64,
+    #[serde(default = "default_flight_interval_ms")]
+    pub interval_ms: u64,
+    /// Seconds the capture keeps recording after a trigger
+    #[serde(default = "default_flight_post_trigger_secs")]
+    pub post_trigger_secs: u64,
+    #[serde(default = "default_true")]
+    pub trigger_on_alerts: bool,
+    /// Alert rule ids that trigger a dump; empty means any fired alert
+    #[serde(default)]
+    pub alert_rule_ids: Vec<String>,
+}
+
+impl Default for FlightRecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            buffer_minutes: default_flight_buffer_minutes(),
+            interval_ms: default_flight_interval_ms(),
+            post_trigger_secs: default_flight_post_trigger_secs(),
+            trigger_on_alerts: true,
+            alert_rule_ids: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightRecorderDump {
+    pub path: String,
+    /// command | hotkey | alert:<rule id>
+    pub reason: String,
+    pub triggered_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// Buffered samples written at the trigger plus those recorded since
+    pub samples: u64,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightRecorderStatus {
+    pub enabled: bool,
+    pub buffered_samples: usize,
+    pub buffered_ms: u64,
+    /// The dump still recording its post-trigger window, or else the last finished one
+    pub dump: Option<FlightRecorderDump>,
+}
@@ -0,0 +1,163 @@
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, State};
+
+use crate::{
+    core::{
+        power,
+        sampling::{AdaptiveSampler, ConsumerLevel, SamplingPlan, MAX_INTERVAL_MS},
+        store,
+    },
+    state::SharedState,
+    tauri_adapter::TauriAdapter,
+    types::{AdaptiveSamplingConfig, SamplingStatus, TelemetrySnapshot},
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const SAMPLING_CONFIG_FILE: &str = "adaptive-sampling.json";
+/// Background consumers change rarely; re-checking them every tick would take seven locks.
+const CONSUMER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct SamplingRuntime {
+    sampler: AdaptiveSampler,
+    background: bool,
+    last_consumer_check: Option<Instant>,
+    on_battery: Option<bool>,
+    last_power_check: Option<Instant>,
+}
+
+fn read_sampling_config(app: &AppHandle) -> Option<AdaptiveSamplingConfig> {
+    store::read_json(&TauriAdapter::new(app), SAMPLING_CONFIG_FILE)
+}
+
+fn write_sampling_config(app: &AppHandle, config: &AdaptiveSamplingConfig) -> CmdResult<()> {
+    store::write_json(&TauriAdapter::new(app), SAMPLING_CONFIG_FILE, config)
+}
+
+fn normalize_sampling_config(input: AdaptiveSamplingConfig) -> CmdResult<AdaptiveSamplingConfig> {
+    if !input.app_cpu_budget_pct.is_finite() || input.app_cpu_budget_pct < 0.0 {
+        return Err("app CPU budget must be 0 (off) or a positive percentage".to_string());
+    }
+    Ok(AdaptiveSamplingConfig {
+        enabled: input.enabled,
+        idle_interval_ms: input.idle_interval_ms.clamp(1000, MAX_INTERVAL_MS),
+        background_interval_ms: input.background_interval_ms.clamp(200, MAX_INTERVAL_MS),
+        power_saver_on_battery: input.power_saver_on_battery,
+        power_saver_interval_ms: input.power_saver_interval_ms.clamp(200, MAX_INTERVAL_MS),
+        app_cpu_budget_pct: input.app_cpu_budget_pct.min(100.0),
+    })
+}
+
+async fn has_background_consumer(state: &SharedState) -> bool {
+    state.snapshot_tx.receiver_count() > 0
+        || state.alerts.lock().await.has_enabled_rules()
+        || state.anomaly.lock().await.is_enabled()
+        || state.health_digest.lock().await.is_enabled()
+        || state.flight_recorder.lock().await.is_enabled()
+        || state.mqtt.lock().await.is_running()
+        || state.telemetry_stream.lock().await.is_running()
+}
+
+async fn has_interactive_consumer(state: &SharedState, window_visible: bool) -> bool {
+    window_visible
+        || state.settings.read().await.native_taskbar_monitor_enabled
+        || state.profiler.lock().await.is_active()
+}
+
+/// Decides the cadence for the next telemetry tick; called from the telemetry loop.
+pub async fn plan_tick(
+    state: &SharedState,
+    configured_interval_ms: u64,
+    window_visible: bool,
+) -> SamplingPlan {
+    let interactive = has_interactive_consumer(state, window_visible).await;
+    let consumer_check_due = state
+        .sampling
+        .lock()
+        .await
+        .last_consumer_check
+        .is_none_or(|at| at.elapsed() >= CONSUMER_CHECK_INTERVAL);
+    let background = if consumer_check_due && !interactive {
+        Some(has_background_consumer(state).await)
+    } else {
+        None
+    };
+
+    let mut runtime = state.sampling.lock().await;
+    if let Some(background) = background {
+        runtime.background = background;
+        runtime.last_consumer_check = Some(Instant::now());
+    }
+    if runtime
+        .last_power_check
+        .is_none_or(|at| at.elapsed() >= POWER_CHECK_INTERVAL)
+    {
+        runtime.on_battery = power::on_battery();
+        runtime.last_power_check = Some(Instant::now());
+    }
+    let consumers = if interactive {
+        ConsumerLevel::Interactive
+    } else if runtime.background {
+        ConsumerLevel::Background
+    } else {
+        ConsumerLevel::Idle
+    };
+    let on_battery = runtime.on_battery;
+    runtime
+        .sampler
+        .plan(configured_interval_ms, consumers, on_battery)
+}
+
+pub async fn observe_snapshot(
+    state: &SharedState,
+    plan: &SamplingPlan,
+    snapshot: &TelemetrySnapshot,
+) {
+    state
+        .sampling
+        .lock()
+        .await
+        .sampler
+        .observe_snapshot(plan, snapshot);
+}
+
+pub fn start_adaptive_sampling(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        let config = read_sampling_config(&app)
+            .and_then(|config| normalize_sampling_config(config).ok())
+            .unwrap_or_default();
+        state.sampling.lock().await.sampler.set_config(config);
+    });
+}
+
+#[tauri::command]
+pub async fn get_sampling_config(
+    state: State<'_, SharedState>,
+) -> CmdResult<AdaptiveSamplingConfig> {
+    Ok(state.sampling.lock().await.sampler.config().clone())
+}
+
+#[tauri::command]
+pub async fn save_sampling_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: AdaptiveSamplingConfig,
+) -> CmdResult<AdaptiveSamplingConfig> {
+    let normalized = normalize_sampling_config(config)?;
+    write_sampling_config(&app, &normalized)?;
+    state
+        .sampling
+        .lock()
+        .await
+        .sampler
+        .set_config(normalized.clone());
+    Ok(normalized)
+}
+
+#[tauri::command]
+pub async fn get_sampling_status(state: State<'_, SharedState>) -> CmdResult<SamplingStatus> {
+    Ok(state.sampling.lock().await.sampler.status())
+}
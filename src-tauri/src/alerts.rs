@@ -0,0 +1,706 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use chrono::{Local, Utc};
+use tauri::{AppHandle, State};
+
+use crate::{
+    app::SnapshotSource,
+    core::{
+        events::CoreEvent,
+        metric_path::MetricPath,
+        ports::{CoreContext, Notification, PathProvider},
+        reminders::{html_escape, reminder_email_shell},
+        store,
+        units::UnitFormatter,
+    },
+    state::SharedState,
+    tauri_adapter::TauriAdapter,
+    types::{AlertEvent, AlertRule, AlertRuleStore, TelemetrySnapshot},
+    webhooks::WebhookMessage,
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const ALERT_RULES_FILE: &str = "alert-rules.json";
+const ALERT_KIND_THRESHOLD: &str = "threshold";
+pub(crate) const ALERT_KIND_ANOMALY: &str = "anomaly";
+pub(crate) const ALERT_FIRED: &str = "fired";
+pub(crate) const ALERT_CLEARED: &str = "cleared";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparator {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl Comparator {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gt" | ">" => Some(Self::Gt),
+            "gte" | ">=" => Some(Self::Gte),
+            "lt" | "<" => Some(Self::Lt),
+            "lte" | "<=" => Some(Self::Lte),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+        }
+    }
+
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Gt => value > threshold,
+            Self::Gte => value >= threshold,
+            Self::Lt => value < threshold,
+            Self::Lte => value <= threshold,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct CompiledRule {
+    rule: AlertRule,
+    metric: MetricPath,
+    comparator: Comparator,
+}
+
+impl CompiledRule {
+    /// Whether both rules fire and clear on the same readings; names, cooldowns and
+    /// recipients may differ.
+    fn same_condition(&self, other: &Self) -> bool {
+        self.metric == other.metric
+            && self.comparator == other.comparator
+            && self.rule.threshold == other.rule.threshold
+            && self.rule.clear_threshold == other.rule.clear_threshold
+            && self.rule.sustain_secs == other.rule.sustain_secs
+    }
+}
+
+#[derive(Default)]
+struct RuleTracker {
+    breach_since: Option<Instant>,
+    active: bool,
+    last_fired: Option<Instant>,
+    last_event: Option<AlertEvent>,
+}
+
+/// Per-rule sustain/hysteresis/cooldown state, fed one snapshot at a time.
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<CompiledRule>,
+    trackers: HashMap<String, RuleTracker>,
+}
+
+impl AlertEngine {
+    fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        let previous = std::mem::take(&mut self.rules);
+        self.rules = rules
+            .into_iter()
+            .filter_map(|rule| {
+                let metric = MetricPath::parse(&rule.metric).ok()?;
+                let comparator = Comparator::parse(&rule.comparator)?;
+                Some(CompiledRule {
+                    rule,
+                    metric,
+                    comparator,
+                })
+            })
+            .collect();
+        // Keep state for rules whose condition survived the edit so an unchanged alert does not
+        // re-fire; a rule watching something else starts over.
+        self.trackers.retain(|id, _| {
+            let previous = previous.iter().find(|c| &c.rule.id == id);
+            let current = self.rules.iter().find(|c| &c.rule.id == id);
+            match (previous, current) {
+                (Some(previous), Some(current)) => previous.same_condition(current),
+                _ => false,
+            }
+        });
+    }
+
+    /// The same rules with no tracker state, for a replay to evaluate on its own clock.
+    fn replay_copy(&self) -> Self {
+        Self {
+            rules: self.rules.clone(),
+            trackers: HashMap::new(),
+        }
+    }
+
+    pub fn has_enabled_rules(&self) -> bool {
+        self.rules.iter().any(|compiled| compiled.rule.enabled)
+    }
+
+    fn active_alerts(&self) -> Vec<AlertEvent> {
+        self.rules
+            .iter()
+            .filter_map(|compiled| self.trackers.get(&compiled.rule.id))
+            .filter(|tracker| tracker.active)
+            .filter_map(|tracker| tracker.last_event.clone())
+            .collect()
+    }
+
+    fn evaluate(
+        &mut self,
+        snapshot: &TelemetrySnapshot,
+        now: Instant,
+    ) -> Vec<(AlertRule, AlertEvent)> {
+        let mut transitions = Vec::new();
+        for compiled in &self.rules {
+            let rule = &compiled.rule;
+            let tracker = self.trackers.entry(rule.id.clone()).or_default();
+            if !rule.enabled {
+                *tracker = RuleTracker::default();
+                continue;
+            }
+            let Some(value) = compiled.metric.value(snapshot) else {
+                tracker.breach_since = None;
+                continue;
+            };
+
+            if tracker.active {
+                let clear_at = rule.clear_threshold.unwrap_or(rule.threshold);
+                if !compiled.comparator.holds(value, clear_at) {
+                    tracker.active = false;
+                    tracker.breach_since = None;
+                    let event = alert_event(rule, ALERT_CLEARED, value, clear_at);
+                    tracker.last_event = Some(event.clone());
+                    transitions.push((rule.clone(), event));
+                }
+                continue;
+            }
+
+            if !compiled.comparator.holds(value, rule.threshold) {
+                tracker.breach_since = None;
+                continue;
+            }
+            let since = *tracker.breach_since.get_or_insert(now);
+            let sustained = now.duration_since(since) >= Duration::from_secs(rule.sustain_secs);
+            let cooled_down = tracker
+                .last_fired
+                .is_none_or(|at| now.duration_since(at) >= Duration::from_secs(rule.cooldown_secs));
+            if sustained && cooled_down {
+                tracker.active = true;
+                tracker.last_fired = Some(now);
+                let event = alert_event(rule, ALERT_FIRED, value, rule.threshold);
+                tracker.last_event = Some(event.clone());
+                transitions.push((rule.clone(), event));
+            }
+        }
+        transitions
+    }
+}
+
+fn alert_event(rule: &AlertRule, state: &str, value: f64, threshold: f64) -> AlertEvent {
+    AlertEvent {
+        kind: ALERT_KIND_THRESHOLD.to_string(),
+        rule_id: rule.id.clone(),
+        name: rule.name.clone(),
+        metric: rule.metric.clone(),
+        state: state.to_string(),
+        value,
+        threshold,
+        timestamp: Utc::now().to_rfc3339(),
+    }
+}
+
+fn read_alert_rules_file(paths: &dyn PathProvider) -> Option<AlertRuleStore> {
+    store::read_json(paths, ALERT_RULES_FILE)
+}
+
+fn write_alert_rules_file(paths: &dyn PathProvider, rules: &AlertRuleStore) -> CmdResult<()> {
+    store::write_json(paths, ALERT_RULES_FILE, rules)
+}
+
+fn normalize_alert_rule(mut rule: AlertRule, index: usize) -> CmdResult<AlertRule> {
+    rule.id = rule.id.trim().to_string();
+    if rule.id.is_empty() {
+        rule.id = format!("alert-{}-{index}", Utc::now().timestamp_millis());
+    }
+    rule.name = rule.name.trim().to_string();
+    rule.metric = rule.metric.trim().to_string();
+    if rule.name.is_empty() {
+        rule.name = rule.metric.clone();
+    }
+    MetricPath::parse(&rule.metric).map_err(|e| format!("alert \"{}\": {e}", rule.name))?;
+    let comparator = Comparator::parse(&rule.comparator).ok_or_else(|| {
+        format!(
+            "alert \"{}\": unsupported comparator {} (expected gt, gte, lt or lte)",
+            rule.name, rule.comparator
+        )
+    })?;
+    rule.comparator = comparator.as_str().to_string();
+    if !rule.threshold.is_finite() {
+        return Err(format!(
+            "alert \"{}\": threshold is not a number",
+            rule.name
+        ));
+    }
+    rule.clear_threshold = rule.clear_threshold.filter(|value| value.is_finite());
+    rule.email = rule.email.trim().to_string();
+    rule.webhook_id = rule.webhook_id.trim().to_string();
+    Ok(rule)
+}
+
+fn normalize_alert_rule_store(input: AlertRuleStore) -> CmdResult<AlertRuleStore> {
+    let rules = input
+        .rules
+        .into_iter()
+        .enumerate()
+        .map(|(index, rule)| normalize_alert_rule(rule, index))
+        .collect::<CmdResult<Vec<_>>>()?;
+    Ok(AlertRuleStore { rules })
+}
+
+/// A fired or cleared event plus where to deliver it. Threshold rules and the anomaly
+/// detector both go through [`dispatch_alert`].
+pub(crate) struct AlertNotice {
+    pub event: AlertEvent,
+    /// Human readable condition, e.g. `gpu.temp > 85 for 30s`
+    pub condition: String,
+    pub email: String,
+    pub webhook_id: String,
+}
+
+impl AlertNotice {
+    fn from_rule(rule: &AlertRule, event: AlertEvent) -> Self {
+        let comparator = Comparator::parse(&rule.comparator).unwrap_or(Comparator::Gt);
+        let mut condition = format!("{} {} {}", rule.metric, comparator.symbol(), rule.threshold);
+        if rule.sustain_secs > 0 {
+            condition.push_str(&format!(" for {}s", rule.sustain_secs));
+        }
+        Self {
+            event,
+            condition,
+            email: rule.email.clone(),
+            webhook_id: rule.webhook_id.clone(),
+        }
+    }
+}
+
+fn alert_email(notice: &AlertNotice, units: &UnitFormatter) -> (String, String, String) {
+    let event = &notice.event;
+    let value = units.metric(&event.metric, event.value);
+    let local_time = Local::now().format("%Y-%m-%d %H:%M:%S");
+    let subject = format!("[PulseCore] Alert: {}", event.name);
+    let plain = format!(
+        "{}\n\nCondition: {}\nCurrent value: {value}\nTime: {local_time}",
+        event.name, notice.condition
+    );
+    let inner = format!(
+        r#"<p style="margin:0 0 14px;line-height:1.65;">Condition <code>{}</code> has been met.</p>
+      <p style="margin:0 0 6px;">Current value: <strong>{}</strong></p>
+      <p style="margin:0;color:#5b6b82;">{local_time}</p>"#,
+        html_escape(&notice.condition),
+        html_escape(&value)
+    );
+    let html = reminder_email_shell(&subject, inner);
+    (subject, plain, html)
+}
+
+fn alert_webhook_message(notice: &AlertNotice, units: &UnitFormatter) -> WebhookMessage {
+    let event = &notice.event;
+    let title = if event.state == ALERT_FIRED {
+        format!("Alert fired: {}", event.name)
+    } else {
+        format!("Alert cleared: {}", event.name)
+    };
+    WebhookMessage::new(
+        "alert",
+        title,
+        format!(
+            "{}\nCurrent value: {}",
+            notice.condition,
+            units.metric(&event.metric, event.value)
+        ),
+    )
+}
+
+/// Publishes `alert://fired` / `alert://cleared`, posts to the notice's webhook and mails fired
+/// alerts when a recipient is set.
+pub(crate) async fn dispatch_alert(ctx: &CoreContext, state: &SharedState, notice: AlertNotice) {
+    let event = &notice.event;
+    ctx.events.publish(CoreEvent::Alert(event.clone()));
+    let units = UnitFormatter::lenient(&state.settings.read().await.units);
+    if !notice.webhook_id.is_empty() {
+        let message = alert_webhook_message(&notice, &units);
+        send_in_background(
+            ctx,
+            format!("alert webhook {}", notice.webhook_id),
+            Notification::Webhook {
+                endpoint_id: notice.webhook_id.clone(),
+                event: message.event,
+                title: message.title,
+                text: message.text,
+            },
+        );
+    }
+    if event.state != ALERT_FIRED {
+        return;
+    }
+    crate::digest::record_alert(state, event).await;
+    crate::flight_recorder::on_alert_fired(ctx, state, &event.rule_id).await;
+    if notice.email.is_empty() {
+        return;
+    }
+    let Some(smtp) = state.reminder_smtp_config.read().await.clone() else {
+        tracing::warn!("alert {} has a recipient but no smtp config", event.rule_id);
+        return;
+    };
+    let (subject, text, html) = alert_email(&notice, &units);
+    send_in_background(
+        ctx,
+        format!("alert email for {}", event.rule_id),
+        Notification::Email {
+            smtp,
+            to: notice.email.clone(),
+            subject,
+            text,
+            html: Some(html),
+        },
+    );
+}
+
+fn send_in_background(ctx: &CoreContext, what: String, notification: Notification) {
+    let notifier = ctx.notifier.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = notifier.notify(notification).await {
+            tracing::warn!("failed to send {what}: {err}");
+        }
+    });
+}
+
+/// Runs the rules against a published snapshot. Breach durations and cooldowns follow the
+/// replay's virtual clock for recorded snapshots.
+pub async fn evaluate_snapshot(
+    ctx: &CoreContext,
+    state: &SharedState,
+    snapshot: &TelemetrySnapshot,
+    source: SnapshotSource,
+) {
+    let now = match source {
+        SnapshotSource::Live => Instant::now(),
+        SnapshotSource::Replay { now } => now,
+    };
+    // Replays run on `replay_alerts` so their virtual clock never stamps live cooldowns.
+    let engine = match source {
+        SnapshotSource::Live => &state.alerts,
+        SnapshotSource::Replay { .. } => &state.replay_alerts,
+    };
+    let transitions = {
+        let mut engine = engine.lock().await;
+        if !engine.has_enabled_rules() {
+            return;
+        }
+        engine.evaluate(snapshot, now)
+    };
+    for (rule, event) in transitions {
+        match source {
+            SnapshotSource::Live => {
+                dispatch_alert(ctx, state, AlertNotice::from_rule(&rule, event)).await
+            }
+            // Shown in the app only; a replayed alert is not news to mail, post or count.
+            SnapshotSource::Replay { .. } => ctx.events.publish(CoreEvent::Alert(event)),
+        }
+    }
+}
+
+/// Gives a starting replay a fresh engine with the live rules.
+pub async fn reset_replay_alerts(state: &SharedState) {
+    let engine = state.alerts.lock().await.replay_copy();
+    *state.replay_alerts.lock().await = engine;
+}
+
+pub fn start_alert_engine(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        let Some(store) = read_alert_rules_file(&TauriAdapter::new(&app)) else {
+            return;
+        };
+        match normalize_alert_rule_store(store) {
+            Ok(store) => state.alerts.lock().await.set_rules(store.rules),
+            Err(err) => tracing::warn!("ignoring invalid alert rules file: {err}"),
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_alert_rules(app: AppHandle) -> CmdResult<AlertRuleStore> {
+    Ok(read_alert_rules_file(&TauriAdapter::new(&app))
+        .and_then(|store| normalize_alert_rule_store(store).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn save_alert_rules(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    store: AlertRuleStore,
+) -> CmdResult<AlertRuleStore> {
+    let normalized = normalize_alert_rule_store(store)?;
+    write_alert_rules_file(&TauriAdapter::new(&app), &normalized)?;
+    state
+        .alerts
+        .lock()
+        .await
+        .set_rules(normalized.rules.clone());
+    Ok(normalized)
+}
+
+#[tauri::command]
+pub async fn get_active_alerts(state: State<'_, SharedState>) -> CmdResult<Vec<AlertEvent>> {
+    let mut active = state.alerts.lock().await.active_alerts();
+    active.extend(crate::anomaly::active_anomalies(state.inner()).await);
+    Ok(active)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        core::{
+            events::EventBus,
+            ports::{DirPaths, NoWindows, RecordingNotifier, SystemClock},
+            test_support,
+        },
+        state::AppState,
+        types::AppSettings,
+    };
+
+    fn snapshot_with_gpu_temp(temp: f64) -> TelemetrySnapshot {
+        let mut snapshot = test_support::snapshot();
+        snapshot.gpu.temperature_c = Some(temp);
+        snapshot
+    }
+
+    fn gpu_hot_rule() -> AlertRule {
+        normalize_alert_rule(
+            AlertRule {
+                id: "gpu-hot".to_string(),
+                enabled: true,
+                name: "GPU hot".to_string(),
+                metric: "gpu.temp".to_string(),
+                comparator: ">".to_string(),
+                threshold: 85.0,
+                sustain_secs: 30,
+                clear_threshold: Some(80.0),
+                cooldown_secs: 300,
+                email: String::new(),
+                webhook_id: String::new(),
+            },
+            0,
+        )
+        .unwrap()
+    }
+
+    fn states(transitions: &[(AlertRule, AlertEvent)]) -> Vec<&str> {
+        transitions.iter().map(|(_, e)| e.state.as_str()).collect()
+    }
+
+    #[test]
+    fn fires_after_sustain_and_clears_below_hysteresis() {
+        let mut engine = AlertEngine::default();
+        engine.set_rules(vec![gpu_hot_rule()]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(engine
+            .evaluate(&snapshot_with_gpu_temp(90.0), at(0))
+            .is_empty());
+        assert!(engine
+            .evaluate(&snapshot_with_gpu_temp(90.0), at(29))
+            .is_empty());
+        let fired = engine.evaluate(&snapshot_with_gpu_temp(88.0), at(30));
+        assert_eq!(states(&fired), vec![ALERT_FIRED]);
+        assert_eq!(engine.active_alerts().len(), 1);
+
+        // Between clear threshold and threshold the alert stays active.
+        assert!(engine
+            .evaluate(&snapshot_with_gpu_temp(82.0), at(40))
+            .is_empty());
+        let cleared = engine.evaluate(&snapshot_with_gpu_temp(79.0), at(50));
+        assert_eq!(states(&cleared), vec![ALERT_CLEARED]);
+        assert!(engine.active_alerts().is_empty());
+    }
+
+    #[test]
+    fn dip_resets_sustain_window() {
+        let mut engine = AlertEngine::default();
+        engine.set_rules(vec![gpu_hot_rule()]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        engine.evaluate(&snapshot_with_gpu_temp(90.0), at(0));
+        engine.evaluate(&snapshot_with_gpu_temp(70.0), at(20));
+        assert!(engine
+            .evaluate(&snapshot_with_gpu_temp(90.0), at(31))
+            .is_empty());
+        assert_eq!(
+            states(&engine.evaluate(&snapshot_with_gpu_temp(90.0), at(61))),
+            vec![ALERT_FIRED]
+        );
+    }
+
+    #[test]
+    fn cooldown_suppresses_refire() {
+        let mut engine = AlertEngine::default();
+        engine.set_rules(vec![gpu_hot_rule()]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        engine.evaluate(&snapshot_with_gpu_temp(90.0), at(0));
+        engine.evaluate(&snapshot_with_gpu_temp(90.0), at(30));
+        engine.evaluate(&snapshot_with_gpu_temp(70.0), at(40));
+        engine.evaluate(&snapshot_with_gpu_temp(90.0), at(50));
+        assert!(engine
+            .evaluate(&snapshot_with_gpu_temp(90.0), at(120))
+            .is_empty());
+        assert_eq!(
+            states(&engine.evaluate(&snapshot_with_gpu_temp(90.0), at(330))),
+            vec![ALERT_FIRED]
+        );
+    }
+
+    #[test]
+    fn editing_the_condition_resets_the_tracker() {
+        let mut engine = AlertEngine::default();
+        engine.set_rules(vec![gpu_hot_rule()]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        engine.evaluate(&snapshot_with_gpu_temp(90.0), at(0));
+        engine.evaluate(&snapshot_with_gpu_temp(90.0), at(30));
+        assert_eq!(engine.active_alerts().len(), 1);
+
+        let mut renamed = gpu_hot_rule();
+        renamed.name = "GPU very hot".to_string();
+        renamed.cooldown_secs = 60;
+        engine.set_rules(vec![renamed]);
+        assert_eq!(engine.active_alerts().len(), 1);
+
+        let mut stricter = gpu_hot_rule();
+        stricter.threshold = 95.0;
+        engine.set_rules(vec![stricter]);
+        assert!(engine.active_alerts().is_empty());
+        assert!(engine
+            .evaluate(&snapshot_with_gpu_temp(96.0), at(40))
+            .is_empty());
+    }
+
+    fn context(notifier: Arc<RecordingNotifier>, bus: EventBus) -> CoreContext {
+        CoreContext {
+            clock: Arc::new(SystemClock),
+            paths: Arc::new(DirPaths(std::env::temp_dir())),
+            notifier,
+            events: Arc::new(bus),
+            windows: Arc::new(NoWindows),
+        }
+    }
+
+    #[tokio::test]
+    async fn replayed_alerts_stay_in_the_app() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let bus = EventBus::default();
+        let mut events = bus.subscribe();
+        let ctx = context(notifier.clone(), bus);
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        let mut rule = gpu_hot_rule();
+        rule.sustain_secs = 0;
+        rule.webhook_id = "hook".to_string();
+        state.alerts.lock().await.set_rules(vec![rule]);
+        reset_replay_alerts(&state).await;
+        let mut subscribers = state.snapshot_tx.subscribe();
+
+        crate::app::publish_snapshot(
+            &ctx,
+            &state,
+            snapshot_with_gpu_temp(90.0),
+            SnapshotSource::Replay {
+                now: Instant::now(),
+            },
+        )
+        .await;
+
+        match events.try_recv() {
+            Ok(CoreEvent::Alert(event)) => assert_eq!(event.state, ALERT_FIRED),
+            other => panic!("expected an alert event, got {other:?}"),
+        }
+        assert!(notifier.sent().is_empty());
+        assert!(subscribers.try_recv().is_err());
+        assert_eq!(
+            state.latest_snapshot.read().await.gpu.temperature_c,
+            Some(90.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_leave_live_alert_state_alone() {
+        let bus = EventBus::default();
+        let mut events = bus.subscribe();
+        let ctx = context(Arc::new(RecordingNotifier::default()), bus);
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        let mut rule = gpu_hot_rule();
+        rule.sustain_secs = 0;
+        state.alerts.lock().await.set_rules(vec![rule]);
+        let live_now = Instant::now();
+        let fired = state
+            .alerts
+            .lock()
+            .await
+            .evaluate(&snapshot_with_gpu_temp(90.0), live_now);
+        assert_eq!(fired.len(), 1);
+
+        reset_replay_alerts(&state).await;
+        let replay_start = live_now + Duration::from_secs(3600);
+        for (temp, offset) in [(90.0, 0), (40.0, 10)] {
+            crate::app::publish_snapshot(
+                &ctx,
+                &state,
+                snapshot_with_gpu_temp(temp),
+                SnapshotSource::Replay {
+                    now: replay_start + Duration::from_secs(offset),
+                },
+            )
+            .await;
+        }
+        let mut replayed = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let CoreEvent::Alert(event) = event {
+                replayed.push(event.state);
+            }
+        }
+        assert_eq!(replayed, [ALERT_FIRED, ALERT_CLEARED]);
+
+        let engine = state.alerts.lock().await;
+        let tracker = &engine.trackers["gpu-hot"];
+        assert!(tracker.active);
+        assert_eq!(tracker.last_fired, Some(live_now));
+        assert_eq!(engine.active_alerts().len(), 1);
+    }
+
+    #[test]
+    fn rejects_unknown_metric() {
+        let mut rule = gpu_hot_rule();
+        rule.metric = "gpu.bogus".to_string();
+        assert!(normalize_alert_rule(rule, 0).is_err());
+    }
+}
@@ -0,0 +1,462 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use chrono::{Local, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::{
+    alerts::{dispatch_alert, AlertNotice, ALERT_CLEARED, ALERT_FIRED, ALERT_KIND_ANOMALY},
+    core::{
+        metric_path::MetricPath,
+        ports::{CoreContext, PathProvider},
+        store,
+        units::UnitFormatter,
+    },
+    state::SharedState,
+    tauri_adapter::TauriAdapter,
+    types::{AlertEvent, AnomalyDetectionConfig, TelemetrySnapshot},
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const ANOMALY_CONFIG_FILE: &str = "anomaly-detection.json";
+const ANOMALY_BASELINE_FILE: &str = "anomaly-baselines.json";
+const ANOMALY_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+const BASELINE_SAVE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const HOURS_PER_DAY: usize = 24;
+/// Anomalous samples still teach the baseline, just slower, so a lasting level shift is
+/// eventually accepted while a spike barely moves it.
+const ANOMALOUS_LEARNING_FACTOR: f64 = 0.1;
+/// Standard deviation floors keep near-constant metrics from flagging tiny wiggles.
+const MIN_RELATIVE_STD: f64 = 0.02;
+const MIN_ABSOLUTE_STD: f64 = 1e-3;
+
+/// Exponentially weighted mean/variance for one metric in one hour-of-day bucket.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ewma {
+    mean: f64,
+    var: f64,
+    count: u32,
+}
+
+impl Ewma {
+    /// Behaves like a plain running average until the bucket has enough history.
+    fn learning_rate(&self, alpha: f64) -> f64 {
+        alpha.max(1.0 / (f64::from(self.count) + 1.0))
+    }
+
+    fn update(&mut self, value: f64, alpha: f64) {
+        if self.count == 0 {
+            self.mean = value;
+            self.var = 0.0;
+        } else {
+            let diff = value - self.mean;
+            let increment = alpha * diff;
+            self.mean += increment;
+            self.var = (1.0 - alpha) * (self.var + diff * increment);
+        }
+        self.count = self.count.saturating_add(1);
+    }
+
+    fn std_dev(&self) -> f64 {
+        self.var
+            .max(0.0)
+            .sqrt()
+            .max(self.mean.abs() * MIN_RELATIVE_STD)
+            .max(MIN_ABSOLUTE_STD)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BaselineFile {
+    buckets: HashMap<String, Vec<Ewma>>,
+}
+
+#[derive(Default)]
+struct AnomalyTracker {
+    anomalous_streak: u32,
+    calm_streak: u32,
+    active: bool,
+    last_fired: Option<Instant>,
+    last_event: Option<AlertEvent>,
+}
+
+struct AnomalyTransition {
+    event: AlertEvent,
+    condition: String,
+}
+
+/// Hour-of-day EWMA baselines per metric plus fire/clear tracking.
+#[derive(Default)]
+pub struct AnomalyDetector {
+    config: AnomalyDetectionConfig,
+    metrics: Vec<(String, MetricPath)>,
+    buckets: HashMap<String, Vec<Ewma>>,
+    trackers: HashMap<String, AnomalyTracker>,
+    last_sample: Option<Instant>,
+    last_saved: Option<Instant>,
+    dirty: bool,
+}
+
+impl AnomalyDetector {
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.metrics.is_empty()
+    }
+
+    fn set_config(&mut self, config: AnomalyDetectionConfig) {
+        self.metrics = config
+            .metrics
+            .iter()
+            .filter_map(|path| Some((path.clone(), MetricPath::parse(path).ok()?)))
+            .collect();
+        let keep: HashSet<&str> = self.metrics.iter().map(|(key, _)| key.as_str()).collect();
+        self.trackers.retain(|key, _| keep.contains(key.as_str()));
+        self.config = config;
+    }
+
+    fn load_baselines(&mut self, file: BaselineFile) {
+        self.buckets = file
+            .buckets
+            .into_iter()
+            .filter(|(_, buckets)| buckets.len() == HOURS_PER_DAY)
+            .collect();
+    }
+
+    fn baseline_file(&self) -> BaselineFile {
+        BaselineFile {
+            buckets: self.buckets.clone(),
+        }
+    }
+
+    fn active_events(&self) -> Vec<AlertEvent> {
+        self.trackers
+            .values()
+            .filter(|tracker| tracker.active)
+            .filter_map(|tracker| tracker.last_event.clone())
+            .collect()
+    }
+
+    fn observe(
+        &mut self,
+        snapshot: &TelemetrySnapshot,
+        hour: usize,
+        now: Instant,
+        units: &UnitFormatter,
+    ) -> Vec<AnomalyTransition> {
+        let config = &self.config;
+        let mut transitions = Vec::new();
+        for (key, path) in &self.metrics {
+            let Some(value) = path.value(snapshot) else {
+                continue;
+            };
+            let bucket = &mut self
+                .buckets
+                .entry(key.clone())
+                .or_insert_with(|| vec![Ewma::default(); HOURS_PER_DAY])[hour];
+            let tracker = self.trackers.entry(key.clone()).or_default();
+
+            let mean = bucket.mean;
+            let std_dev = bucket.std_dev();
+            let z = (value - mean) / std_dev;
+            let anomalous = bucket.count >= config.warmup_samples && z.abs() > config.sigma;
+            let mut learning = bucket.learning_rate(config.alpha);
+            if anomalous {
+                learning *= ANOMALOUS_LEARNING_FACTOR;
+            }
+            bucket.update(value, learning);
+            self.dirty = true;
+
+            if anomalous {
+                tracker.anomalous_streak += 1;
+                tracker.calm_streak = 0;
+            } else {
+                tracker.anomalous_streak = 0;
+                tracker.calm_streak += 1;
+            }
+
+            // The σ band is shown as a range so offset units like °F convert correctly.
+            let condition = format!(
+                "{key} is {z:+.1}σ from its {hour:02}:00 baseline (mean {}, 1σ range {}–{})",
+                units.metric(key, mean),
+                units.metric(key, mean - std_dev),
+                units.metric(key, mean + std_dev),
+            );
+            let bound = mean + z.signum() * config.sigma * std_dev;
+            if tracker.active {
+                if tracker.calm_streak >= config.sustain_samples {
+                    tracker.active = false;
+                    let event = anomaly_event(key, ALERT_CLEARED, value, bound);
+                    tracker.last_event = Some(event.clone());
+                    transitions.push(AnomalyTransition { event, condition });
+                }
+                continue;
+            }
+            let cooled_down = tracker.last_fired.is_none_or(|at| {
+                now.duration_since(at) >= Duration::from_secs(config.cooldown_secs)
+            });
+            if tracker.anomalous_streak >= config.sustain_samples && cooled_down {
+                tracker.active = true;
+                tracker.last_fired = Some(now);
+                let event = anomaly_event(key, ALERT_FIRED, value, bound);
+                tracker.last_event = Some(event.clone());
+                transitions.push(AnomalyTransition { event, condition });
+            }
+        }
+        transitions
+    }
+}
+
+fn anomaly_event(metric: &str, state: &str, value: f64, threshold: f64) -> AlertEvent {
+    AlertEvent {
+        kind: ALERT_KIND_ANOMALY.to_string(),
+        rule_id: format!("anomaly:{metric}"),
+        name: format!("Anomaly: {metric}"),
+        metric: metric.to_string(),
+        state: state.to_string(),
+        value,
+        threshold,
+        timestamp: Utc::now().to_rfc3339(),
+    }
+}
+
+fn read_anomaly_config(paths: &dyn PathProvider) -> Option<AnomalyDetectionConfig> {
+    store::read_json(paths, ANOMALY_CONFIG_FILE)
+}
+
+fn write_anomaly_config(
+    paths: &dyn PathProvider,
+    config: &AnomalyDetectionConfig,
+) -> CmdResult<()> {
+    store::write_json(paths, ANOMALY_CONFIG_FILE, config)
+}
+
+fn read_baseline_file(paths: &dyn PathProvider) -> Option<BaselineFile> {
+    store::read_json(paths, ANOMALY_BASELINE_FILE)
+}
+
+fn write_baseline_file(paths: &dyn PathProvider, file: &BaselineFile) -> CmdResult<()> {
+    store::write_json_compact(paths, ANOMALY_BASELINE_FILE, file)
+}
+
+fn normalize_anomaly_config(input: AnomalyDetectionConfig) -> CmdResult<AnomalyDetectionConfig> {
+    let defaults = AnomalyDetectionConfig::default();
+    let mut seen = HashSet::new();
+    let mut metrics = Vec::new();
+    for metric in input.metrics {
+        let metric = metric.trim().to_string();
+        if metric.is_empty() {
+            continue;
+        }
+        MetricPath::parse(&metric)?;
+        if seen.insert(metric.to_ascii_lowercase()) {
+            metrics.push(metric);
+        }
+    }
+    let finite_or = |value: f64, fallback: f64| {
+        if value.is_finite() {
+            value
+        } else {
+            fallback
+        }
+    };
+    Ok(AnomalyDetectionConfig {
+        enabled: input.enabled,
+        metrics,
+        sigma: finite_or(input.sigma, defaults.sigma).clamp(1.0, 10.0),
+        alpha: finite_or(input.alpha, defaults.alpha).clamp(0.001, 0.5),
+        warmup_samples: input.warmup_samples.max(1),
+        sustain_samples: input.sustain_samples.clamp(1, 100),
+        cooldown_secs: input.cooldown_secs,
+        email: input.email.trim().to_string(),
+        webhook_id: input.webhook_id.trim().to_string(),
+    })
+}
+
+/// Feeds the detector from the telemetry loop, sampling at most every
+/// [`ANOMALY_SAMPLE_INTERVAL`] so baselines do not depend on the refresh rate.
+pub async fn evaluate_snapshot(
+    ctx: &CoreContext,
+    state: &SharedState,
+    snapshot: &TelemetrySnapshot,
+) {
+    let now = Instant::now();
+    let units = UnitFormatter::lenient(&state.settings.read().await.units);
+    let (transitions, config, to_save) = {
+        let mut detector = state.anomaly.lock().await;
+        if !detector.config.enabled || detector.metrics.is_empty() {
+            return;
+        }
+        if detector
+            .last_sample
+            .is_some_and(|at| now.duration_since(at) < ANOMALY_SAMPLE_INTERVAL)
+        {
+            return;
+        }
+        detector.last_sample = Some(now);
+        let hour = Local::now().hour() as usize;
+        let transitions = detector.observe(snapshot, hour, now, &units);
+        let save_due = detector
+            .last_saved
+            .is_none_or(|at| now.duration_since(at) >= BASELINE_SAVE_INTERVAL);
+        let to_save = (detector.dirty && save_due).then(|| {
+            detector.dirty = false;
+            detector.last_saved = Some(now);
+            detector.baseline_file()
+        });
+        (transitions, detector.config.clone(), to_save)
+    };
+
+    if let Some(file) = to_save {
+        if let Err(err) = write_baseline_file(&*ctx.paths, &file) {
+            tracing::debug!("failed to persist anomaly baselines: {err}");
+        }
+    }
+    for transition in transitions {
+        let notice = AlertNotice {
+            event: transition.event,
+            condition: transition.condition,
+            email: config.email.clone(),
+            webhook_id: config.webhook_id.clone(),
+        };
+        dispatch_alert(ctx, state, notice).await;
+    }
+}
+
+pub(crate) async fn active_anomalies(state: &SharedState) -> Vec<AlertEvent> {
+    state.anomaly.lock().await.active_events()
+}
+
+pub fn start_anomaly_detector(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        let paths = TauriAdapter::new(&app);
+        let config = read_anomaly_config(&paths)
+            .and_then(|config| normalize_anomaly_config(config).ok())
+            .unwrap_or_default();
+        let mut detector = state.anomaly.lock().await;
+        if let Some(file) = read_baseline_file(&paths) {
+            detector.load_baselines(file);
+        }
+        detector.set_config(config);
+    });
+}
+
+#[tauri::command]
+pub fn get_anomaly_config(app: AppHandle) -> CmdResult<AnomalyDetectionConfig> {
+    Ok(read_anomaly_config(&TauriAdapter::new(&app))
+        .and_then(|config| normalize_anomaly_config(config).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn save_anomaly_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: AnomalyDetectionConfig,
+) -> CmdResult<AnomalyDetectionConfig> {
+    let normalized = normalize_anomaly_config(config)?;
+    write_anomaly_config(&TauriAdapter::new(&app), &normalized)?;
+    state.anomaly.lock().await.set_config(normalized.clone());
+    Ok(normalized)
+}
+
+#[tauri::command]
+pub async fn reset_anomaly_baselines(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+) -> CmdResult<()> {
+    let mut detector = state.anomaly.lock().await;
+    detector.buckets.clear();
+    detector.trackers.clear();
+    detector.dirty = false;
+    write_baseline_file(&TauriAdapter::new(&app), &BaselineFile::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support;
+
+    fn snapshot_with_app_memory(mb: f64) -> TelemetrySnapshot {
+        let mut snapshot = test_support::snapshot();
+        snapshot.app_memory_mb = Some(mb);
+        snapshot
+    }
+
+    fn detector() -> AnomalyDetector {
+        let mut detector = AnomalyDetector::default();
+        detector.set_config(
+            normalize_anomaly_config(AnomalyDetectionConfig {
+                enabled: true,
+                metrics: vec!["app.mem".to_string()],
+                warmup_samples: 20,
+                sustain_samples: 2,
+                cooldown_secs: 0,
+                ..AnomalyDetectionConfig::default()
+            })
+            .unwrap(),
+        );
+        detector
+    }
+
+    #[test]
+    fn ewma_tracks_mean_and_variance() {
+        let mut ewma = Ewma::default();
+        for value in [10.0, 12.0, 10.0, 12.0] {
+            ewma.update(value, ewma.learning_rate(0.01));
+        }
+        assert!((ewma.mean - 11.0).abs() < 1e-9);
+        assert!(ewma.std_dev() > 0.5 && ewma.std_dev() < 1.5);
+    }
+
+    #[test]
+    fn climbing_app_memory_fires_then_clears() {
+        let mut detector = detector();
+        let units = UnitFormatter::default();
+        let now = Instant::now();
+        for i in 0..40 {
+            let noise = if i % 2 == 0 { 1.0 } else { -1.0 };
+            let fired = detector.observe(&snapshot_with_app_memory(120.0 + noise), 9, now, &units);
+            assert!(fired.is_empty());
+        }
+
+        assert!(detector
+            .observe(&snapshot_with_app_memory(400.0), 9, now, &units)
+            .is_empty());
+        let fired = detector.observe(&snapshot_with_app_memory(420.0), 9, now, &units);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].event.state, ALERT_FIRED);
+        assert_eq!(fired[0].event.kind, ALERT_KIND_ANOMALY);
+        assert!(
+            fired[0].condition.contains(" MB–"),
+            "{}",
+            fired[0].condition
+        );
+        assert_eq!(detector.active_events().len(), 1);
+
+        detector.observe(&snapshot_with_app_memory(121.0), 9, now, &units);
+        let cleared = detector.observe(&snapshot_with_app_memory(119.0), 9, now, &units);
+        assert_eq!(cleared.len(), 1);
+        assert_eq!(cleared[0].event.state, ALERT_CLEARED);
+    }
+
+    #[test]
+    fn hour_buckets_are_independent() {
+        let mut detector = detector();
+        let units = UnitFormatter::default();
+        let now = Instant::now();
+        for _ in 0..40 {
+            detector.observe(&snapshot_with_app_memory(100.0), 3, now, &units);
+        }
+        // Hour 15 has no history yet, so a very different value is not anomalous there.
+        for _ in 0..5 {
+            assert!(detector
+                .observe(&snapshot_with_app_memory(900.0), 15, now, &units)
+                .is_empty());
+        }
+    }
+}
@@ -1,16 +1,23 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use chrono::{Datelike, Local, Timelike};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::AppHandle;
 
 use crate::{
+    core::{
+        events::CoreEvent,
+        ports::CoreContext,
+        reminders::{self, DueReminder},
+        units::UnitFormatter,
+    },
     ipc::{commands, game_sync, steam_market},
     state::SharedState,
-    types::TaskReminder,
+    tauri_adapter::core_context,
+    types::TelemetrySnapshot,
 };
 
 pub fn start_telemetry_loop(app: AppHandle, state: SharedState) {
     tauri::async_runtime::spawn(async move {
+        let ctx = core_context(&app);
         let mut current_rate = state
             .refresh_rate_ms
             .load(std::sync::atomic::Ordering::Relaxed)
@@ -19,47 +26,79 @@ pub fn start_telemetry_loop(app: AppHandle, state: SharedState) {
         ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
         loop {
+            ticker.tick().await;
+
+            let has_visible_consumer = !ctx.windows.visible_labels().is_empty();
             let configured_rate = state
                 .refresh_rate_ms
                 .load(std::sync::atomic::Ordering::Relaxed)
                 .max(10);
-            if configured_rate != current_rate {
-                current_rate = configured_rate;
-                ticker = tokio::time::interval(Duration::from_millis(current_rate));
+            let plan =
+                crate::adaptive_sampling::plan_tick(&state, configured_rate, has_visible_consumer)
+                    .await;
+            if plan.interval_ms != current_rate {
+                current_rate = plan.interval_ms;
+                let period = Duration::from_millis(current_rate);
+                ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
                 ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
             }
-            ticker.tick().await;
-
-            let visible_labels = visible_consumer_labels(&app);
-            let has_visible_consumer = !visible_labels.is_empty();
-            let snapshot = state.collect_snapshot(current_rate).await;
-
-            state.record_snapshot(snapshot.clone()).await;
-            crate::native_taskbar::refresh(snapshot.clone());
-
-            if has_visible_consumer {
-                for label in &visible_labels {
-                    if let Err(e) = app.emit_to(*label, "telemetry://snapshot", snapshot.clone()) {
-                        tracing::warn!("failed to emit telemetry snapshot to {label}: {e}");
-                    }
-                }
+            // A replay owns the publishing path until it ends; keep the live collector quiet.
+            if state.replay.lock().await.is_active() {
+                continue;
             }
+            let snapshot = state.collect_snapshot(plan).await;
+            crate::adaptive_sampling::observe_snapshot(&state, &plan, &snapshot).await;
 
-            if visible_labels.iter().any(|label| *label == "main") {
-                if let Some(win) = app.get_webview_window("main") {
-                    let title = format!(
-                        "PulseCore | CPU {:.0}% | RAM {:.0}% | Down {:.1} MB/s",
-                        snapshot.cpu.usage_pct,
-                        snapshot.memory.usage_pct,
-                        snapshot.network.download_bytes_per_sec / 1024.0 / 1024.0
-                    );
-                    let _ = win.set_title(&title);
-                }
-            }
+            publish_snapshot(&ctx, &state, snapshot, SnapshotSource::Live).await;
         }
     });
 }
 
+/// Where a published snapshot came from.
+#[derive(Debug, Clone, Copy)]
+pub enum SnapshotSource {
+    Live,
+    /// A recorded profile; `now` is the replay's virtual clock so alert hold times follow
+    /// the recording rather than the wall clock.
+    Replay {
+        now: Instant,
+    },
+}
+
+/// Hands a snapshot to every live consumer: stream/MQTT subscribers, the native taskbar,
+/// alert rules, windows and the main window title. Replayed snapshots stay inside the app:
+/// they skip stream/MQTT subscribers, alert emails and webhooks, the anomaly baselines and
+/// the digest statistics.
+pub async fn publish_snapshot(
+    ctx: &CoreContext,
+    state: &SharedState,
+    snapshot: TelemetrySnapshot,
+    source: SnapshotSource,
+) {
+    match source {
+        SnapshotSource::Live => state.record_snapshot(snapshot.clone()).await,
+        SnapshotSource::Replay { .. } => state.record_replayed_snapshot(snapshot.clone()).await,
+    }
+    crate::native_taskbar::refresh(snapshot.clone());
+    crate::tray_icon::refresh(ctx, state, &snapshot).await;
+    crate::alerts::evaluate_snapshot(ctx, state, &snapshot, source).await;
+    if let SnapshotSource::Live = source {
+        crate::anomaly::evaluate_snapshot(ctx, state, &snapshot).await;
+        crate::digest::record_snapshot(ctx, state, &snapshot).await;
+    }
+
+    let visible_labels = ctx.windows.visible_labels();
+    if visible_labels.iter().any(|label| label == "main") {
+        let units = UnitFormatter::lenient(&state.settings.read().await.units);
+        let title = state.window_title.read().await.render(&snapshot, &units);
+        ctx.events.publish(CoreEvent::WindowTitle(title));
+    }
+
+    if !visible_labels.is_empty() {
+        ctx.events.publish(CoreEvent::Snapshot(snapshot));
+    }
+}
+
 pub fn start_memory_trim_loop(state: SharedState) {
     const MIN_INTERVAL_MS: u64 = 60_000;
     const MAX_INTERVAL_MS: u64 = 30 * 60 * 1000;
@@ -112,96 +151,44 @@ pub fn start_task_reminder_loop(app: AppHandle, state: SharedState) {
             state.reminder_last_fired.lock().await.clear();
         }
 
+        let ctx = core_context(&app);
         let mut ticker = tokio::time::interval(Duration::from_millis(15_000));
         ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut shutdown_warned_for = None;
 
         loop {
             ticker.tick().await;
-            if let Err(err) = tick_task_reminders(&app, &state).await {
-                tracing::warn!("task reminder tick failed: {err}");
-            }
+            tick_task_reminders(&ctx, &state).await;
+            crate::webhooks::check_shutdown_warning(&ctx.paths, &mut shutdown_warned_for);
+            crate::digest::check_digest_schedule(&ctx, &state).await;
         }
     });
 }
 
-async fn tick_task_reminders(app: &AppHandle, state: &SharedState) -> Result<(), String> {
-    let now = Local::now();
-    let current_time = format!("{:02}:{:02}", now.hour(), now.minute());
-    let weekday = now.weekday().number_from_monday() as u8;
-    let day_of_month = now.day() as u8;
-    let marker = format!("{:04}-{:02}-{:02}", now.year(), now.month(), now.day());
-
-    {
+async fn tick_task_reminders(ctx: &CoreContext, state: &SharedState) {
+    let reminders = state.task_reminders.read().await.clone();
+    let due = {
         let mut fired = state.reminder_last_fired.lock().await;
-        let suffix = format!("@{marker}");
-        fired.retain(|key, _| key.ends_with(&suffix));
+        reminders::take_due_reminders(&reminders, ctx.clock.local_now(), &mut fired)
+    };
+    if due.is_empty() {
+        return;
     }
 
-    let reminders = state.task_reminders.read().await.clone();
+    // Each delivery, with its webhook retries, runs on its own so a slow endpoint does not
+    // hold up the other reminders or the checks that share this loop.
     let smtp = state.reminder_smtp_config.read().await.clone();
-
-    for reminder in reminders {
-        if !reminder.enabled {
-            continue;
-        }
-        let due_slots = collect_due_slots(&reminder, &current_time, weekday, day_of_month, &marker);
-        if due_slots.is_empty() {
-            continue;
-        }
-
-        for slot in due_slots {
-            let fire_key = format!("{}|{}", reminder.id, slot);
-            let should_fire = {
-                let mut fired = state.reminder_last_fired.lock().await;
-                if fired.contains_key(&fire_key) {
-                    false
-                } else {
-                    fired.insert(fire_key.clone(), now.to_rfc3339());
-                    true
-                }
-            };
-            if !should_fire {
-                continue;
-            }
-
-            if let Err(err) =
-                commands::trigger_task_reminder_backend(app, smtp.clone(), &reminder).await
-            {
+    for DueReminder { reminder, fire_key } in due {
+        let ctx = ctx.clone();
+        let state = state.clone();
+        let smtp = smtp.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(err) = reminders::trigger_reminder(&ctx, smtp, &reminder).await {
                 tracing::warn!("trigger reminder failed ({}): {err}", reminder.id);
                 state.reminder_last_fired.lock().await.remove(&fire_key);
             }
-        }
+        });
     }
-
-    Ok(())
-}
-
-fn collect_due_slots(
-    reminder: &TaskReminder,
-    current_time: &str,
-    weekday: u8,
-    day_of_month: u8,
-    marker: &str,
-) -> Vec<String> {
-    let mut due = Vec::new();
-
-    for time in &reminder.daily_times {
-        if time == current_time {
-            due.push(format!("daily@{time}@{marker}"));
-        }
-    }
-    for slot in &reminder.weekly_slots {
-        if slot.weekday == weekday && slot.time == current_time {
-            due.push(format!("weekly@{}-{}@{marker}", slot.weekday, slot.time));
-        }
-    }
-    for slot in &reminder.monthly_slots {
-        if slot.day == day_of_month && slot.time == current_time {
-            due.push(format!("monthly@{}-{}@{marker}", slot.day, slot.time));
-        }
-    }
-
-    due
 }
 
 #[cfg(windows)]
@@ -335,18 +322,6 @@ fn trim_all_processes_working_set() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn visible_consumer_labels(app: &AppHandle) -> Vec<&'static str> {
-    let mut labels = Vec::with_capacity(3);
-    for label in ["main", "taskbar", "toolkit"] {
-        if let Some(win) = app.get_webview_window(label) {
-            if win.is_visible().unwrap_or(false) {
-                labels.push(label);
-            }
-        }
-    }
-    labels
-}
-
 pub fn register_invoke_handler(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wry> {
     builder.invoke_handler(tauri::generate_handler![
         commands::get_initial_state,
@@ -370,6 +345,13 @@ pub fn register_invoke_handler(builder: tauri::Builder<tauri::Wry>) -> tauri::Bu
         commands::set_memory_trim_enabled,
         commands::set_memory_trim_system_enabled,
         commands::set_memory_trim_interval,
+        commands::set_window_title_template,
+        commands::preview_display_template,
+        commands::get_metric_thresholds,
+        commands::set_metric_thresholds,
+        commands::get_unit_preferences,
+        commands::set_unit_preferences,
+        crate::settings::update_app_settings,
         commands::save_export_config,
         commands::confirm_factory_reset,
         commands::get_shutdown_plan,
@@ -379,8 +361,48 @@ pub fn register_invoke_handler(builder: tauri::Builder<tauri::Wry>) -> tauri::Bu
         commands::start_profile_capture,
         commands::stop_profile_capture,
         commands::get_profile_status,
+        commands::list_profile_captures,
+        commands::analyze_profile,
+        commands::compare_profiles,
+        commands::save_profile_baseline,
+        commands::get_profile_baseline,
+        commands::export_profile_trace,
+        crate::replay::start_profile_replay,
+        crate::replay::stop_profile_replay,
+        crate::replay::pause_profile_replay,
+        crate::replay::resume_profile_replay,
+        crate::replay::seek_profile_replay,
+        crate::replay::set_profile_replay_speed,
+        crate::replay::get_profile_replay_status,
+        crate::flight_recorder::get_flight_recorder_config,
+        crate::flight_recorder::save_flight_recorder_config,
+        crate::flight_recorder::get_flight_recorder_status,
+        crate::flight_recorder::trigger_flight_recorder,
+        crate::tray_icon::get_tray_icon_config,
+        crate::tray_icon::save_tray_icon_config,
         commands::get_profile_output_dir,
         commands::open_profile_output_path,
+        crate::telemetry_stream::get_telemetry_stream_config,
+        crate::telemetry_stream::save_telemetry_stream_config,
+        crate::telemetry_stream::get_telemetry_stream_status,
+        crate::mqtt::get_mqtt_config,
+        crate::mqtt::save_mqtt_config,
+        crate::mqtt::get_mqtt_status,
+        crate::alerts::get_alert_rules,
+        crate::alerts::save_alert_rules,
+        crate::alerts::get_active_alerts,
+        crate::adaptive_sampling::get_sampling_config,
+        crate::adaptive_sampling::save_sampling_config,
+        crate::adaptive_sampling::get_sampling_status,
+        crate::anomaly::get_anomaly_config,
+        crate::anomaly::save_anomaly_config,
+        crate::anomaly::reset_anomaly_baselines,
+        crate::digest::get_health_digest_config,
+        crate::digest::save_health_digest_config,
+        crate::digest::send_health_digest_now,
+        crate::webhooks::get_webhook_store,
+        crate::webhooks::save_webhook_store,
+        crate::webhooks::test_webhook,
         commands::send_reminder_email,
         commands::force_close_reminder_screens,
         commands::debug_log,
@@ -1,2 +0,0 @@
-pub mod collectors;
-pub mod device_info;
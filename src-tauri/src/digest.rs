@@ -0,0 +1,732 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::{
+    core::{
+        ports::{CoreContext, Notification, PathProvider},
+        reminders::{html_escape, reminder_email_shell},
+        store,
+        units::{UnitFormatter, GIB},
+    },
+    ipc::commands,
+    state::SharedState,
+    tauri_adapter::{core_context, TauriAdapter},
+    types::{AlertEvent, DiskMetrics, HealthDigestConfig, TelemetrySnapshot},
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const DIGEST_CONFIG_FILE: &str = "health-digest.json";
+const DIGEST_STATS_FILE: &str = "health-digest-stats.json";
+const DIGEST_SAVE_INTERVAL_SECS: u64 = 10 * 60;
+/// Longer gaps between snapshots (sleep, suspended app) are not counted as traffic.
+const MAX_TRAFFIC_GAP_SECS: f64 = 10.0;
+const NEAR_FULL_DISK_PCT: f64 = 90.0;
+const MAX_LISTED_ALERTS: usize = 50;
+const MAX_LISTED_PROFILE_RUNS: usize = 20;
+/// Wait after the first failed send; doubles with each further failure up to
+/// [`DIGEST_RETRY_MAX`].
+const DIGEST_RETRY_BASE: Duration = Duration::from_secs(60);
+const DIGEST_RETRY_MAX: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetricAccumulator {
+    sum: f64,
+    count: u64,
+    peak: Option<f64>,
+}
+
+impl MetricAccumulator {
+    fn add(&mut self, value: Option<f64>) {
+        let Some(value) = value.filter(|v| v.is_finite()) else {
+            return;
+        };
+        self.sum += value;
+        self.count += 1;
+        self.peak = Some(self.peak.map_or(value, |peak| peak.max(value)));
+    }
+
+    fn average(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DigestAlert {
+    name: String,
+    metric: String,
+    value: f64,
+    timestamp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DigestProfileRun {
+    pub path: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub samples: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DigestStats {
+    period_start: DateTime<Utc>,
+    cpu_usage: MetricAccumulator,
+    gpu_usage: MetricAccumulator,
+    memory_usage: MetricAccumulator,
+    cpu_temp: MetricAccumulator,
+    gpu_temp: MetricAccumulator,
+    download_bytes: f64,
+    upload_bytes: f64,
+    last_sample_at: Option<DateTime<Utc>>,
+    alerts_fired: u32,
+    alerts: Vec<DigestAlert>,
+    profile_runs: Vec<DigestProfileRun>,
+    /// When the last scheduled digest went out; the next one is due at the first slot after it
+    last_sent_at: Option<DateTime<Utc>>,
+}
+
+impl Default for DigestStats {
+    fn default() -> Self {
+        Self {
+            period_start: Utc::now(),
+            cpu_usage: MetricAccumulator::default(),
+            gpu_usage: MetricAccumulator::default(),
+            memory_usage: MetricAccumulator::default(),
+            cpu_temp: MetricAccumulator::default(),
+            gpu_temp: MetricAccumulator::default(),
+            download_bytes: 0.0,
+            upload_bytes: 0.0,
+            last_sample_at: None,
+            alerts_fired: 0,
+            alerts: Vec::new(),
+            profile_runs: Vec::new(),
+            last_sent_at: None,
+        }
+    }
+}
+
+impl DigestStats {
+    fn record(&mut self, snapshot: &TelemetrySnapshot) {
+        self.cpu_usage.add(Some(snapshot.cpu.usage_pct));
+        self.gpu_usage.add(snapshot.gpu.usage_pct);
+        self.memory_usage.add(Some(snapshot.memory.usage_pct));
+        self.cpu_temp.add(snapshot.cpu.temperature_c);
+        self.gpu_temp.add(snapshot.gpu.temperature_c);
+        if let Some(previous) = self.last_sample_at {
+            let elapsed = (snapshot.timestamp - previous).num_milliseconds() as f64 / 1000.0;
+            if elapsed > 0.0 && elapsed <= MAX_TRAFFIC_GAP_SECS {
+                self.download_bytes += snapshot.network.download_bytes_per_sec * elapsed;
+                self.upload_bytes += snapshot.network.upload_bytes_per_sec * elapsed;
+            }
+        }
+        self.last_sample_at = Some(snapshot.timestamp);
+    }
+
+    /// Starts a new period after a digest went out at `sent_at`.
+    fn reset(&mut self, sent_at: DateTime<Utc>) {
+        *self = Self {
+            period_start: sent_at,
+            last_sent_at: Some(sent_at),
+            ..Self::default()
+        };
+    }
+}
+
+#[derive(Default)]
+pub struct HealthDigestRuntime {
+    config: HealthDigestConfig,
+    stats: DigestStats,
+    loaded: bool,
+    last_saved: Option<Instant>,
+    sending: bool,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl HealthDigestRuntime {
+    pub fn is_enabled(&self) -> bool {
+        self.loaded && self.config.enabled
+    }
+}
+
+fn read_digest_config(paths: &dyn PathProvider) -> Option<HealthDigestConfig> {
+    store::read_json(paths, DIGEST_CONFIG_FILE)
+}
+
+fn write_digest_config(paths: &dyn PathProvider, config: &HealthDigestConfig) -> CmdResult<()> {
+    store::write_json(paths, DIGEST_CONFIG_FILE, config)
+}
+
+fn read_digest_stats(paths: &dyn PathProvider) -> Option<DigestStats> {
+    store::read_json(paths, DIGEST_STATS_FILE)
+}
+
+fn write_digest_stats(paths: &dyn PathProvider, stats: &DigestStats) -> CmdResult<()> {
+    store::write_json_compact(paths, DIGEST_STATS_FILE, stats)
+}
+
+fn normalize_digest_config(input: HealthDigestConfig) -> CmdResult<HealthDigestConfig> {
+    let frequency = if input.frequency.eq_ignore_ascii_case("weekly") {
+        "weekly"
+    } else {
+        "daily"
+    };
+    let time = commands::parse_hhmm_generic(&input.time)
+        .ok_or_else(|| "digest time must be in HH:mm format".to_string())?;
+    Ok(HealthDigestConfig {
+        enabled: input.enabled,
+        frequency: frequency.to_string(),
+        time,
+        weekday: input.weekday.clamp(1, 7),
+        email: input.email.trim().to_string(),
+    })
+}
+
+/// The first scheduled slot after `after`, e.g. the next 09:00 (on the configured weekday for
+/// weekly digests).
+fn next_slot(config: &HealthDigestConfig, after: DateTime<Local>) -> Option<DateTime<Local>> {
+    let time = NaiveTime::parse_from_str(&config.time, "%H:%M").ok()?;
+    (0..=8).find_map(|offset| {
+        let date = after.date_naive().checked_add_days(Days::new(offset))?;
+        if config.frequency == "weekly"
+            && date.weekday().number_from_monday() as u8 != config.weekday
+        {
+            return None;
+        }
+        // `earliest` skips slots that fall into a DST gap.
+        Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .filter(|slot| *slot > after)
+    })
+}
+
+/// Whether a slot has passed since the last digest (or the start of the period), so a slot
+/// missed while the app was closed or the mail failed is caught up on the next check.
+fn is_due(config: &HealthDigestConfig, stats: &DigestStats, now: DateTime<Local>) -> bool {
+    let reference = stats.last_sent_at.unwrap_or(stats.period_start);
+    config.enabled
+        && next_slot(config, reference.with_timezone(&Local)).is_some_and(|slot| now >= slot)
+}
+
+/// A metric from the digest's accumulators in the user's units, `N/A` when never seen.
+fn format_metric(units: &UnitFormatter, path: &str, value: Option<f64>) -> String {
+    value.map_or_else(|| "N/A".to_string(), |v| units.metric(path, v))
+}
+
+fn format_disk(units: &UnitFormatter, disk: &DiskMetrics) -> (String, String, String) {
+    (
+        units.percent(disk.usage_pct, 1),
+        units.bytes(disk.used_gb * GIB, 1),
+        units.bytes(disk.total_gb * GIB, 1),
+    )
+}
+
+fn format_local(value: DateTime<Utc>) -> String {
+    value
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// Builds `(subject, plain, html)` for the accumulated period.
+fn render_digest(
+    config: &HealthDigestConfig,
+    stats: &DigestStats,
+    disks: &[DiskMetrics],
+    period_end: DateTime<Utc>,
+    units: &UnitFormatter,
+) -> (String, String, String) {
+    let kind = if config.frequency == "weekly" {
+        "Weekly"
+    } else {
+        "Daily"
+    };
+    let title = format!("{kind} system health digest");
+    let subject = format!("[PulseCore] {title}");
+    let period = format!(
+        "{} – {}",
+        format_local(stats.period_start),
+        format_local(period_end)
+    );
+
+    let usage_rows = [
+        ("CPU", "cpu.usage", &stats.cpu_usage),
+        ("GPU", "gpu.usage", &stats.gpu_usage),
+        ("RAM", "mem.usage", &stats.memory_usage),
+    ];
+    let near_full: Vec<&DiskMetrics> = disks
+        .iter()
+        .filter(|disk| disk.usage_pct >= NEAR_FULL_DISK_PCT)
+        .collect();
+    let disk_name = |disk: &DiskMetrics| {
+        if disk.label.trim().is_empty() {
+            disk.name.clone()
+        } else {
+            format!("{} ({})", disk.name, disk.label)
+        }
+    };
+
+    let mut plain = format!("{title}\nPeriod: {period}\n\nUsage (average / peak)\n");
+    for (label, path, acc) in usage_rows {
+        plain.push_str(&format!(
+            "  {label}: {} / {}\n",
+            format_metric(units, path, acc.average()),
+            format_metric(units, path, acc.peak)
+        ));
+    }
+    plain.push_str(&format!(
+        "\nHottest temperatures\n  CPU: {}\n  GPU: {}\n",
+        format_metric(units, "cpu.temp", stats.cpu_temp.peak),
+        format_metric(units, "gpu.temp", stats.gpu_temp.peak)
+    ));
+    plain.push_str(&format!(
+        "\nNetwork traffic\n  Download: {}\n  Upload: {}\n",
+        units.bytes(stats.download_bytes, 2),
+        units.bytes(stats.upload_bytes, 2)
+    ));
+    plain.push_str(&format!("\nDisks at or above {NEAR_FULL_DISK_PCT:.0}%\n"));
+    if near_full.is_empty() {
+        plain.push_str("  None\n");
+    }
+    for disk in &near_full {
+        let (usage, used, total) = format_disk(units, disk);
+        plain.push_str(&format!(
+            "  {}: {usage} ({used} / {total})\n",
+            disk_name(disk)
+        ));
+    }
+    plain.push_str(&format!("\nAlerts fired: {}\n", stats.alerts_fired));
+    for alert in &stats.alerts {
+        plain.push_str(&format!(
+            "  {} – {} ({} = {})\n",
+            alert.timestamp,
+            alert.name,
+            alert.metric,
+            units.metric(&alert.metric, alert.value)
+        ));
+    }
+    plain.push_str(&format!("\nProfiler runs: {}\n", stats.profile_runs.len()));
+    for run in &stats.profile_runs {
+        plain.push_str(&format!(
+            "  {} – {}: {} samples -> {}\n",
+            format_local(run.started_at),
+            format_local(run.finished_at),
+            run.samples,
+            run.path
+        ));
+    }
+
+    let cell = "padding:6px 10px;border-bottom:1px solid #e6ecf4;text-align:left;";
+    let heading = "margin:20px 0 8px;font-size:15px;color:#101827;";
+    let mut inner = format!(
+        r#"<p style="margin:0 0 14px;color:#5b6b82;">{}</p>"#,
+        html_escape(&period)
+    );
+    inner.push_str(&format!(
+        r#"<h2 style="{heading}">Usage</h2><table style="border-collapse:collapse;width:100%;"><tr><th style="{cell}"></th><th style="{cell}">Average</th><th style="{cell}">Peak</th></tr>"#
+    ));
+    for (label, path, acc) in usage_rows {
+        inner.push_str(&format!(
+            r#"<tr><td style="{cell}">{label}</td><td style="{cell}">{}</td><td style="{cell}">{}</td></tr>"#,
+            format_metric(units, path, acc.average()),
+            format_metric(units, path, acc.peak)
+        ));
+    }
+    inner.push_str("</table>");
+    inner.push_str(&format!(
+        r#"<h2 style="{heading}">Hottest temperatures</h2><p style="margin:0;line-height:1.65;">CPU {} · GPU {}</p>"#,
+        format_metric(units, "cpu.temp", stats.cpu_temp.peak),
+        format_metric(units, "gpu.temp", stats.gpu_temp.peak)
+    ));
+    inner.push_str(&format!(
+        r#"<h2 style="{heading}">Network traffic</h2><p style="margin:0;line-height:1.65;">Download {} · Upload {}</p>"#,
+        units.bytes(stats.download_bytes, 2),
+        units.bytes(stats.upload_bytes, 2)
+    ));
+    inner.push_str(&format!(
+        r#"<h2 style="{heading}">Disks at or above {NEAR_FULL_DISK_PCT:.0}%</h2>"#
+    ));
+    if near_full.is_empty() {
+        inner.push_str(r#"<p style="margin:0;line-height:1.65;">None</p>"#);
+    } else {
+        inner.push_str(r#"<ul style="margin:0;padding-left:20px;line-height:1.65;">"#);
+        for disk in &near_full {
+            let (usage, used, total) = format_disk(units, disk);
+            inner.push_str(&format!(
+                "<li>{}: <strong>{usage}</strong> ({used} / {total})</li>",
+                html_escape(&disk_name(disk))
+            ));
+        }
+        inner.push_str("</ul>");
+    }
+    inner.push_str(&format!(
+        r#"<h2 style="{heading}">Alerts fired ({})</h2>"#,
+        stats.alerts_fired
+    ));
+    if !stats.alerts.is_empty() {
+        inner.push_str(r#"<ul style="margin:0;padding-left:20px;line-height:1.65;">"#);
+        for alert in &stats.alerts {
+            inner.push_str(&format!(
+                "<li>{} – {} (<code>{}</code> = {})</li>",
+                html_escape(&alert.timestamp),
+                html_escape(&alert.name),
+                html_escape(&alert.metric),
+                html_escape(&units.metric(&alert.metric, alert.value))
+            ));
+        }
+        inner.push_str("</ul>");
+    }
+    inner.push_str(&format!(
+        r#"<h2 style="{heading}">Profiler runs ({})</h2>"#,
+        stats.profile_runs.len()
+    ));
+    if !stats.profile_runs.is_empty() {
+        inner.push_str(
+            r#"<ul style="margin:0;padding-left:20px;line-height:1.65;word-break:break-all;">"#,
+        );
+        for run in &stats.profile_runs {
+            inner.push_str(&format!(
+                "<li>{} – {}: {} samples<br />{}</li>",
+                format_local(run.started_at),
+                format_local(run.finished_at),
+                run.samples,
+                html_escape(&run.path)
+            ));
+        }
+        inner.push_str("</ul>");
+    }
+
+    let html = reminder_email_shell(&title, inner);
+    (subject, plain, html)
+}
+
+async fn send_digest(
+    ctx: &CoreContext,
+    state: &SharedState,
+    config: &HealthDigestConfig,
+) -> CmdResult<()> {
+    let stats = state.health_digest.lock().await.stats.clone();
+    let disks = state.latest_snapshot.read().await.disks.clone();
+    let smtp = state
+        .reminder_smtp_config
+        .read()
+        .await
+        .clone()
+        .ok_or_else(|| "SMTP config is required.".to_string())?;
+    let target = if config.email.is_empty() {
+        smtp.from_email.trim().to_string()
+    } else {
+        config.email.clone()
+    };
+    if target.is_empty() {
+        return Err("recipient email is empty; configure SMTP from email first".to_string());
+    }
+    let units = UnitFormatter::lenient(&state.settings.read().await.units);
+    let (subject, plain, html) = render_digest(config, &stats, &disks, Utc::now(), &units);
+    ctx.notifier
+        .notify(Notification::Email {
+            smtp,
+            to: target,
+            subject,
+            text: plain,
+            html: Some(html),
+        })
+        .await
+}
+
+async fn ensure_loaded(paths: &dyn PathProvider, state: &SharedState) {
+    let mut runtime = state.health_digest.lock().await;
+    if runtime.loaded {
+        return;
+    }
+    runtime.loaded = true;
+    if let Some(config) = read_digest_config(paths).and_then(|c| normalize_digest_config(c).ok()) {
+        runtime.config = config;
+    }
+    if let Some(stats) = read_digest_stats(paths) {
+        runtime.stats = stats;
+    }
+}
+
+pub fn start_health_digest(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        ensure_loaded(&TauriAdapter::new(&app), &state).await;
+    });
+}
+
+/// Folds a snapshot into the running period; persisted every few minutes so a restart
+/// does not lose the day.
+pub async fn record_snapshot(ctx: &CoreContext, state: &SharedState, snapshot: &TelemetrySnapshot) {
+    let to_save = {
+        let mut runtime = state.health_digest.lock().await;
+        if !runtime.loaded || !runtime.config.enabled {
+            return;
+        }
+        runtime.stats.record(snapshot);
+        let due = runtime
+            .last_saved
+            .is_none_or(|at| at.elapsed().as_secs() >= DIGEST_SAVE_INTERVAL_SECS);
+        if !due {
+            return;
+        }
+        runtime.last_saved = Some(Instant::now());
+        runtime.stats.clone()
+    };
+    if let Err(err) = write_digest_stats(&*ctx.paths, &to_save) {
+        tracing::debug!("failed to persist digest stats: {err}");
+    }
+}
+
+pub(crate) async fn record_alert(state: &SharedState, event: &AlertEvent) {
+    let mut runtime = state.health_digest.lock().await;
+    if !runtime.config.enabled {
+        return;
+    }
+    let stats = &mut runtime.stats;
+    stats.alerts_fired = stats.alerts_fired.saturating_add(1);
+    if stats.alerts.len() < MAX_LISTED_ALERTS {
+        let timestamp = DateTime::parse_from_rfc3339(&event.timestamp)
+            .map(|at| format_local(at.with_timezone(&Utc)))
+            .unwrap_or_else(|_| event.timestamp.clone());
+        stats.alerts.push(DigestAlert {
+            name: event.name.clone(),
+            metric: event.metric.clone(),
+            value: event.value,
+            timestamp,
+        });
+    }
+}
+
+pub(crate) async fn record_profile_run(state: &SharedState, run: DigestProfileRun) {
+    let mut runtime = state.health_digest.lock().await;
+    if !runtime.config.enabled {
+        return;
+    }
+    let runs = &mut runtime.stats.profile_runs;
+    if runs.len() >= MAX_LISTED_PROFILE_RUNS {
+        runs.remove(0);
+    }
+    runs.push(run);
+}
+
+/// Backoff before retrying after `failures` failed sends in a row.
+fn retry_delay(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    DIGEST_RETRY_BASE
+        .saturating_mul(1 << doublings)
+        .min(DIGEST_RETRY_MAX)
+}
+
+/// Called from the reminder loop; sends once a slot has passed and starts a new period only
+/// after the mail went out. The send runs on its own task, and a failed one is retried with
+/// an exponential backoff.
+pub async fn check_digest_schedule(ctx: &CoreContext, state: &SharedState) {
+    ensure_loaded(&*ctx.paths, state).await;
+    let config = {
+        let mut runtime = state.health_digest.lock().await;
+        if runtime.sending
+            || runtime.retry_at.is_some_and(|at| Instant::now() < at)
+            || !is_due(&runtime.config, &runtime.stats, Local::now())
+        {
+            return;
+        }
+        runtime.sending = true;
+        runtime.config.clone()
+    };
+
+    let ctx = ctx.clone();
+    let state = state.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = send_digest(&ctx, &state, &config).await;
+        let stats = {
+            let mut runtime = state.health_digest.lock().await;
+            runtime.sending = false;
+            if let Err(err) = result {
+                runtime.failures += 1;
+                let delay = retry_delay(runtime.failures);
+                runtime.retry_at = Some(Instant::now() + delay);
+                tracing::warn!(
+                    "health digest email failed ({} in a row), retrying in {} min: {err}",
+                    runtime.failures,
+                    delay.as_secs() / 60
+                );
+                return;
+            }
+            runtime.failures = 0;
+            runtime.retry_at = None;
+            runtime.stats.reset(Utc::now());
+            runtime.last_saved = Some(Instant::now());
+            runtime.stats.clone()
+        };
+        if let Err(err) = write_digest_stats(&*ctx.paths, &stats) {
+            tracing::debug!("failed to persist digest stats: {err}");
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_health_digest_config(app: AppHandle) -> CmdResult<HealthDigestConfig> {
+    Ok(read_digest_config(&TauriAdapter::new(&app))
+        .and_then(|config| normalize_digest_config(config).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn save_health_digest_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: HealthDigestConfig,
+) -> CmdResult<HealthDigestConfig> {
+    let normalized = normalize_digest_config(config)?;
+    let paths = TauriAdapter::new(&app);
+    write_digest_config(&paths, &normalized)?;
+    ensure_loaded(&paths, state.inner()).await;
+    let mut runtime = state.health_digest.lock().await;
+    if normalized.enabled && !runtime.config.enabled {
+        // Nothing was recorded while disabled; start the period now instead of catching up.
+        runtime.stats = DigestStats::default();
+    }
+    // A changed config may fix whatever made the last send fail.
+    runtime.failures = 0;
+    runtime.retry_at = None;
+    runtime.config = normalized.clone();
+    Ok(normalized)
+}
+
+/// Mails the current period without resetting it, e.g. to preview the layout.
+#[tauri::command]
+pub async fn send_health_digest_now(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+) -> CmdResult<()> {
+    let ctx = core_context(&app);
+    ensure_loaded(&*ctx.paths, state.inner()).await;
+    let config = state.health_digest.lock().await.config.clone();
+    send_digest(&ctx, state.inner(), &config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn failed_sends_back_off_exponentially_up_to_an_hour() {
+        assert_eq!(retry_delay(1), DIGEST_RETRY_BASE);
+        assert_eq!(retry_delay(2), DIGEST_RETRY_BASE * 2);
+        assert_eq!(retry_delay(4), DIGEST_RETRY_BASE * 8);
+        assert_eq!(retry_delay(7), DIGEST_RETRY_MAX);
+        assert_eq!(retry_delay(u32::MAX), DIGEST_RETRY_MAX);
+    }
+
+    #[test]
+    fn accumulator_tracks_average_and_peak() {
+        let mut acc = MetricAccumulator::default();
+        acc.add(Some(10.0));
+        acc.add(None);
+        acc.add(Some(30.0));
+        assert_eq!(acc.average(), Some(20.0));
+        assert_eq!(acc.peak, Some(30.0));
+    }
+
+    fn weekly_config() -> HealthDigestConfig {
+        HealthDigestConfig {
+            enabled: true,
+            frequency: "weekly".to_string(),
+            time: "09:00".to_string(),
+            weekday: 1,
+            email: String::new(),
+        }
+    }
+
+    #[test]
+    fn weekly_digest_only_due_on_its_weekday() {
+        // 2026-10-19 is a Monday.
+        let sunday = Local.with_ymd_and_hms(2026, 10, 18, 9, 0, 0).unwrap();
+        let monday = Local.with_ymd_and_hms(2026, 10, 19, 9, 0, 0).unwrap();
+        assert_eq!(next_slot(&weekly_config(), sunday), Some(monday));
+        assert_eq!(
+            next_slot(&weekly_config(), monday),
+            Some(monday + chrono::Duration::days(7))
+        );
+        let daily = HealthDigestConfig {
+            frequency: "daily".to_string(),
+            ..weekly_config()
+        };
+        assert_eq!(
+            next_slot(&daily, sunday),
+            Some(sunday + chrono::Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn missed_slots_are_caught_up_once() {
+        let config = weekly_config();
+        let sent = Local.with_ymd_and_hms(2026, 10, 12, 9, 0, 5).unwrap();
+        let mut stats = DigestStats::default();
+        stats.reset(sent.with_timezone(&Utc));
+
+        // The Monday 09:00 minute was missed; any later check sends it.
+        let tuesday = Local.with_ymd_and_hms(2026, 10, 20, 14, 30, 0).unwrap();
+        assert!(!is_due(
+            &config,
+            &stats,
+            tuesday - chrono::Duration::days(2)
+        ));
+        assert!(is_due(&config, &stats, tuesday));
+        stats.reset(tuesday.with_timezone(&Utc));
+        assert!(!is_due(
+            &config,
+            &stats,
+            tuesday + chrono::Duration::hours(1)
+        ));
+
+        let disabled = HealthDigestConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(!is_due(
+            &disabled,
+            &stats,
+            tuesday + chrono::Duration::days(30)
+        ));
+    }
+
+    #[test]
+    fn digest_lists_near_full_disks_in_both_bodies() {
+        let config = HealthDigestConfig::default();
+        let stats = DigestStats {
+            download_bytes: 3.0 * 1024.0 * 1024.0 * 1024.0,
+            ..DigestStats::default()
+        };
+        let disks = vec![DiskMetrics {
+            name: "D:\\".to_string(),
+            label: "Games".to_string(),
+            used_gb: 950.0,
+            total_gb: 1000.0,
+            usage_pct: 95.0,
+            read_bytes_per_sec: None,
+            write_bytes_per_sec: None,
+        }];
+        let (subject, plain, html) = render_digest(
+            &config,
+            &stats,
+            &disks,
+            Utc::now(),
+            &UnitFormatter::default(),
+        );
+        assert!(subject.contains("Daily"));
+        assert!(plain.contains("D:\\ (Games): 95.0%"));
+        assert!(plain.contains("Download: 3.00 GB"));
+        assert!(html.contains("Games"));
+        assert!(html.starts_with("<!doctype html>"));
+    }
+}
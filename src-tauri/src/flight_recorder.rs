@@ -0,0 +1,413 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, State};
+
+use crate::{
+    core::{
+        flight_recorder::FlightBuffer,
+        ports::{CoreContext, PathProvider},
+        process_scope::ProcessScope,
+        profile::{profile_output_dir, ProfileSample},
+        profile_store::{profile_segments, ProfileWriter},
+        store,
+    },
+    profiler::ProfileSampler,
+    state::SharedState,
+    tauri_adapter::{core_context, TauriAdapter},
+    types::{
+        FlightRecorderConfig, FlightRecorderDump, FlightRecorderStatus, ProfileStorageOptions,
+    },
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const FLIGHT_RECORDER_CONFIG_FILE: &str = "flight-recorder.json";
+/// How often a disabled recorder re-reads its config.
+const IDLE_POLL: Duration = Duration::from_secs(1);
+
+struct ActiveDump {
+    /// `None` while the buffered samples are still being written off the lock.
+    writer: Option<ProfileWriter>,
+    /// Samples recorded while `writer` is `None`, written once it is attached.
+    pending: Vec<ProfileSample>,
+    info: FlightRecorderDump,
+}
+
+/// What [`FlightRecorderRuntime::trigger`] did.
+enum Trigger {
+    /// A dump was already recording; its window now ends later.
+    Extended(FlightRecorderDump),
+    /// A new dump was started; the caller writes `samples` and attaches the writer.
+    Started {
+        samples: Vec<ProfileSample>,
+        info: FlightRecorderDump,
+    },
+}
+
+/// In-memory pre-trigger buffer plus the dump currently recording its post-trigger window.
+#[derive(Default)]
+pub struct FlightRecorderRuntime {
+    config: FlightRecorderConfig,
+    buffer: FlightBuffer,
+    dump: Option<ActiveDump>,
+    last_dump: Option<FlightRecorderDump>,
+}
+
+impl FlightRecorderRuntime {
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn set_config(&mut self, config: FlightRecorderConfig) {
+        self.buffer.set_window(config.buffer_minutes * 60 * 1000);
+        if !config.enabled {
+            self.buffer.clear();
+            self.finish_dump();
+        }
+        self.config = config;
+    }
+
+    fn status(&self) -> FlightRecorderStatus {
+        FlightRecorderStatus {
+            enabled: self.config.enabled,
+            buffered_samples: self.buffer.len(),
+            buffered_ms: self.buffer.span_ms(),
+            dump: self
+                .dump
+                .as_ref()
+                .map(|dump| dump.info.clone())
+                .or_else(|| self.last_dump.clone()),
+        }
+    }
+
+    fn record(&mut self, sample: ProfileSample) {
+        if let Some(dump) = self.dump.as_mut() {
+            match dump.writer.as_mut() {
+                Some(writer) => match writer.write_sample(&sample) {
+                    Ok(()) => dump.info.samples += 1,
+                    Err(err) => tracing::warn!("flight recorder write failed: {err}"),
+                },
+                None => {
+                    dump.pending.push(sample.clone());
+                    dump.info.samples += 1;
+                }
+            }
+            if dump.writer.is_some() && sample.timestamp >= dump.info.ends_at {
+                self.finish_dump();
+            }
+        }
+        self.buffer.push(sample);
+    }
+
+    fn finish_dump(&mut self) {
+        let Some(mut dump) = self.dump.take() else {
+            return;
+        };
+        // Without a writer, `attach_writer` finds the dump gone and closes the file itself.
+        if let Some(writer) = dump.writer.as_mut() {
+            if let Err(err) = writer.close() {
+                tracing::warn!("flight recorder close failed: {err}");
+            }
+        }
+        dump.info.finished = true;
+        tracing::info!(
+            "flight recorder dump finished: {} samples -> {}",
+            dump.info.samples,
+            dump.info.path
+        );
+        self.last_dump = Some(dump.info);
+    }
+
+    /// Starts a dump to `path` and hands back the buffered samples to write; a trigger while a
+    /// dump is still recording extends that dump instead of starting another.
+    fn trigger(&mut self, path: PathBuf, reason: String) -> CmdResult<Trigger> {
+        if !self.config.enabled {
+            return Err("Flight recorder is disabled.".to_string());
+        }
+        let now = Utc::now();
+        let ends_at = now + chrono::Duration::seconds(self.config.post_trigger_secs as i64);
+        if let Some(dump) = self.dump.as_mut() {
+            dump.info.ends_at = dump.info.ends_at.max(ends_at);
+            return Ok(Trigger::Extended(dump.info.clone()));
+        }
+        if self.buffer.is_empty() {
+            return Err("Flight recorder has not buffered any samples yet.".to_string());
+        }
+
+        let info = FlightRecorderDump {
+            path: path.to_string_lossy().to_string(),
+            reason,
+            triggered_at: now,
+            ends_at,
+            samples: self.buffer.len() as u64,
+            finished: false,
+        };
+        tracing::info!("flight recorder triggered ({}): {}", info.reason, info.path);
+        self.dump = Some(ActiveDump {
+            writer: None,
+            pending: Vec::new(),
+            info: info.clone(),
+        });
+        Ok(Trigger::Started {
+            samples: self.buffer.iter().cloned().collect(),
+            info,
+        })
+    }
+
+    /// Hands the dump started for `path` its writer once the buffered samples are on disk.
+    fn attach_writer(&mut self, path: &str, mut writer: ProfileWriter) {
+        let Some(dump) = self.dump.as_mut().filter(|dump| dump.info.path == path) else {
+            // Finished (e.g. the recorder was disabled) while the buffer was being written.
+            if let Err(err) = writer.close() {
+                tracing::warn!("flight recorder close failed: {err}");
+            }
+            return;
+        };
+        for sample in dump.pending.drain(..) {
+            if let Err(err) = writer.write_sample(&sample) {
+                tracing::warn!("flight recorder write failed: {err}");
+            }
+        }
+        dump.writer = Some(writer);
+        if self.config.post_trigger_secs == 0 || Utc::now() >= dump.info.ends_at {
+            self.finish_dump();
+        }
+    }
+
+    /// Drops the dump started for `path` after its buffer could not be written.
+    fn abandon_dump(&mut self, path: &str) {
+        if self
+            .dump
+            .as_ref()
+            .is_some_and(|dump| dump.info.path == path)
+        {
+            self.dump = None;
+        }
+    }
+}
+
+/// Creates the dump file and writes the pre-trigger samples; blocking file I/O.
+fn write_buffered(path: &Path, samples: &[ProfileSample]) -> CmdResult<ProfileWriter> {
+    let mut writer = ProfileWriter::create(
+        path,
+        ProfileStorageOptions {
+            segment_max_bytes: 0,
+            max_total_bytes: 0,
+            ..ProfileStorageOptions::default()
+        },
+    )?;
+    for sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.flush()?;
+    Ok(writer)
+}
+
+fn read_flight_recorder_config(paths: &dyn PathProvider) -> Option<FlightRecorderConfig> {
+    store::read_json(paths, FLIGHT_RECORDER_CONFIG_FILE)
+}
+
+fn write_flight_recorder_config(
+    paths: &dyn PathProvider,
+    config: &FlightRecorderConfig,
+) -> CmdResult<()> {
+    store::write_json(paths, FLIGHT_RECORDER_CONFIG_FILE, config)
+}
+
+fn normalize_flight_recorder_config(input: FlightRecorderConfig) -> FlightRecorderConfig {
+    FlightRecorderConfig {
+        enabled: input.enabled,
+        buffer_minutes: input.buffer_minutes.clamp(1, 30),
+        interval_ms: input.interval_ms.clamp(200, 10_000),
+        post_trigger_secs: input.post_trigger_secs.min(600),
+        trigger_on_alerts: input.trigger_on_alerts,
+        alert_rule_ids: input
+            .alert_rule_ids
+            .into_iter()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect(),
+    }
+}
+
+fn dump_path(paths: &dyn PathProvider) -> PathBuf {
+    dump_path_at(&profile_output_dir(paths), Utc::now())
+}
+
+/// Creating a writer deletes an existing capture of the same name, so a dump that would
+/// share one gets a numeric suffix instead.
+fn dump_path_at(dir: &Path, at: DateTime<Utc>) -> PathBuf {
+    let stem = format!("flight-{}", at.format("%Y%m%d-%H%M%S-%3f"));
+    let mut path = dir.join(format!("{stem}.jsonl"));
+    let mut copy = 1;
+    while !profile_segments(&path).is_empty() {
+        copy += 1;
+        path = dir.join(format!("{stem}-{copy}.jsonl"));
+    }
+    path
+}
+
+async fn trigger(
+    paths: &dyn PathProvider,
+    state: &SharedState,
+    reason: String,
+) -> CmdResult<FlightRecorderDump> {
+    let path = dump_path(paths);
+    let started = state
+        .flight_recorder
+        .lock()
+        .await
+        .trigger(path.clone(), reason)?;
+    let (samples, info) = match started {
+        Trigger::Extended(info) => return Ok(info),
+        Trigger::Started { samples, info } => (samples, info),
+    };
+    // Gzip-writing minutes of samples must not hold up sampling or alert dispatch.
+    let written = tokio::task::spawn_blocking(move || write_buffered(&path, &samples))
+        .await
+        .unwrap_or_else(|_| Err("flight recorder writer panicked".to_string()));
+    let mut runtime = state.flight_recorder.lock().await;
+    match written {
+        Ok(writer) => {
+            runtime.attach_writer(&info.path, writer);
+            Ok(info)
+        }
+        Err(err) => {
+            runtime.abandon_dump(&info.path);
+            Err(err)
+        }
+    }
+}
+
+/// Dumps the buffer when a matching alert fires; called from alert dispatch.
+pub async fn on_alert_fired(ctx: &CoreContext, state: &SharedState, rule_id: &str) {
+    {
+        let runtime = state.flight_recorder.lock().await;
+        let config = &runtime.config;
+        if !config.enabled
+            || !config.trigger_on_alerts
+            || !(config.alert_rule_ids.is_empty()
+                || config.alert_rule_ids.iter().any(|id| id == rule_id))
+        {
+            return;
+        }
+    }
+    // Replayed alerts would dump the pre-replay buffer, which has nothing to do with them.
+    if state.replay.lock().await.is_active() {
+        return;
+    }
+    if let Err(err) = trigger(&*ctx.paths, state, format!("alert:{rule_id}")).await {
+        tracing::warn!("flight recorder trigger for {rule_id} failed: {err}");
+    }
+}
+
+pub fn start_flight_recorder(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        let ctx = core_context(&app);
+        if let Some(config) = read_flight_recorder_config(&*ctx.paths) {
+            state
+                .flight_recorder
+                .lock()
+                .await
+                .set_config(normalize_flight_recorder_config(config));
+        }
+
+        let mut sampler: Option<ProfileSampler> = None;
+        loop {
+            let (enabled, interval_ms) = {
+                let runtime = state.flight_recorder.lock().await;
+                (runtime.config.enabled, runtime.config.interval_ms)
+            };
+            if !enabled {
+                // Release the process table while nothing is buffered.
+                sampler = None;
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            }
+            // Replayed snapshots are not what happened on this machine; keep them out.
+            if !state.replay.lock().await.is_active() {
+                let sample = sampler
+                    .get_or_insert_with(|| ProfileSampler::new(ProcessScope::default()))
+                    .sample(&ctx, &state)
+                    .await;
+                state.flight_recorder.lock().await.record(sample);
+            }
+            tokio::time::sleep(Duration::from_millis(interval_ms)).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_flight_recorder_config(
+    state: State<'_, SharedState>,
+) -> CmdResult<FlightRecorderConfig> {
+    Ok(state.flight_recorder.lock().await.config.clone())
+}
+
+#[tauri::command]
+pub async fn save_flight_recorder_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: FlightRecorderConfig,
+) -> CmdResult<FlightRecorderConfig> {
+    let config = normalize_flight_recorder_config(config);
+    write_flight_recorder_config(&TauriAdapter::new(&app), &config)?;
+    state
+        .flight_recorder
+        .lock()
+        .await
+        .set_config(config.clone());
+    Ok(config)
+}
+
+#[tauri::command]
+pub async fn get_flight_recorder_status(
+    state: State<'_, SharedState>,
+) -> CmdResult<FlightRecorderStatus> {
+    Ok(state.flight_recorder.lock().await.status())
+}
+
+/// Dumps the buffer now; `reason` defaults to `command` (the front end passes `hotkey`).
+#[tauri::command]
+pub async fn trigger_flight_recorder(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    reason: Option<String>,
+) -> CmdResult<FlightRecorderDump> {
+    let reason = reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty())
+        .unwrap_or_else(|| "command".to_string());
+    trigger(&TauriAdapter::new(&app), state.inner(), reason).await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::core::test_support;
+
+    #[test]
+    fn dumps_in_the_same_millisecond_keep_their_own_files() {
+        let dir = std::env::temp_dir().join(format!("flight-dumps-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let at = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let samples = [test_support::profile_sample(test_support::snapshot())];
+
+        let first = dump_path_at(&dir, at);
+        write_buffered(&first, &samples).unwrap().close().unwrap();
+        let second = dump_path_at(&dir, at);
+        assert_ne!(first, second);
+        write_buffered(&second, &samples).unwrap().close().unwrap();
+
+        assert_eq!(profile_segments(&first).len(), 1);
+        assert_eq!(profile_segments(&second).len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
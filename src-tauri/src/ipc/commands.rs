@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Datelike, Days, Local, NaiveTime, Utc};
 use lettre::{
     message::{header::ContentType, Mailbox, MultiPart, SinglePart},
     transport::smtp::authentication::Credentials,
@@ -7,18 +7,33 @@ use lettre::{
 #[cfg(windows)]
 use std::process::Command;
 use std::{collections::HashSet, fs, path::PathBuf};
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Manager, State};
 
 use crate::{
-    core::device_info,
+    core::{
+        device_info,
+        ports::PathProvider,
+        process_scope::ProcessScope,
+        profile::{ensure_profile_path, profile_output_dir, resolve_existing_profile},
+        profile_analysis::{self, AnalysisOptions, ProfileReportFile, ReportFormat},
+        profile_diff::{self, ProfileBaseline, ProfileComparison, RegressionTolerances},
+        profile_store::capture_name,
+        profile_trace::{self, TraceExport},
+        reminders, store,
+        taskbar::{apply_legacy_toggles, migrate_segments, validate_segments},
+        template::Template,
+        thresholds::ThresholdBands,
+        units::UnitFormatter,
+    },
     local_ai,
-    profiler::{ensure_profile_path, profile_output_dir, ProfileStatus},
+    profiler::CaptureOptions,
     state::SharedState,
+    tauri_adapter::{core_context, TauriAdapter},
     types::{
-        AppBootstrap, AppRuntimeInfo, MonthlyReminderSlot, NativeTaskbarConfig,
-        ReminderAdvancedSettings, ReminderScreenEventPayload, ScheduleShutdownRequest,
-        SendReminderEmailRequest, ShutdownPlan, SmtpEmailConfig, TaskReminder, TaskReminderStore,
-        WeeklyReminderSlot,
+        AppBootstrap, AppRuntimeInfo, MetricThreshold, MonthlyReminderSlot, NativeTaskbarConfig,
+        ProfileScope, ProfileStatus, ProfileStorageOptions, ReminderAdvancedSettings,
+        ScheduleShutdownRequest, SendReminderEmailRequest, ShutdownPlan, SmtpEmailConfig,
+        TaskReminder, TaskReminderStore, UnitPreferences, WeeklyReminderSlot,
     },
 };
 
@@ -49,10 +64,35 @@ fn clear_shutdown_plan_file(app: &AppHandle) {
     }
 }
 
-fn read_shutdown_plan(app: &AppHandle) -> Option<ShutdownPlan> {
-    let path = shutdown_plan_path(app).ok()?;
-    let text = fs::read_to_string(path).ok()?;
-    serde_json::from_str::<ShutdownPlan>(&text).ok()
+fn read_shutdown_plan(paths: &dyn PathProvider) -> Option<ShutdownPlan> {
+    store::read_json(paths, SHUTDOWN_PLAN_FILE)
+}
+
+/// Next moment the persisted shutdown plan will power the machine off, if any.
+pub(crate) fn next_scheduled_shutdown(paths: &dyn PathProvider) -> Option<DateTime<Utc>> {
+    let plan = read_shutdown_plan(paths).and_then(normalize_shutdown_plan)?;
+    match plan.mode.as_str() {
+        "countdown" | "once" => plan.execute_at,
+        "daily" | "weekly" | "monthly" => {
+            let time = NaiveTime::parse_from_str(plan.time.as_deref()?, "%H:%M").ok()?;
+            let now = Local::now();
+            // Monthly plans on the 31st can skip a month, so look a little past two months ahead.
+            (0..=62u64).find_map(|offset| {
+                let date = now.date_naive().checked_add_days(Days::new(offset))?;
+                let matches = match plan.mode.as_str() {
+                    "weekly" => Some(date.weekday().number_from_monday() as u8) == plan.weekday,
+                    "monthly" => Some(date.day() as u8) == plan.day_of_month,
+                    _ => true,
+                };
+                if !matches {
+                    return None;
+                }
+                let at = date.and_time(time).and_local_timezone(Local).earliest()?;
+                (at > now).then(|| at.with_timezone(&Utc))
+            })
+        }
+        _ => None,
+    }
 }
 
 #[cfg(windows)]
@@ -173,8 +213,29 @@ pub async fn configure_native_taskbar_monitor(
     app: AppHandle,
     state: State<'_, SharedState>,
     enabled: bool,
-    config: NativeTaskbarConfig,
+    mut config: NativeTaskbarConfig,
 ) -> CmdResult<()> {
+    if config.segments.is_empty() {
+        if let Some(segments) = crate::native_taskbar::read_segments(&app) {
+            config.segments = segments;
+            apply_legacy_toggles(&mut config);
+        }
+    }
+    migrate_segments(&mut config);
+    validate_segments(&config.segments)?;
+    crate::native_taskbar::write_segments(&app, &config.segments)?;
+    // The taskbar and the overlay share one set of bands: an empty list takes the stored one,
+    // a non-empty list replaces it.
+    if config.thresholds.is_empty() {
+        config.thresholds = state.settings.read().await.metric_thresholds.clone();
+    } else {
+        ThresholdBands::parse(&config.thresholds)?;
+        let thresholds = config.thresholds.clone();
+        state
+            .update_settings(|settings| settings.metric_thresholds = thresholds)
+            .await;
+    }
+    config.units = state.settings.read().await.units.clone();
     crate::native_taskbar::configure(app, state.inner().clone(), enabled, config).await
 }
 
@@ -230,6 +291,9 @@ pub fn get_auto_start_enabled() -> CmdResult<bool> {
     }
 }
 
+/// Starts a named capture; several can run at once. `name` defaults to the file name.
+// Flat arguments keep the existing `{ path, intervalMs, durationMs }` invoke shape working.
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn start_profile_capture(
     app: AppHandle,
@@ -237,89 +301,207 @@ pub async fn start_profile_capture(
     path: String,
     interval_ms: u64,
     duration_ms: Option<u64>,
+    storage: Option<ProfileStorageOptions>,
+    scope: Option<ProfileScope>,
+    name: Option<String>,
 ) -> CmdResult<ProfileStatus> {
-    let mut profiler_lock = state.profiler.lock().await;
-    if profiler_lock.is_some() {
-        return Err("Profile capture already running.".to_string());
-    }
-
-    let resolved = ensure_profile_path(&app, &path);
-    let handle = crate::profiler::start_profile_capture(
-        app,
+    let scope = ProcessScope::parse(&scope.unwrap_or_default())?;
+    if state.replay.lock().await.is_active() {
+        return Err("Stop the profile replay before starting a capture.".to_string());
+    }
+
+    let ctx = core_context(&app);
+    let resolved = ensure_profile_path(&*ctx.paths, &path);
+    let name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| capture_name(&resolved));
+    state.profiler.lock().await.start(
+        ctx,
         state.inner().clone(),
-        resolved,
-        interval_ms,
-        duration_ms,
+        CaptureOptions {
+            name,
+            path: resolved,
+            interval_ms,
+            duration_ms,
+            storage: storage.unwrap_or_default(),
+            scope,
+        },
     )
-    .await?;
-
-    let status = handle.status();
-    *profiler_lock = Some(handle);
-    Ok(status)
 }
 
+/// Stops the named capture, or every running capture without a name (returning the last).
 #[tauri::command]
-pub async fn stop_profile_capture(state: State<'_, SharedState>) -> CmdResult<ProfileStatus> {
-    let handle = {
-        let mut profiler_lock = state.profiler.lock().await;
-        profiler_lock.take()
+pub async fn stop_profile_capture(
+    state: State<'_, SharedState>,
+    name: Option<String>,
+) -> CmdResult<ProfileStatus> {
+    let handles = {
+        let mut runtime = state.profiler.lock().await;
+        match name.as_deref() {
+            Some(name) => runtime.take(name).into_iter().collect(),
+            None => runtime.take_all(),
+        }
     };
 
-    if let Some(handle) = handle {
-        let status = handle.status();
-        handle.stop().await;
-        return Ok(ProfileStatus {
-            active: false,
-            path: status.path,
-            started_at: status.started_at,
-            samples: status.samples,
-        });
+    let mut status = None;
+    for handle in handles {
+        status = Some(handle.stop().await);
     }
+    Ok(status.unwrap_or_default())
+}
 
-    Ok(ProfileStatus {
-        active: false,
-        path: None,
-        started_at: None,
-        samples: 0,
+/// The named capture, or the first running one without a name.
+#[tauri::command]
+pub async fn get_profile_status(
+    state: State<'_, SharedState>,
+    name: Option<String>,
+) -> CmdResult<ProfileStatus> {
+    Ok(state.profiler.lock().await.status(name.as_deref()))
+}
+
+/// Running captures and the last result under each finished name.
+#[tauri::command]
+pub async fn list_profile_captures(state: State<'_, SharedState>) -> CmdResult<Vec<ProfileStatus>> {
+    Ok(state.profiler.lock().await.statuses())
+}
+
+fn write_task_reminder_store_file(app: &AppHandle, reminders: &TaskReminderStore) -> CmdResult<()> {
+    store::write_json(&TauriAdapter::new(app), TASK_REMINDER_FILE, reminders)
+}
+
+pub(crate) fn read_task_reminder_store_file(app: &AppHandle) -> Option<TaskReminderStore> {
+    store::read_json(&TauriAdapter::new(app), TASK_REMINDER_FILE)
+}
+
+/// Analyzes a capture and writes `<name>.report.md` (or `.json`) next to it.
+#[tauri::command]
+pub async fn analyze_profile(
+    app: AppHandle,
+    path: String,
+    format: Option<String>,
+    options: Option<AnalysisOptions>,
+) -> CmdResult<ProfileReportFile> {
+    if path.trim().is_empty() {
+        return Err("profile path is required".to_string());
+    }
+    let format = ReportFormat::parse(format.as_deref())?;
+    let options = options.unwrap_or_default();
+    let profile = resolve_existing_profile(&TauriAdapter::new(&app), &path);
+    tauri::async_runtime::spawn_blocking(move || {
+        let report = profile_analysis::analyze_profile(&profile, &options)?;
+        let report_path = profile_analysis::write_report(&profile, &report, format)?;
+        Ok(ProfileReportFile {
+            report_path: report_path.to_string_lossy().to_string(),
+            report,
+        })
     })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
+/// Compares a capture against another one or, without `baseline_path`, the stored baseline.
+/// Tolerances default to the ones saved with the baseline.
 #[tauri::command]
-pub async fn get_profile_status(state: State<'_, SharedState>) -> CmdResult<ProfileStatus> {
-    let profiler_lock = state.profiler.lock().await;
-    if let Some(handle) = profiler_lock.as_ref() {
-        return Ok(handle.status());
-    }
-    Ok(ProfileStatus {
-        active: false,
-        path: None,
-        started_at: None,
-        samples: 0,
+pub async fn compare_profiles(
+    app: AppHandle,
+    path: String,
+    baseline_path: Option<String>,
+    tolerances: Option<RegressionTolerances>,
+) -> CmdResult<ProfileComparison> {
+    if path.trim().is_empty() {
+        return Err("profile path is required".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let paths = TauriAdapter::new(&app);
+        let (baseline, saved_tolerances) =
+            match baseline_path.filter(|value| !value.trim().is_empty()) {
+                Some(baseline_path) => {
+                    let baseline_path = resolve_existing_profile(&paths, &baseline_path);
+                    (
+                        profile_diff::load_report(&baseline_path)?,
+                        RegressionTolerances::default(),
+                    )
+                }
+                None => {
+                    let stored = profile_diff::read_baseline(&paths).ok_or_else(|| {
+                        "No profile baseline saved; pass a baseline profile or save one first."
+                            .to_string()
+                    })?;
+                    (stored.report, stored.tolerances)
+                }
+            };
+        let current = profile_diff::load_report(&resolve_existing_profile(&paths, &path))?;
+        Ok(profile_diff::compare_reports(
+            &baseline,
+            &current,
+            &tolerances.unwrap_or(saved_tolerances),
+        ))
     })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
-fn task_reminder_store_path(app: &AppHandle) -> CmdResult<PathBuf> {
-    let mut dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
-    dir.push(TASK_REMINDER_FILE);
-    Ok(dir)
+/// Analyzes a capture and stores it as the baseline for `compare_profiles`.
+#[tauri::command]
+pub async fn save_profile_baseline(
+    app: AppHandle,
+    path: String,
+    tolerances: Option<RegressionTolerances>,
+) -> CmdResult<ProfileBaseline> {
+    if path.trim().is_empty() {
+        return Err("profile path is required".to_string());
+    }
+    let paths = TauriAdapter::new(&app);
+    let profile = resolve_existing_profile(&paths, &path);
+    let report = tauri::async_runtime::spawn_blocking(move || profile_diff::load_report(&profile))
+        .await
+        .map_err(|e| e.to_string())??;
+    let baseline = ProfileBaseline {
+        saved_at: Utc::now(),
+        tolerances: tolerances.unwrap_or_default(),
+        report,
+    };
+    profile_diff::write_baseline(&paths, &baseline)?;
+    Ok(baseline)
 }
 
-fn write_task_reminder_store_file(app: &AppHandle, store: &TaskReminderStore) -> CmdResult<()> {
-    let path = task_reminder_store_path(app)?;
-    let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
-    fs::write(path, json).map_err(|e| e.to_string())
+#[tauri::command]
+pub fn get_profile_baseline(app: AppHandle) -> CmdResult<Option<ProfileBaseline>> {
+    Ok(profile_diff::read_baseline(&TauriAdapter::new(&app)))
 }
 
-pub(crate) fn read_task_reminder_store_file(app: &AppHandle) -> Option<TaskReminderStore> {
-    let path = task_reminder_store_path(app).ok()?;
-    let text = fs::read_to_string(path).ok()?;
-    serde_json::from_str::<TaskReminderStore>(&text).ok()
+/// Converts a capture to `<name>.trace.json` (or `output_path`) for `chrome://tracing` or ui.perfetto.dev.
+#[tauri::command]
+pub async fn export_profile_trace(
+    app: AppHandle,
+    path: String,
+    output_path: Option<String>,
+) -> CmdResult<TraceExport> {
+    if path.trim().is_empty() {
+        return Err("profile path is required".to_string());
+    }
+    let paths = TauriAdapter::new(&app);
+    let profile = resolve_existing_profile(&paths, &path);
+    // A relative output path lands next to the profile.
+    let output = output_path
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            profile
+                .parent()
+                .map(|dir| dir.join(value.trim()))
+                .unwrap_or_else(|| PathBuf::from(value.trim()))
+        });
+    tauri::async_runtime::spawn_blocking(move || {
+        profile_trace::export_trace(&profile, output.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
 pub fn get_profile_output_dir(app: AppHandle) -> CmdResult<String> {
-    let dir = profile_output_dir(&app);
+    let dir = profile_output_dir(&TauriAdapter::new(&app));
     fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
     Ok(dir.to_string_lossy().to_string())
 }
@@ -356,7 +538,7 @@ pub fn open_profile_output_path(path: String) -> CmdResult<()> {
     }
 }
 
-fn parse_hhmm_generic(value: &str) -> Option<String> {
+pub(crate) fn parse_hhmm_generic(value: &str) -> Option<String> {
     let parts: Vec<&str> = value.trim().split(':').collect();
     if parts.len() != 2 {
         return None;
@@ -392,6 +574,8 @@ fn normalize_smtp_config(input: Option<SmtpEmailConfig>) -> Option<SmtpEmailConf
 fn normalize_task_reminder(input: TaskReminder) -> TaskReminder {
     let channel = if input.channel.eq_ignore_ascii_case("fullscreen") {
         "fullscreen".to_string()
+    } else if input.channel.eq_ignore_ascii_case("webhook") {
+        "webhook".to_string()
     } else {
         "email".to_string()
     };
@@ -461,6 +645,7 @@ fn normalize_task_reminder(input: TaskReminder) -> TaskReminder {
         title,
         channel,
         email: input.email.trim().to_string(),
+        webhook_id: input.webhook_id.trim().to_string(),
         daily_times,
         weekly_slots,
         monthly_slots,
@@ -488,6 +673,17 @@ fn normalize_advanced_settings(
     Some(settings)
 }
 
+/// Checks what normalizing cannot fix; run on saves so stored reminders keep loading.
+fn validate_task_reminder(reminder: &TaskReminder) -> CmdResult<()> {
+    if reminder.channel == "webhook" && reminder.webhook_id.is_empty() {
+        return Err(format!(
+            "reminder \"{}\": choose a webhook for the webhook channel",
+            reminder.title
+        ));
+    }
+    Ok(())
+}
+
 pub(crate) fn normalize_task_reminder_store(input: TaskReminderStore) -> TaskReminderStore {
     let mut normalized = Vec::with_capacity(input.reminders.len());
     for reminder in input.reminders {
@@ -503,7 +699,7 @@ pub(crate) fn normalize_task_reminder_store(input: TaskReminderStore) -> TaskRem
     }
 }
 
-async fn send_email_internal(
+pub(crate) async fn send_email_internal(
     smtp: SmtpEmailConfig,
     to_address: String,
     subject: String,
@@ -641,6 +837,9 @@ pub async fn save_task_reminder_store(
     store: TaskReminderStore,
 ) -> CmdResult<TaskReminderStore> {
     let mut normalized = normalize_task_reminder_store(store);
+    for reminder in &normalized.reminders {
+        validate_task_reminder(reminder)?;
+    }
     if let Some(disk) = read_task_reminder_store_file(&app) {
         let disk = normalize_task_reminder_store(disk);
         for incoming in &mut normalized.reminders {
@@ -666,258 +865,6 @@ pub async fn save_task_reminder_store(
     Ok(normalized)
 }
 
-fn html_escape(value: &str) -> String {
-    value
-        .replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&#39;")
-}
-
-fn html_with_line_breaks(value: &str) -> String {
-    html_escape(value).replace('\n', "<br />")
-}
-
-fn is_http_url(value: &str) -> bool {
-    let normalized = value.trim().to_ascii_lowercase();
-    normalized.starts_with("https://") || normalized.starts_with("http://")
-}
-
-fn is_supported_image_src(value: &str) -> bool {
-    let normalized = value.trim().to_ascii_lowercase();
-    is_http_url(&normalized) || normalized.starts_with("data:image/")
-}
-
-fn looks_like_html_content(value: &str) -> bool {
-    let normalized = value.trim_start().to_ascii_lowercase();
-    normalized.starts_with("<!doctype")
-        || normalized.starts_with("<html")
-        || (normalized.contains('<') && normalized.contains('>'))
-}
-
-fn reminder_plain_body(reminder: &TaskReminder) -> String {
-    let content = reminder.content.trim();
-    if content.is_empty() {
-        return reminder.title.clone();
-    }
-
-    match reminder.content_type.as_str() {
-        "web" => format!("{}\n\nOpen web page:\n{content}", reminder.title),
-        "image" => format!("{}\n\nImage:\n{content}", reminder.title),
-        _ => reminder.content.clone(),
-    }
-}
-
-fn reminder_email_shell(title: &str, inner_html: String) -> String {
-    format!(
-        r#"<!doctype html>
-<html>
-  <body style="margin:0;padding:24px;background:#f6f8fb;color:#172033;font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Arial,sans-serif;">
-    <main style="max-width:720px;margin:0 auto;background:#ffffff;border:1px solid #dde5f0;border-radius:16px;padding:24px;">
-      <h1 style="margin:0 0 18px;font-size:22px;line-height:1.35;color:#101827;">{}</h1>
-      {}
-    </main>
-  </body>
-</html>"#,
-        html_escape(title),
-        inner_html
-    )
-}
-
-fn simple_markdown_to_email_html(value: &str) -> String {
-    let normalized = value.replace("\r\n", "\n").replace('\r', "\n");
-    let mut html = String::new();
-    let mut paragraph: Vec<String> = Vec::new();
-    let mut list_open = false;
-    let mut code_open = false;
-    let mut code_lines: Vec<String> = Vec::new();
-
-    let flush_paragraph = |html: &mut String, paragraph: &mut Vec<String>| {
-        if paragraph.is_empty() {
-            return;
-        }
-        html.push_str("<p style=\"margin:0 0 14px;line-height:1.65;\">");
-        html.push_str(&html_with_line_breaks(&paragraph.join("\n")));
-        html.push_str("</p>");
-        paragraph.clear();
-    };
-    let close_list = |html: &mut String, list_open: &mut bool| {
-        if *list_open {
-            html.push_str("</ul>");
-            *list_open = false;
-        }
-    };
-
-    for line in normalized.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("```") {
-            if code_open {
-                html.push_str("<pre style=\"margin:0 0 14px;padding:12px;border-radius:10px;background:#101827;color:#e8eef7;overflow:auto;\"><code>");
-                html.push_str(&html_escape(&code_lines.join("\n")));
-                html.push_str("</code></pre>");
-                code_lines.clear();
-                code_open = false;
-            } else {
-                flush_paragraph(&mut html, &mut paragraph);
-                close_list(&mut html, &mut list_open);
-                code_open = true;
-            }
-            continue;
-        }
-        if code_open {
-            code_lines.push(line.to_string());
-            continue;
-        }
-        if trimmed.is_empty() {
-            flush_paragraph(&mut html, &mut paragraph);
-            close_list(&mut html, &mut list_open);
-            continue;
-        }
-        if let Some(text) = trimmed.strip_prefix("# ") {
-            flush_paragraph(&mut html, &mut paragraph);
-            close_list(&mut html, &mut list_open);
-            html.push_str("<h2 style=\"margin:0 0 12px;font-size:20px;line-height:1.35;\">");
-            html.push_str(&html_escape(text));
-            html.push_str("</h2>");
-            continue;
-        }
-        if let Some(text) = trimmed
-            .strip_prefix("- ")
-            .or_else(|| trimmed.strip_prefix("* "))
-        {
-            flush_paragraph(&mut html, &mut paragraph);
-            if !list_open {
-                html.push_str("<ul style=\"margin:0 0 14px;padding-left:22px;line-height:1.65;\">");
-                list_open = true;
-            }
-            html.push_str("<li>");
-            html.push_str(&html_with_line_breaks(text));
-            html.push_str("</li>");
-            continue;
-        }
-        paragraph.push(line.to_string());
-    }
-
-    if code_open {
-        html.push_str("<pre style=\"margin:0 0 14px;padding:12px;border-radius:10px;background:#101827;color:#e8eef7;overflow:auto;\"><code>");
-        html.push_str(&html_escape(&code_lines.join("\n")));
-        html.push_str("</code></pre>");
-    }
-    flush_paragraph(&mut html, &mut paragraph);
-    close_list(&mut html, &mut list_open);
-
-    if html.is_empty() {
-        "<p style=\"margin:0;line-height:1.65;\">No content.</p>".to_string()
-    } else {
-        html
-    }
-}
-
-fn reminder_html_body(reminder: &TaskReminder) -> Option<String> {
-    let content = reminder.content.trim();
-    match reminder.content_type.as_str() {
-        "markdown" => Some(reminder_email_shell(
-            &reminder.title,
-            simple_markdown_to_email_html(content),
-        )),
-        "web" => {
-            if looks_like_html_content(content) {
-                return Some(if content.to_ascii_lowercase().contains("<html") {
-                    content.to_string()
-                } else {
-                    reminder_email_shell(&reminder.title, content.to_string())
-                });
-            }
-
-            let inner = if is_http_url(content) {
-                let safe_url = html_escape(content);
-                format!(
-                    r#"<p style="margin:0 0 14px;line-height:1.65;">Most email clients block embedded web pages, so this reminder is sent as a safe link card.</p>
-<p style="margin:0 0 18px;"><a href="{0}" style="display:inline-block;padding:11px 16px;border-radius:10px;background:#2563eb;color:#ffffff;text-decoration:none;font-weight:600;">Open web page</a></p>
-<p style="margin:0;color:#526070;line-height:1.6;word-break:break-all;">{0}</p>"#,
-                    safe_url
-                )
-            } else if content.is_empty() {
-                "<p style=\"margin:0;line-height:1.65;\">No web URL configured.</p>".to_string()
-            } else {
-                format!(
-                    "<p style=\"margin:0;line-height:1.65;word-break:break-all;\">{}</p>",
-                    html_escape(content)
-                )
-            };
-            Some(reminder_email_shell(&reminder.title, inner))
-        }
-        "image" => {
-            let inner = if is_supported_image_src(content) {
-                let safe_src = html_escape(content);
-                format!(
-                    r#"<p style="margin:0 0 14px;line-height:1.65;">Image reminder:</p>
-<img src="{0}" alt="{1}" style="display:block;max-width:100%;height:auto;border-radius:12px;border:1px solid #dde5f0;" />"#,
-                    safe_src,
-                    html_escape(&reminder.title)
-                )
-            } else if content.is_empty() {
-                "<p style=\"margin:0;line-height:1.65;\">No image configured.</p>".to_string()
-            } else {
-                format!(
-                    "<p style=\"margin:0;line-height:1.65;word-break:break-all;\">{}</p>",
-                    html_escape(content)
-                )
-            };
-            Some(reminder_email_shell(&reminder.title, inner))
-        }
-        _ => None,
-    }
-}
-
-pub(crate) async fn trigger_task_reminder_backend(
-    app: &AppHandle,
-    smtp_config: Option<SmtpEmailConfig>,
-    reminder: &TaskReminder,
-) -> CmdResult<()> {
-    if reminder.channel.eq_ignore_ascii_case("fullscreen") {
-        let payload = ReminderScreenEventPayload {
-            title: reminder.title.clone(),
-            content: reminder.content.clone(),
-            content_type: reminder.content_type.clone(),
-            advanced_settings: reminder.advanced_settings.clone(),
-        };
-        if app.get_webview_window("main").is_some() {
-            app.emit_to("main", "reminder://trigger", payload)
-                .map_err(|e| e.to_string())?;
-            return Ok(());
-        }
-        if app.get_webview_window("toolkit").is_some() {
-            app.emit_to("toolkit", "reminder://trigger", payload)
-                .map_err(|e| e.to_string())?;
-            return Ok(());
-        }
-        if app.get_webview_window("taskbar").is_some() {
-            app.emit_to("taskbar", "reminder://trigger", payload)
-                .map_err(|e| e.to_string())?;
-        }
-        return Ok(());
-    }
-    let smtp = smtp_config.ok_or_else(|| "SMTP config is required.".to_string())?;
-    let target = if reminder.email.trim().is_empty() {
-        smtp.from_email.trim().to_string()
-    } else {
-        reminder.email.trim().to_string()
-    };
-    if target.is_empty() {
-        return Err("recipient email is empty; configure SMTP from email first".to_string());
-    }
-    send_email_internal(
-        smtp,
-        target,
-        format!("[PulseCore] {}", reminder.title),
-        reminder_plain_body(reminder),
-        reminder_html_body(reminder),
-    )
-    .await
-}
-
 #[tauri::command]
 pub async fn trigger_task_reminder_now(
     app: AppHandle,
@@ -925,8 +872,9 @@ pub async fn trigger_task_reminder_now(
     reminder: TaskReminder,
 ) -> CmdResult<()> {
     let normalized = normalize_task_reminder(reminder);
+    validate_task_reminder(&normalized)?;
     let smtp = state.reminder_smtp_config.read().await.clone();
-    trigger_task_reminder_backend(&app, smtp, &normalized).await
+    reminders::trigger_reminder(&core_context(&app), smtp, &normalized).await
 }
 
 #[tauri::command]
@@ -1575,7 +1523,7 @@ fn normalize_shutdown_plan(mut plan: ShutdownPlan) -> Option<ShutdownPlan> {
 
 #[tauri::command]
 pub async fn get_shutdown_plan(app: AppHandle) -> CmdResult<Option<ShutdownPlan>> {
-    let plan = read_shutdown_plan(&app).and_then(normalize_shutdown_plan);
+    let plan = read_shutdown_plan(&TauriAdapter::new(&app)).and_then(normalize_shutdown_plan);
     if plan.is_none() {
         clear_shutdown_plan_file(&app);
     }
@@ -1829,8 +1777,9 @@ pub async fn set_memory_trim_enabled(
 ) -> CmdResult<()> {
     use std::sync::atomic::Ordering;
     state.memory_trim_enabled.store(enabled, Ordering::Relaxed);
-    let mut settings = state.settings.write().await;
-    settings.memory_trim_enabled = enabled;
+    state
+        .update_settings(|settings| settings.memory_trim_enabled = enabled)
+        .await;
     Ok(())
 }
 
@@ -1843,7 +1792,9 @@ pub async fn set_memory_trim_system_enabled(
     state
         .memory_trim_system_enabled
         .store(enabled, Ordering::Relaxed);
-    state.settings.write().await.memory_trim_system_enabled = enabled;
+    state
+        .update_settings(|settings| settings.memory_trim_system_enabled = enabled)
+        .await;
     Ok(())
 }
 
@@ -1858,11 +1809,85 @@ pub async fn set_memory_trim_interval(
     state
         .memory_trim_interval_ms
         .store(interval_ms, Ordering::Relaxed);
-    let mut settings = state.settings.write().await;
-    settings.memory_trim_interval_minutes = minutes as u8;
+    state
+        .update_settings(|settings| settings.memory_trim_interval_minutes = minutes as u8)
+        .await;
     Ok(())
 }
 
+/// Sets the main window title template; an empty or missing one restores the default.
+#[tauri::command]
+pub async fn set_window_title_template(
+    state: State<'_, SharedState>,
+    template: Option<String>,
+) -> CmdResult<()> {
+    let template = template
+        .map(|template| template.trim().to_string())
+        .filter(|template| !template.is_empty());
+    if let Some(template) = template.as_deref() {
+        Template::parse(template)?;
+    }
+    state
+        .update_settings(|settings| settings.window_title_template = template)
+        .await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_metric_thresholds(
+    state: State<'_, SharedState>,
+) -> CmdResult<Vec<MetricThreshold>> {
+    Ok(state.settings.read().await.metric_thresholds.clone())
+}
+
+/// Replaces the warn/critical bands shared by the overlay and the native taskbar.
+#[tauri::command]
+pub async fn set_metric_thresholds(
+    state: State<'_, SharedState>,
+    thresholds: Vec<MetricThreshold>,
+) -> CmdResult<Vec<MetricThreshold>> {
+    ThresholdBands::parse(&thresholds)?;
+    let stored = thresholds.clone();
+    state
+        .update_settings(|settings| settings.metric_thresholds = stored)
+        .await;
+    let snapshot = state.latest_snapshot.read().await.clone();
+    crate::native_taskbar::set_thresholds(thresholds.clone(), snapshot);
+    Ok(thresholds)
+}
+
+#[tauri::command]
+pub async fn get_unit_preferences(state: State<'_, SharedState>) -> CmdResult<UnitPreferences> {
+    Ok(state.settings.read().await.units.clone())
+}
+
+/// Replaces the units and number style used by the taskbar, window title and emails.
+#[tauri::command]
+pub async fn set_unit_preferences(
+    state: State<'_, SharedState>,
+    units: UnitPreferences,
+) -> CmdResult<UnitPreferences> {
+    UnitFormatter::parse(&units)?;
+    let stored = units.clone();
+    state
+        .update_settings(|settings| settings.units = stored)
+        .await;
+    let snapshot = state.latest_snapshot.read().await.clone();
+    crate::native_taskbar::set_units(units.clone(), snapshot);
+    Ok(units)
+}
+
+/// Renders a display template against the latest snapshot, for live previews while editing.
+#[tauri::command]
+pub async fn preview_display_template(
+    state: State<'_, SharedState>,
+    template: String,
+) -> CmdResult<String> {
+    let template = Template::parse(&template)?;
+    let units = UnitFormatter::lenient(&state.settings.read().await.units);
+    Ok(template.render(&*state.latest_snapshot.read().await, &units))
+}
+
 #[tauri::command]
 pub async fn save_export_config(path: String, content: String) -> CmdResult<()> {
     if path.trim().is_empty() {
@@ -1,24 +1,36 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod adaptive_sampling;
+mod alerts;
+mod anomaly;
 mod app;
-mod core;
+mod digest;
+mod flight_recorder;
 mod ipc;
 mod local_ai;
+mod mqtt;
 mod native_taskbar;
 mod profiler;
+mod replay;
+mod settings;
 mod startup_items;
-mod system_tools;
 mod state;
-mod types;
+mod system_tools;
+mod tauri_adapter;
+mod telemetry_stream;
+mod tray_icon;
+mod webhooks;
 
 use crate::state::AppState;
+use pulsecore_core::{core, types};
 use tauri::{Manager, RunEvent};
 
 fn main() {
     tracing_subscriber::fmt()
         .with_env_filter(
-            std::env::var("RUST_LOG")
-                .unwrap_or_else(|_| "pulsecorelite=info,tauri=info".to_string()),
+            std::env::var("RUST_LOG").unwrap_or_else(|_| {
+                "pulsecorelite=info,pulsecore_core=info,tauri=info".to_string()
+            }),
         )
         .init();
 
@@ -26,12 +38,25 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_process::init())
         .setup(|app| {
-            let state = tauri::async_runtime::block_on(AppState::initialize())
+            let settings = crate::settings::load_settings(app.handle());
+            let state = tauri::async_runtime::block_on(AppState::initialize(settings))
                 .expect("failed to initialize PulseCoreLite state");
 
             app.manage(state.clone());
+            crate::settings::start_settings_store(app.handle().clone(), state.clone());
+            crate::adaptive_sampling::start_adaptive_sampling(app.handle().clone(), state.clone());
+            crate::alerts::start_alert_engine(app.handle().clone(), state.clone());
+            crate::anomaly::start_anomaly_detector(app.handle().clone(), state.clone());
+            crate::digest::start_health_digest(app.handle().clone(), state.clone());
+            crate::flight_recorder::start_flight_recorder(app.handle().clone(), state.clone());
+            crate::tray_icon::start_tray_icon(app.handle().clone(), state.clone());
             crate::app::start_telemetry_loop(app.handle().clone(), state.clone());
             crate::app::start_memory_trim_loop(state.clone());
+            crate::telemetry_stream::start_telemetry_stream_service(
+                app.handle().clone(),
+                state.clone(),
+            );
+            crate::mqtt::start_mqtt_service(app.handle().clone(), state.clone());
             crate::app::start_task_reminder_loop(app.handle().clone(), state);
 
             #[cfg(desktop)]
@@ -54,9 +79,16 @@ fn main() {
     app.run(|app_handle, event| {
         if matches!(event, RunEvent::Exit | RunEvent::ExitRequested { .. }) {
             if let Some(state) = app_handle.try_state::<crate::state::SharedState>() {
+                tauri::async_runtime::block_on(crate::settings::flush_settings(
+                    app_handle,
+                    state.inner(),
+                ));
                 tauri::async_runtime::block_on(crate::local_ai::shutdown_local_ai_runtime(
                     state.inner().clone(),
                 ));
+                tauri::async_runtime::block_on(crate::mqtt::shutdown_mqtt_publisher(
+                    state.inner().clone(),
+                ));
             }
         }
     });
@@ -0,0 +1,698 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex as StdMutex},
+    time::{Duration, Instant},
+};
+
+use rumqttc::{
+    AsyncClient, Event, LastWill, MqttOptions, Packet, QoS, TlsConfiguration, Transport,
+};
+use serde_json::{json, Map, Value};
+use tauri::{async_runtime::JoinHandle, AppHandle, State};
+use tokio::sync::{
+    broadcast::{self, error::TryRecvError},
+    watch,
+};
+
+use crate::{
+    core::{ports::PathProvider, store},
+    state::SharedState,
+    tauri_adapter::TauriAdapter,
+    types::{MqttConfig, MqttStatus, TelemetrySnapshot},
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const MQTT_CONFIG_FILE: &str = "mqtt.json";
+const MQTT_KEEP_ALIVE_SECS: u64 = 30;
+const MQTT_REQUEST_CAPACITY: usize = 64;
+const MQTT_MIN_PUBLISH_INTERVAL_MS: u64 = 1000;
+const MQTT_RECONNECT_MIN_MS: u64 = 1000;
+const MQTT_RECONNECT_MAX_MS: u64 = 30_000;
+const AVAILABILITY_ONLINE: &str = "online";
+const AVAILABILITY_OFFLINE: &str = "offline";
+
+#[derive(Default)]
+pub struct MqttRuntime {
+    handle: Option<MqttHandle>,
+    status: Arc<StdMutex<MqttStatus>>,
+}
+
+struct MqttHandle {
+    stop: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl MqttRuntime {
+    pub fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    fn status(&self) -> MqttStatus {
+        let mut status = self
+            .status
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default();
+        status.running = self.handle.is_some();
+        status
+    }
+
+    fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.stop.send(true);
+            handle.task.abort();
+        }
+        if let Ok(mut status) = self.status.lock() {
+            status.connected = false;
+        }
+    }
+}
+
+/// One Home Assistant sensor: its key in the state payload plus discovery metadata.
+#[derive(Debug, Clone, PartialEq)]
+struct SensorReading {
+    key: String,
+    name: String,
+    unit: Option<&'static str>,
+    device_class: Option<&'static str>,
+    value: f64,
+}
+
+impl SensorReading {
+    fn new(key: impl Into<String>, name: impl Into<String>, value: f64) -> Self {
+        Self {
+            key: key.into(),
+            name: name.into(),
+            unit: None,
+            device_class: None,
+            value,
+        }
+    }
+
+    fn unit(mut self, unit: &'static str) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    fn device_class(mut self, device_class: &'static str) -> Self {
+        self.device_class = Some(device_class);
+        self
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn sanitize_id(value: &str) -> String {
+    let mut id = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if ch.is_ascii_alphanumeric() {
+            id.push(ch.to_ascii_lowercase());
+        } else if !id.ends_with('_') {
+            id.push('_');
+        }
+    }
+    let trimmed = id.trim_matches('_');
+    if trimmed.is_empty() {
+        "unknown".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn sensor_readings(snapshot: &TelemetrySnapshot) -> Vec<SensorReading> {
+    let mut readings = vec![
+        SensorReading::new("cpu_usage", "CPU Usage", snapshot.cpu.usage_pct).unit("%"),
+        SensorReading::new("memory_usage", "Memory Usage", snapshot.memory.usage_pct).unit("%"),
+        SensorReading::new("memory_used", "Memory Used", snapshot.memory.used_mb)
+            .unit("MB")
+            .device_class("data_size"),
+        SensorReading::new(
+            "net_download",
+            "Network Download",
+            snapshot.network.download_bytes_per_sec,
+        )
+        .unit("B/s")
+        .device_class("data_rate"),
+        SensorReading::new(
+            "net_upload",
+            "Network Upload",
+            snapshot.network.upload_bytes_per_sec,
+        )
+        .unit("B/s")
+        .device_class("data_rate"),
+    ];
+
+    if let Some(temp) = snapshot.cpu.temperature_c {
+        readings.push(
+            SensorReading::new("cpu_temp", "CPU Temperature", temp)
+                .unit("°C")
+                .device_class("temperature"),
+        );
+    }
+    if let Some(freq) = snapshot.cpu.frequency_mhz {
+        readings.push(
+            SensorReading::new("cpu_freq", "CPU Frequency", freq as f64)
+                .unit("MHz")
+                .device_class("frequency"),
+        );
+    }
+    if let Some(usage) = snapshot.gpu.usage_pct {
+        readings.push(SensorReading::new("gpu_usage", "GPU Usage", usage).unit("%"));
+    }
+    if let Some(temp) = snapshot.gpu.temperature_c {
+        readings.push(
+            SensorReading::new("gpu_temp", "GPU Temperature", temp)
+                .unit("°C")
+                .device_class("temperature"),
+        );
+    }
+    if let Some(used) = snapshot.gpu.memory_used_mb {
+        readings.push(
+            SensorReading::new("gpu_memory_used", "GPU Memory Used", used)
+                .unit("MB")
+                .device_class("data_size"),
+        );
+    }
+    if let Some(latency) = snapshot.network.latency_ms {
+        readings.push(
+            SensorReading::new("net_latency", "Network Latency", latency)
+                .unit("ms")
+                .device_class("duration"),
+        );
+    }
+    if let Some(watts) = snapshot.power_watts {
+        readings.push(
+            SensorReading::new("power", "Power", watts)
+                .unit("W")
+                .device_class("power"),
+        );
+    }
+    for disk in &snapshot.disks {
+        let id = sanitize_id(&disk.name);
+        let label = if disk.label.trim().is_empty() {
+            disk.name.trim().to_string()
+        } else {
+            disk.label.trim().to_string()
+        };
+        readings.push(
+            SensorReading::new(
+                format!("disk_{id}_usage"),
+                format!("Disk {label} Usage"),
+                disk.usage_pct,
+            )
+            .unit("%"),
+        );
+        if let Some(read) = disk.read_bytes_per_sec {
+            readings.push(
+                SensorReading::new(
+                    format!("disk_{id}_read"),
+                    format!("Disk {label} Read"),
+                    read,
+                )
+                .unit("B/s")
+                .device_class("data_rate"),
+            );
+        }
+        if let Some(write) = disk.write_bytes_per_sec {
+            readings.push(
+                SensorReading::new(
+                    format!("disk_{id}_write"),
+                    format!("Disk {label} Write"),
+                    write,
+                )
+                .unit("B/s")
+                .device_class("data_rate"),
+            );
+        }
+    }
+
+    readings
+}
+
+fn state_payload(readings: &[SensorReading], snapshot: &TelemetrySnapshot) -> Value {
+    let mut map = Map::new();
+    map.insert(
+        "timestamp".to_string(),
+        json!(snapshot.timestamp.to_rfc3339()),
+    );
+    for reading in readings {
+        if reading.value.is_finite() {
+            map.insert(reading.key.clone(), json!(round2(reading.value)));
+        }
+    }
+    Value::Object(map)
+}
+
+struct TopicLayout {
+    node_id: String,
+    state: String,
+    availability: String,
+    discovery_prefix: String,
+}
+
+impl TopicLayout {
+    fn new(config: &MqttConfig) -> Self {
+        let prefix = config.topic_prefix.trim_end_matches('/');
+        Self {
+            node_id: sanitize_id(&config.client_id),
+            state: format!("{prefix}/state"),
+            availability: format!("{prefix}/availability"),
+            discovery_prefix: config.discovery_prefix.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn discovery_topic(&self, reading: &SensorReading) -> String {
+        format!(
+            "{}/sensor/{}/{}/config",
+            self.discovery_prefix, self.node_id, reading.key
+        )
+    }
+
+    fn discovery_payload(&self, reading: &SensorReading) -> Value {
+        let mut payload = json!({
+            "name": reading.name,
+            "unique_id": format!("{}_{}", self.node_id, reading.key),
+            "object_id": format!("{}_{}", self.node_id, reading.key),
+            "state_topic": self.state,
+            "value_template": format!("{{{{ value_json.{} }}}}", reading.key),
+            "state_class": "measurement",
+            "availability_topic": self.availability,
+            "payload_available": AVAILABILITY_ONLINE,
+            "payload_not_available": AVAILABILITY_OFFLINE,
+            "device": {
+                "identifiers": [self.node_id],
+                "name": format!("PulseCoreLite ({})", self.node_id),
+                "manufacturer": "PulseCoreLite",
+                "model": "PulseCoreLite",
+                "sw_version": env!("CARGO_PKG_VERSION"),
+            },
+        });
+        if let Some(unit) = reading.unit {
+            payload["unit_of_measurement"] = json!(unit);
+        }
+        if let Some(device_class) = reading.device_class {
+            payload["device_class"] = json!(device_class);
+        }
+        payload
+    }
+}
+
+fn qos_from_level(level: u8) -> QoS {
+    match level {
+        2 => QoS::ExactlyOnce,
+        1 => QoS::AtLeastOnce,
+        _ => QoS::AtMostOnce,
+    }
+}
+
+fn mqtt_options(config: &MqttConfig, topics: &TopicLayout) -> MqttOptions {
+    let mut options = MqttOptions::new(config.client_id.clone(), config.host.clone(), config.port);
+    options.set_keep_alive(Duration::from_secs(MQTT_KEEP_ALIVE_SECS));
+    options.set_last_will(LastWill::new(
+        topics.availability.clone(),
+        AVAILABILITY_OFFLINE,
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if !config.username.is_empty() {
+        options.set_credentials(config.username.clone(), config.password.clone());
+    }
+    if config.use_tls {
+        options.set_transport(Transport::tls_with_config(TlsConfiguration::Native));
+    }
+    options
+}
+
+fn read_mqtt_config(paths: &dyn PathProvider) -> Option<MqttConfig> {
+    store::read_json(paths, MQTT_CONFIG_FILE)
+}
+
+fn write_mqtt_config(paths: &dyn PathProvider, config: &MqttConfig) -> CmdResult<()> {
+    store::write_json(paths, MQTT_CONFIG_FILE, config)
+}
+
+fn normalize_mqtt_config(input: MqttConfig) -> MqttConfig {
+    let defaults = MqttConfig::default();
+    let non_empty = |value: String, fallback: String| {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            fallback
+        } else {
+            trimmed.to_string()
+        }
+    };
+    MqttConfig {
+        enabled: input.enabled,
+        host: input.host.trim().to_string(),
+        port: if input.port == 0 {
+            defaults.port
+        } else {
+            input.port
+        },
+        use_tls: input.use_tls,
+        username: input.username.trim().to_string(),
+        password: input.password,
+        client_id: non_empty(input.client_id, defaults.client_id),
+        topic_prefix: non_empty(
+            input.topic_prefix.trim_matches('/').to_string(),
+            defaults.topic_prefix,
+        ),
+        qos: input.qos.min(2),
+        publish_interval_ms: input.publish_interval_ms.max(MQTT_MIN_PUBLISH_INTERVAL_MS),
+        home_assistant_discovery: input.home_assistant_discovery,
+        discovery_prefix: non_empty(
+            input.discovery_prefix.trim_matches('/').to_string(),
+            defaults.discovery_prefix,
+        ),
+    }
+}
+
+fn set_status(status: &StdMutex<MqttStatus>, update: impl FnOnce(&mut MqttStatus)) {
+    if let Ok(mut guard) = status.lock() {
+        update(&mut guard);
+    }
+}
+
+/// Drives one broker session: keeps the event loop polled (rumqttc reconnects on the next
+/// poll after an error), republishes availability/discovery on every ConnAck and pushes a
+/// throttled state message for incoming snapshots.
+async fn run_mqtt_publisher(
+    config: MqttConfig,
+    mut snapshots: broadcast::Receiver<TelemetrySnapshot>,
+    mut latest: TelemetrySnapshot,
+    status: Arc<StdMutex<MqttStatus>>,
+    mut stop: watch::Receiver<bool>,
+) {
+    let topics = TopicLayout::new(&config);
+    let qos = qos_from_level(config.qos);
+    let interval = Duration::from_millis(config.publish_interval_ms);
+    let (client, mut eventloop) =
+        AsyncClient::new(mqtt_options(&config, &topics), MQTT_REQUEST_CAPACITY);
+
+    let mut announced: HashSet<String> = HashSet::new();
+    let mut connected = false;
+    let mut last_published: Option<Instant> = None;
+    let mut backoff_ms = MQTT_RECONNECT_MIN_MS;
+    let mut reconnect_delay: Option<Duration> = None;
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        if let Some(delay) = reconnect_delay.take() {
+            tokio::select! {
+                _ = stop.changed() => break,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+        tokio::select! {
+            _ = stop.changed() => break,
+            event = eventloop.poll() => match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    connected = true;
+                    backoff_ms = MQTT_RECONNECT_MIN_MS;
+                    announced.clear();
+                    set_status(&status, |status| {
+                        status.connected = true;
+                        status.error = None;
+                    });
+                    let _ = client.try_publish(
+                        topics.availability.clone(),
+                        QoS::AtLeastOnce,
+                        true,
+                        AVAILABILITY_ONLINE,
+                    );
+                    last_published = None;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    connected = false;
+                    set_status(&status, |status| {
+                        status.connected = false;
+                        status.error = Some(err.to_string());
+                    });
+                    tracing::debug!("mqtt connection error, retrying in {backoff_ms}ms: {err}");
+                    reconnect_delay = Some(Duration::from_millis(backoff_ms));
+                    backoff_ms = (backoff_ms * 2).min(MQTT_RECONNECT_MAX_MS);
+                }
+            },
+            _ = ticker.tick() => {
+                loop {
+                    match snapshots.try_recv() {
+                        Ok(snapshot) => latest = snapshot,
+                        Err(TryRecvError::Lagged(_)) => continue,
+                        Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+                    }
+                }
+                if !connected || last_published.is_some_and(|at| at.elapsed() < interval / 2) {
+                    continue;
+                }
+
+                let readings = sensor_readings(&latest);
+                if config.home_assistant_discovery {
+                    for reading in &readings {
+                        if announced.contains(&reading.key) {
+                            continue;
+                        }
+                        let published = client.try_publish(
+                            topics.discovery_topic(reading),
+                            QoS::AtLeastOnce,
+                            true,
+                            topics.discovery_payload(reading).to_string(),
+                        );
+                        if published.is_ok() {
+                            announced.insert(reading.key.clone());
+                        }
+                    }
+                }
+                let payload = state_payload(&readings, &latest).to_string();
+                if client.try_publish(topics.state.clone(), qos, false, payload).is_ok() {
+                    last_published = Some(Instant::now());
+                    set_status(&status, |status| status.published += 1);
+                }
+            }
+        }
+    }
+
+    // Graceful shutdown: announce offline ourselves since the broker only sends the LWT
+    // for unexpected disconnects.
+    if connected {
+        let _ = client.try_publish(
+            topics.availability.clone(),
+            QoS::AtLeastOnce,
+            true,
+            AVAILABILITY_OFFLINE,
+        );
+        let _ = client.try_disconnect();
+        let _ = tokio::time::timeout(Duration::from_millis(500), async {
+            while eventloop.poll().await.is_ok() {}
+        })
+        .await;
+    }
+    set_status(&status, |status| status.connected = false);
+}
+
+/// Starts the publisher at launch when the persisted config has it enabled.
+pub fn start_mqtt_service(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        let Some(config) = read_mqtt_config(&TauriAdapter::new(&app)) else {
+            return;
+        };
+        if let Err(err) = apply_mqtt_config(&state, normalize_mqtt_config(config)).await {
+            tracing::warn!("mqtt publisher failed to start: {err}");
+        }
+    });
+}
+
+async fn apply_mqtt_config(state: &SharedState, config: MqttConfig) -> CmdResult<MqttStatus> {
+    let mut runtime = state.mqtt.lock().await;
+    runtime.stop();
+    set_status(&runtime.status, |status| status.error = None);
+    if !config.enabled {
+        return Ok(runtime.status());
+    }
+    if config.host.is_empty() {
+        return Err("mqtt host is empty".to_string());
+    }
+
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let snapshots = state.snapshot_tx.subscribe();
+    let latest = state.latest_snapshot.read().await.clone();
+    let task = tauri::async_runtime::spawn(run_mqtt_publisher(
+        config,
+        snapshots,
+        latest,
+        Arc::clone(&runtime.status),
+        stop_rx,
+    ));
+    runtime.handle = Some(MqttHandle {
+        stop: stop_tx,
+        task,
+    });
+    Ok(runtime.status())
+}
+
+/// Publishes the offline availability message before the process exits.
+pub async fn shutdown_mqtt_publisher(state: SharedState) {
+    let handle = state.mqtt.lock().await.handle.take();
+    if let Some(handle) = handle {
+        let _ = handle.stop.send(true);
+        let _ = tokio::time::timeout(Duration::from_secs(1), handle.task).await;
+    }
+}
+
+#[tauri::command]
+pub fn get_mqtt_config(app: AppHandle) -> CmdResult<MqttConfig> {
+    Ok(read_mqtt_config(&TauriAdapter::new(&app))
+        .map(normalize_mqtt_config)
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn save_mqtt_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: MqttConfig,
+) -> CmdResult<MqttStatus> {
+    let normalized = normalize_mqtt_config(config);
+    write_mqtt_config(&TauriAdapter::new(&app), &normalized)?;
+    apply_mqtt_config(state.inner(), normalized).await
+}
+
+#[tauri::command]
+pub async fn get_mqtt_status(state: State<'_, SharedState>) -> CmdResult<MqttStatus> {
+    Ok(state.mqtt.lock().await.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support;
+
+    fn sample_snapshot() -> TelemetrySnapshot {
+        let mut snapshot = test_support::snapshot();
+        snapshot.cpu.usage_pct = 12.345;
+        snapshot.cpu.frequency_mhz = Some(3600);
+        snapshot.cpu.temperature_c = Some(55.0);
+        snapshot.gpu.usage_pct = Some(40.0);
+        let mut disk = test_support::disk("C:\\", 100.0, 500.0);
+        disk.label = "System".to_string();
+        snapshot.disks.push(disk);
+        snapshot.app_cpu_usage_pct = Some(1.0);
+        snapshot.app_memory_mb = Some(80.0);
+        snapshot
+    }
+
+    #[test]
+    fn sanitizes_ids_for_topics() {
+        assert_eq!(sanitize_id("C:\\"), "c");
+        assert_eq!(sanitize_id("My PC #1"), "my_pc_1");
+        assert_eq!(sanitize_id("///"), "unknown");
+    }
+
+    #[test]
+    fn only_present_metrics_become_sensors() {
+        let readings = sensor_readings(&sample_snapshot());
+        let keys: Vec<&str> = readings.iter().map(|r| r.key.as_str()).collect();
+        assert!(keys.contains(&"cpu_temp"));
+        assert!(keys.contains(&"gpu_usage"));
+        assert!(keys.contains(&"disk_c_usage"));
+        assert!(!keys.contains(&"gpu_temp"));
+        assert!(!keys.contains(&"power"));
+    }
+
+    #[test]
+    fn state_payload_rounds_readings() {
+        let readings = sensor_readings(&sample_snapshot());
+        let state = state_payload(&readings, &sample_snapshot());
+        assert_eq!(state["cpu_usage"], json!(12.35));
+        assert_eq!(state["disk_c_usage"], json!(20.0));
+    }
+
+    #[test]
+    fn discovery_payload_points_at_state_topic() {
+        let topics = TopicLayout::new(&MqttConfig {
+            client_id: "Desk PC".to_string(),
+            topic_prefix: "home/pc/".to_string(),
+            ..MqttConfig::default()
+        });
+        let reading = SensorReading::new("cpu_temp", "CPU Temperature", 50.0)
+            .unit("°C")
+            .device_class("temperature");
+        assert_eq!(
+            topics.discovery_topic(&reading),
+            "homeassistant/sensor/desk_pc/cpu_temp/config"
+        );
+        let payload = topics.discovery_payload(&reading);
+        assert_eq!(payload["state_topic"], json!("home/pc/state"));
+        assert_eq!(payload["availability_topic"], json!("home/pc/availability"));
+        assert_eq!(
+            payload["value_template"],
+            json!("{{ value_json.cpu_temp }}")
+        );
+        assert_eq!(payload["device_class"], json!("temperature"));
+    }
+
+    /// Run against a local broker, e.g. `mosquitto -p 1883`, with
+    /// `PULSECORE_TEST_MQTT_HOST=127.0.0.1 cargo test mqtt -- --ignored`.
+    #[tokio::test]
+    #[ignore = "requires a locally started MQTT broker"]
+    async fn publishes_discovery_and_state_to_local_broker() {
+        let host =
+            std::env::var("PULSECORE_TEST_MQTT_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let config = normalize_mqtt_config(MqttConfig {
+            enabled: true,
+            host: host.clone(),
+            client_id: "pulsecore-test".to_string(),
+            topic_prefix: "pulsecore-test".to_string(),
+            ..MqttConfig::default()
+        });
+
+        let mut watcher_options = MqttOptions::new("pulsecore-test-watcher", host, config.port);
+        watcher_options.set_keep_alive(Duration::from_secs(5));
+        let (watcher, mut watcher_loop) = AsyncClient::new(watcher_options, 16);
+        watcher
+            .subscribe("pulsecore-test/#", QoS::AtLeastOnce)
+            .await
+            .unwrap();
+        watcher
+            .subscribe(
+                "homeassistant/sensor/pulsecore_test/+/config",
+                QoS::AtLeastOnce,
+            )
+            .await
+            .unwrap();
+
+        let (snapshot_tx, snapshot_rx) = broadcast::channel(4);
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let status = Arc::new(StdMutex::new(MqttStatus::default()));
+        let publisher = tokio::spawn(run_mqtt_publisher(
+            config,
+            snapshot_rx,
+            sample_snapshot(),
+            Arc::clone(&status),
+            stop_rx,
+        ));
+        let _ = snapshot_tx.send(sample_snapshot());
+
+        let mut seen_state = false;
+        let mut seen_discovery = false;
+        let deadline = Instant::now() + Duration::from_secs(15);
+        while !(seen_state && seen_discovery) && Instant::now() < deadline {
+            if let Ok(Ok(Event::Incoming(Packet::Publish(publish)))) =
+                tokio::time::timeout(Duration::from_secs(1), watcher_loop.poll()).await
+            {
+                seen_state |= publish.topic == "pulsecore-test/state";
+                seen_discovery |= publish.topic.ends_with("/cpu_usage/config");
+            }
+        }
+
+        let _ = stop_tx.send(true);
+        let _ = publisher.await;
+        assert!(seen_state, "state message not received");
+        assert!(seen_discovery, "discovery config not received");
+        assert!(status.lock().unwrap().published > 0);
+    }
+}
@@ -1,11 +1,36 @@
+use tauri::AppHandle;
+
 use crate::{
+    core::store,
     state::SharedState,
-    types::{AppSettings, NativeTaskbarConfig, TaskbarInfo, TelemetrySnapshot},
+    tauri_adapter::TauriAdapter,
+    types::{
+        AppSettings, MetricThreshold, NativeTaskbarConfig, TaskbarInfo, TaskbarSegmentConfig,
+        TelemetrySnapshot, UnitPreferences,
+    },
 };
 
+/// The segment list is kept by the backend: the front end only stores the legacy `show*`
+/// toggles, which are applied on top of it.
+const SEGMENTS_FILE: &str = "native-taskbar-segments.json";
+
+pub fn read_segments(app: &AppHandle) -> Option<Vec<TaskbarSegmentConfig>> {
+    store::read_json(&TauriAdapter::new(app), SEGMENTS_FILE)
+}
+
+pub fn write_segments(app: &AppHandle, segments: &[TaskbarSegmentConfig]) -> Result<(), String> {
+    store::write_json(&TauriAdapter::new(app), SEGMENTS_FILE, &segments)
+}
+
 #[cfg(windows)]
 mod imp {
     use super::*;
+    use crate::core::{
+        sparkline::{rasterize, MetricHistory},
+        taskbar::{sync_legacy_toggles, SegmentKind, SegmentTone, TaskbarLayout, TaskbarSegment},
+        thresholds::Rgb,
+    };
+    use image::RgbaImage;
     use std::{
         collections::VecDeque,
         ffi::OsStr,
@@ -20,13 +45,14 @@ mod imp {
     };
 
     use serde::Serialize;
-    use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+    use tauri::{Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
     use windows_sys::Win32::{
         Foundation::{COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, RECT, SIZE, WPARAM},
         Graphics::Gdi::{
             BeginPaint, CreateSolidBrush, DeleteObject, EndPaint, FillRect, GetStockObject,
-            GetTextExtentPoint32W, SelectObject, SetBkMode, SetTextColor, TextOutW,
-            ANSI_FIXED_FONT, DEFAULT_GUI_FONT, PAINTSTRUCT, TRANSPARENT,
+            GetTextExtentPoint32W, SelectObject, SetBkMode, SetDIBitsToDevice, SetTextColor,
+            TextOutW, ANSI_FIXED_FONT, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DEFAULT_GUI_FONT,
+            DIB_RGB_COLORS, PAINTSTRUCT, TRANSPARENT,
         },
         UI::{
             Shell::{SHAppBarMessage, ABM_GETTASKBARPOS, APPBARDATA},
@@ -36,10 +62,11 @@ mod imp {
                 PostMessageW, PostQuitMessage, RegisterClassW, SetLayeredWindowAttributes,
                 SetWindowPos, ShowWindow, TrackPopupMenu, TranslateMessage, CS_DBLCLKS,
                 CW_USEDEFAULT, HMENU, HTCAPTION, HWND_NOTOPMOST, HWND_TOPMOST, IDC_ARROW,
-                LWA_ALPHA, MF_CHECKED, MF_SEPARATOR, MF_STRING, MF_UNCHECKED, MSG, SWP_NOACTIVATE,
-                SW_HIDE, SW_SHOWNOACTIVATE, TPM_LEFTALIGN, TPM_RETURNCMD, TPM_RIGHTBUTTON, WM_APP,
-                WM_DESTROY, WM_EXITSIZEMOVE, WM_LBUTTONDBLCLK, WM_MOVE, WM_NCHITTEST, WM_PAINT,
-                WM_RBUTTONUP, WNDCLASSW, WS_EX_LAYERED, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP,
+                LWA_ALPHA, MF_CHECKED, MF_POPUP, MF_SEPARATOR, MF_STRING, MF_UNCHECKED, MSG,
+                SWP_NOACTIVATE, SW_HIDE, SW_SHOWNOACTIVATE, TPM_LEFTALIGN, TPM_RETURNCMD,
+                TPM_RIGHTBUTTON, WM_APP, WM_DESTROY, WM_EXITSIZEMOVE, WM_LBUTTONDBLCLK, WM_MOVE,
+                WM_NCHITTEST, WM_PAINT, WM_RBUTTONUP, WNDCLASSW, WS_EX_LAYERED, WS_EX_TOOLWINDOW,
+                WS_EX_TOPMOST, WS_POPUP,
             },
         },
     };
@@ -63,18 +90,12 @@ mod imp {
     const MENU_THEME_TRANSPARENT: usize = 1007;
     const MENU_THEME_DARK: usize = 1008;
     const MENU_THEME_LIGHT: usize = 1009;
-    const MENU_SHOW_CPU: usize = 1010;
-    const MENU_SHOW_GPU: usize = 1011;
-    const MENU_SHOW_MEMORY: usize = 1012;
-    const MENU_SHOW_APP: usize = 1013;
-    const MENU_SHOW_DOWN: usize = 1014;
-    const MENU_SHOW_UP: usize = 1015;
-    const MENU_SHOW_CPU_FREQ: usize = 1016;
-    const MENU_SHOW_CPU_TEMP: usize = 1017;
-    const MENU_SHOW_GPU_TEMP: usize = 1018;
-    const MENU_SHOW_LATENCY: usize = 1019;
     const MENU_CLOSE_TASKBAR: usize = 1020;
     const MENU_EXIT_APP: usize = 1021;
+    /// Segment entries are numbered from here in the order they were added to the menu.
+    const MENU_SEGMENT_FIRST: usize = 1100;
+    const SPARKLINE_HEIGHT: u32 = 12;
+    const SPARKLINE_GAP: i32 = 4;
 
     #[derive(Clone)]
     enum NativeTaskbarCommand {
@@ -83,27 +104,17 @@ mod imp {
         Close,
     }
 
-    #[derive(Clone, Copy)]
-    enum SegmentTone {
-        Normal,
-        Muted,
-        Cyan,
-        Pink,
-        Orange,
-        Red,
-    }
-
-    #[derive(Clone, Default)]
-    struct NativeTaskbarSegment {
-        label: String,
-        value: String,
-        extra: Option<String>,
-        value_tone: SegmentTone,
+    /// What a segment entry of the context menu does.
+    enum SegmentMenuAction {
+        Toggle(usize),
+        ToggleFreq(usize),
+        ToggleTemp(usize),
+        Add(TaskbarSegmentConfig),
     }
 
     #[derive(Clone, Default)]
     struct NativeTaskbarModel {
-        rows: Vec<Vec<NativeTaskbarSegment>>,
+        rows: Vec<Vec<TaskbarSegment>>,
     }
 
     struct NativeTaskbarShared {
@@ -113,7 +124,11 @@ mod imp {
         app: Mutex<Option<AppHandle>>,
         state: Mutex<Option<SharedState>>,
         config: Mutex<Option<NativeTaskbarConfig>>,
+        /// `config` parsed for rendering; rebuilt on `ApplyConfig`, not on every snapshot.
+        layout: Mutex<Option<Arc<TaskbarLayout>>>,
         model: Mutex<NativeTaskbarModel>,
+        /// Recent values of the metrics the configured sparklines plot.
+        history: Mutex<MetricHistory>,
         manual_position: Mutex<Option<(i32, i32)>>,
         locked_position: Mutex<Option<(i32, i32)>>,
         programmatic_move: AtomicBool,
@@ -128,7 +143,9 @@ mod imp {
                 app: Mutex::new(None),
                 state: Mutex::new(None),
                 config: Mutex::new(None),
+                layout: Mutex::new(None),
                 model: Mutex::new(NativeTaskbarModel::default()),
+                history: Mutex::new(MetricHistory::default()),
                 manual_position: Mutex::new(None),
                 locked_position: Mutex::new(None),
                 programmatic_move: AtomicBool::new(false),
@@ -163,6 +180,14 @@ mod imp {
         r as u32 | ((g as u32) << 8) | ((b as u32) << 16)
     }
 
+    fn colorref_rgb(color: COLORREF) -> Rgb {
+        [
+            (color & 0xFF) as u8,
+            ((color >> 8) & 0xFF) as u8,
+            ((color >> 16) & 0xFF) as u8,
+        ]
+    }
+
     fn config_colors(config: &NativeTaskbarConfig) -> (COLORREF, COLORREF, u8) {
         match config.background_mode.as_str() {
             "light" => (rgb(245, 247, 250), rgb(30, 35, 42), 245),
@@ -269,23 +294,6 @@ mod imp {
         }
     }
 
-    fn format_speed(bytes_per_sec: f64) -> String {
-        format!("{:.1} MB/s", bytes_per_sec / 1024.0 / 1024.0)
-    }
-
-    fn format_latency(latency_ms: Option<f64>, language: &str) -> String {
-        match latency_ms {
-            Some(value) if value.is_finite() => format!("{:.0}ms", value),
-            _ => {
-                if language == "zh-CN" {
-                    "暂无".to_string()
-                } else {
-                    "N/A".to_string()
-                }
-            }
-        }
-    }
-
     #[derive(Clone, Serialize)]
     #[serde(rename_all = "camelCase")]
     struct NativeTaskbarSyncSettings {
@@ -299,6 +307,7 @@ mod imp {
     #[derive(Clone, Serialize)]
     #[serde(rename_all = "camelCase")]
     struct NativeTaskbarSyncPrefs {
+        segments: Vec<TaskbarSegmentConfig>,
         show_cpu: bool,
         show_cpu_freq: bool,
         show_cpu_temp: bool,
@@ -342,6 +351,14 @@ mod imp {
         down: &'static str,
         up: &'static str,
         latency: &'static str,
+        gpu_memory: &'static str,
+        power: &'static str,
+        swap: &'static str,
+        disk_usage: &'static str,
+        disk_read: &'static str,
+        disk_write: &'static str,
+        custom: &'static str,
+        add_segment: &'static str,
         close_taskbar: &'static str,
         exit_app: &'static str,
     }
@@ -370,6 +387,14 @@ mod imp {
                 down: "下行",
                 up: "上行",
                 latency: "延迟",
+                gpu_memory: "显存",
+                power: "功耗",
+                swap: "交换空间",
+                disk_usage: "磁盘占用",
+                disk_read: "磁盘读取",
+                disk_write: "磁盘写入",
+                custom: "自定义",
+                add_segment: "添加项目",
                 close_taskbar: "关闭任务栏监控",
                 exit_app: "退出",
             }
@@ -396,28 +421,205 @@ mod imp {
                 down: "Down",
                 up: "Up",
                 latency: "Latency",
+                gpu_memory: "GPU Memory",
+                power: "Power",
+                swap: "Swap",
+                disk_usage: "Disk Usage",
+                disk_read: "Disk Read",
+                disk_write: "Disk Write",
+                custom: "Custom",
+                add_segment: "Add Segment",
                 close_taskbar: "Close Taskbar Monitor",
                 exit_app: "Exit",
             }
         }
     }
 
-    impl Default for SegmentTone {
-        fn default() -> Self {
-            Self::Normal
+    fn segment_menu_label(
+        text: &MenuText,
+        kind: SegmentKind,
+        segment: &TaskbarSegmentConfig,
+    ) -> String {
+        let name = match kind {
+            SegmentKind::Cpu => text.cpu,
+            SegmentKind::Gpu => text.gpu,
+            SegmentKind::Memory => text.memory,
+            SegmentKind::App => text.app,
+            SegmentKind::Down => text.down,
+            SegmentKind::Up => text.up,
+            SegmentKind::Latency => text.latency,
+            SegmentKind::GpuMemory => text.gpu_memory,
+            SegmentKind::Power => text.power,
+            SegmentKind::Swap => text.swap,
+            SegmentKind::DiskUsage => text.disk_usage,
+            SegmentKind::DiskRead => text.disk_read,
+            SegmentKind::DiskWrite => text.disk_write,
+            SegmentKind::Custom => text.custom,
+        };
+        let name = match segment.label.as_deref().map(str::trim) {
+            Some(label) if !label.is_empty() && kind == SegmentKind::Custom => label,
+            _ => name,
+        };
+        match segment.disk.as_deref() {
+            Some(disk) if kind.is_disk() => format!("{name} ({disk})"),
+            _ => name.to_string(),
         }
     }
 
-    fn usage_tone(value: f64, gpu: bool) -> SegmentTone {
-        if value >= 90.0 {
-            SegmentTone::Red
-        } else if value >= 75.0 {
-            SegmentTone::Orange
-        } else if gpu {
-            SegmentTone::Pink
-        } else {
-            SegmentTone::Cyan
+    fn append_segment_item(
+        menu: HMENU,
+        actions: &mut Vec<SegmentMenuAction>,
+        action: SegmentMenuAction,
+        checked: bool,
+        label: &str,
+    ) {
+        let id = MENU_SEGMENT_FIRST + actions.len();
+        actions.push(action);
+        let label = to_wide(label);
+        unsafe {
+            AppendMenuW(
+                menu,
+                MF_STRING | if checked { MF_CHECKED } else { MF_UNCHECKED },
+                id,
+                label.as_ptr(),
+            );
+        }
+    }
+
+    /// One entry per configured segment (plus CPU/GPU detail toggles), then an "add" submenu
+    /// with the kinds and disks the list does not have yet.
+    fn append_segment_items(
+        menu: HMENU,
+        text: &MenuText,
+        config: &NativeTaskbarConfig,
+        snapshot: Option<&TelemetrySnapshot>,
+    ) -> Vec<SegmentMenuAction> {
+        let mut actions = Vec::new();
+        for (index, segment) in config.segments.iter().enumerate() {
+            let Ok(kind) = SegmentKind::parse(&segment.kind) else {
+                continue;
+            };
+            append_segment_item(
+                menu,
+                &mut actions,
+                SegmentMenuAction::Toggle(index),
+                segment.enabled,
+                &segment_menu_label(text, kind, segment),
+            );
+            match kind {
+                SegmentKind::Cpu => {
+                    append_segment_item(
+                        menu,
+                        &mut actions,
+                        SegmentMenuAction::ToggleFreq(index),
+                        segment.show_freq,
+                        text.cpu_freq,
+                    );
+                    append_segment_item(
+                        menu,
+                        &mut actions,
+                        SegmentMenuAction::ToggleTemp(index),
+                        segment.show_temp,
+                        text.cpu_temp,
+                    );
+                }
+                SegmentKind::Gpu => append_segment_item(
+                    menu,
+                    &mut actions,
+                    SegmentMenuAction::ToggleTemp(index),
+                    segment.show_temp,
+                    text.gpu_temp,
+                ),
+                _ => {}
+            }
+        }
+
+        let has = |kind: SegmentKind, disk: Option<&str>| {
+            config.segments.iter().any(|segment| {
+                SegmentKind::parse(&segment.kind).ok() == Some(kind)
+                    && segment.disk.as_deref() == disk
+            })
+        };
+        let mut candidates: Vec<TaskbarSegmentConfig> = SegmentKind::ALL
+            .into_iter()
+            .filter(|kind| !kind.is_disk() && *kind != SegmentKind::Custom && !has(*kind, None))
+            .map(|kind| TaskbarSegmentConfig::new(kind.as_str()))
+            .collect();
+        for disk in snapshot
+            .map(|snapshot| snapshot.disks.as_slice())
+            .unwrap_or_default()
+        {
+            let id = disk.name.trim_end_matches(['\\', '/']);
+            let id = if id.is_empty() {
+                disk.name.as_str()
+            } else {
+                id
+            };
+            for kind in [
+                SegmentKind::DiskUsage,
+                SegmentKind::DiskRead,
+                SegmentKind::DiskWrite,
+            ] {
+                if !has(kind, Some(id)) {
+                    candidates.push(TaskbarSegmentConfig {
+                        disk: Some(id.to_string()),
+                        ..TaskbarSegmentConfig::new(kind.as_str())
+                    });
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return actions;
+        }
+        let submenu = unsafe { CreatePopupMenu() };
+        if submenu.is_null() {
+            return actions;
+        }
+        for candidate in candidates {
+            let Ok(kind) = SegmentKind::parse(&candidate.kind) else {
+                continue;
+            };
+            let label = segment_menu_label(text, kind, &candidate);
+            append_segment_item(
+                submenu,
+                &mut actions,
+                SegmentMenuAction::Add(candidate),
+                false,
+                &label,
+            );
+        }
+        let add_text = to_wide(text.add_segment);
+        unsafe {
+            AppendMenuW(
+                menu,
+                MF_STRING | MF_POPUP,
+                submenu as usize,
+                add_text.as_ptr(),
+            );
+        }
+        actions
+    }
+
+    fn apply_segment_action(config: &mut NativeTaskbarConfig, action: SegmentMenuAction) {
+        match action {
+            SegmentMenuAction::Toggle(index) => {
+                if let Some(segment) = config.segments.get_mut(index) {
+                    segment.enabled = !segment.enabled;
+                }
+            }
+            SegmentMenuAction::ToggleFreq(index) => {
+                if let Some(segment) = config.segments.get_mut(index) {
+                    segment.show_freq = !segment.show_freq;
+                }
+            }
+            SegmentMenuAction::ToggleTemp(index) => {
+                if let Some(segment) = config.segments.get_mut(index) {
+                    segment.show_temp = !segment.show_temp;
+                }
+            }
+            SegmentMenuAction::Add(segment) => config.segments.push(segment),
         }
+        sync_legacy_toggles(config);
     }
 
     fn get_window_rect(hwnd: HWND) -> Option<RECT> {
@@ -469,6 +671,7 @@ mod imp {
         NativeTaskbarSyncPayload {
             settings,
             prefs: NativeTaskbarSyncPrefs {
+                segments: config.segments.clone(),
                 show_cpu: config.show_cpu,
                 show_cpu_freq: config.show_cpu_freq,
                 show_cpu_temp: config.show_cpu_temp,
@@ -532,6 +735,11 @@ mod imp {
         let Some(config) = next_config else {
             return;
         };
+        if let Some(app) = shared.app.lock().ok().and_then(|guard| guard.clone()) {
+            if let Err(err) = write_segments(&app, &config.segments) {
+                tracing::warn!("failed to save native taskbar segments: {err}");
+            }
+        }
         emit_native_taskbar_sync(shared, &config, true);
         push_command(NativeTaskbarCommand::ApplyConfig(config));
         refresh_from_latest_snapshot(shared);
@@ -541,112 +749,15 @@ mod imp {
         let Some(state) = shared.state.lock().ok().and_then(|guard| guard.clone()) else {
             return;
         };
-        tauri::async_runtime::block_on(async {
-            let mut settings = state.settings.write().await;
-            updater(&mut settings);
-        });
-    }
-
-    fn build_segments(
-        snapshot: &TelemetrySnapshot,
-        config: &NativeTaskbarConfig,
-    ) -> Vec<NativeTaskbarSegment> {
-        let mut parts = Vec::new();
-        if config.show_cpu {
-            let mut extras = Vec::new();
-            if config.show_cpu_freq {
-                if let Some(freq) = snapshot.cpu.frequency_mhz {
-                    extras.push(format!("{}MHz", freq));
-                }
-            }
-            if config.show_cpu_temp {
-                if let Some(temp) = snapshot.cpu.temperature_c {
-                    extras.push(format!("{:.0}°C", temp));
-                }
-            }
-            parts.push(NativeTaskbarSegment {
-                label: "CPU".to_string(),
-                value: format!("{:.0}%", snapshot.cpu.usage_pct),
-                extra: (!extras.is_empty()).then(|| extras.join(" ")),
-                value_tone: usage_tone(snapshot.cpu.usage_pct, false),
-            });
-        }
-        if config.show_gpu {
-            let usage = snapshot.gpu.usage_pct.unwrap_or(0.0);
-            let mut extras = Vec::new();
-            if config.show_gpu_temp {
-                if let Some(temp) = snapshot.gpu.temperature_c {
-                    extras.push(format!("{:.0}°C", temp));
-                }
-            }
-            parts.push(NativeTaskbarSegment {
-                label: "GPU".to_string(),
-                value: format!("{:.0}%", usage),
-                extra: (!extras.is_empty()).then(|| extras.join(" ")),
-                value_tone: usage_tone(usage, true),
-            });
-        }
-        if config.show_memory {
-            parts.push(NativeTaskbarSegment {
-                label: "RAM".to_string(),
-                value: format!("{:.0}%", snapshot.memory.usage_pct),
-                extra: None,
-                value_tone: usage_tone(snapshot.memory.usage_pct, false),
-            });
-        }
-        if config.show_app {
-            parts.push(NativeTaskbarSegment {
-                label: "APP".to_string(),
-                value: format!("{:.1}%", snapshot.app_cpu_usage_pct.unwrap_or(0.0)),
-                extra: snapshot.app_memory_mb.map(|mem| format!("{:.0}MB", mem)),
-                value_tone: SegmentTone::Cyan,
-            });
-        }
-        if config.show_down {
-            parts.push(NativeTaskbarSegment {
-                label: "↓".to_string(),
-                value: format_speed(snapshot.network.download_bytes_per_sec),
-                extra: None,
-                value_tone: SegmentTone::Normal,
-            });
-        }
-        if config.show_up {
-            parts.push(NativeTaskbarSegment {
-                label: "↑".to_string(),
-                value: format_speed(snapshot.network.upload_bytes_per_sec),
-                extra: None,
-                value_tone: SegmentTone::Normal,
-            });
-        }
-        if config.show_latency {
-            parts.push(NativeTaskbarSegment {
-                label: if config.language == "zh-CN" {
-                    "延迟"
-                } else {
-                    "LAT"
-                }
-                .to_string(),
-                value: format_latency(snapshot.network.latency_ms, &config.language),
-                extra: None,
-                value_tone: SegmentTone::Normal,
-            });
-        }
-        if parts.is_empty() {
-            parts.push(NativeTaskbarSegment {
-                label: "PulseCoreLite".to_string(),
-                value: String::new(),
-                extra: None,
-                value_tone: SegmentTone::Muted,
-            });
-        }
-        parts
+        tauri::async_runtime::block_on(state.update_settings(updater));
     }
 
     fn build_model(
         snapshot: &TelemetrySnapshot,
         config: &NativeTaskbarConfig,
+        layout: &TaskbarLayout,
     ) -> NativeTaskbarModel {
-        let parts = build_segments(snapshot, config);
+        let parts = layout.build_segments(snapshot);
         if config.two_line_mode {
             let split = ((parts.len() as f64) / 2.0).ceil() as usize;
             let (top, bottom) = parts.split_at(split.max(1).min(parts.len()));
@@ -659,11 +770,13 @@ mod imp {
     }
 
     fn estimate_model_width(model: &NativeTaskbarModel, config: &NativeTaskbarConfig) -> i32 {
-        let chars = model
+        let per_char = if config.two_line_mode { 8 } else { 9 };
+        let width = model
             .rows
             .iter()
             .map(|row| {
-                row.iter()
+                let chars = row
+                    .iter()
                     .map(|segment| {
                         segment.label.len()
                             + 1
@@ -675,12 +788,17 @@ mod imp {
                                 .unwrap_or(0)
                     })
                     .sum::<usize>()
-                    + row.len().saturating_sub(1) * 3
+                    + row.len().saturating_sub(1) * 3;
+                let sparklines: i32 = row
+                    .iter()
+                    .filter_map(|segment| segment.sparkline.as_ref())
+                    .map(|spec| spec.width as i32 + SPARKLINE_GAP)
+                    .sum();
+                chars as i32 * per_char + sparklines
             })
             .max()
-            .unwrap_or(28) as i32;
-        let per_char = if config.two_line_mode { 8 } else { 9 };
-        (chars * per_char + 28).clamp(240, 1080)
+            .unwrap_or(28 * per_char);
+        (width + 28).clamp(240, 1080)
     }
 
     fn tone_color(config: &NativeTaskbarConfig, tone: SegmentTone) -> COLORREF {
@@ -693,6 +811,7 @@ mod imp {
             SegmentTone::Pink => rgb(188, 19, 254),
             SegmentTone::Orange => rgb(255, 165, 0),
             SegmentTone::Red => rgb(255, 59, 59),
+            SegmentTone::Custom([r, g, b]) => rgb(r, g, b),
             SegmentTone::Normal => match config.background_mode.as_str() {
                 "light" => rgb(24, 29, 38),
                 _ => rgb(240, 243, 246),
@@ -737,11 +856,45 @@ mod imp {
         *x += measure_text(hdc, text);
     }
 
+    /// Copies a rasterized sparkline onto the DC as a top-down 32-bit DIB.
+    fn draw_sparkline(hdc: *mut core::ffi::c_void, x: &mut i32, y: i32, image: &RgbaImage) {
+        let (width, height) = image.dimensions();
+        let bgra: Vec<u8> = image
+            .pixels()
+            .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], 0])
+            .collect();
+        let mut info: BITMAPINFO = unsafe { std::mem::zeroed() };
+        info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+        info.bmiHeader.biWidth = width as i32;
+        info.bmiHeader.biHeight = -(height as i32);
+        info.bmiHeader.biPlanes = 1;
+        info.bmiHeader.biBitCount = 32;
+        info.bmiHeader.biCompression = BI_RGB;
+        unsafe {
+            SetDIBitsToDevice(
+                hdc,
+                *x,
+                y,
+                width,
+                height,
+                0,
+                0,
+                0,
+                height,
+                bgra.as_ptr().cast(),
+                &info as *const BITMAPINFO,
+                DIB_RGB_COLORS,
+            );
+        }
+        *x += width as i32;
+    }
+
     fn draw_row(
         hdc: *mut core::ffi::c_void,
         config: &NativeTaskbarConfig,
-        row: &[NativeTaskbarSegment],
+        row: &[TaskbarSegment],
         top: i32,
+        history: &MetricHistory,
     ) {
         let label_font = unsafe { GetStockObject(DEFAULT_GUI_FONT) };
         let value_font = unsafe { GetStockObject(ANSI_FIXED_FONT) };
@@ -773,6 +926,20 @@ mod imp {
                 draw_text_piece(hdc, &mut x, top, " ", extra_color, label_font);
                 draw_text_piece(hdc, &mut x, top, extra, extra_color, value_font);
             }
+            if let Some(spec) = &segment.sparkline {
+                let (background, _, _) = config_colors(config);
+                let image = rasterize(
+                    &history.values(&spec.metric, spec.seconds),
+                    spec.style,
+                    spec.width,
+                    SPARKLINE_HEIGHT,
+                    spec.max,
+                    colorref_rgb(tone_color(config, segment.value_tone)),
+                    colorref_rgb(background),
+                );
+                x += SPARKLINE_GAP;
+                draw_sparkline(hdc, &mut x, top + 2, &image);
+            }
         }
     }
 
@@ -928,6 +1095,9 @@ mod imp {
             };
             match command {
                 Some(NativeTaskbarCommand::ApplyConfig(config)) => {
+                    if let Ok(mut slot) = shared.layout.lock() {
+                        *slot = Some(Arc::new(TaskbarLayout::new(&config)));
+                    }
                     if let Ok(mut slot) = shared.config.lock() {
                         *slot = Some(config.clone());
                     }
@@ -944,8 +1114,12 @@ mod imp {
                 }
                 Some(NativeTaskbarCommand::UpdateSnapshot(snapshot)) => {
                     let config = shared.config.lock().ok().and_then(|guard| guard.clone());
-                    if let Some(config) = config {
-                        let model = build_model(&snapshot, &config);
+                    let layout = shared.layout.lock().ok().and_then(|guard| guard.clone());
+                    if let (Some(config), Some(layout)) = (config, layout) {
+                        if let Ok(mut history) = shared.history.lock() {
+                            history.record(&snapshot, &layout.sparkline_specs());
+                        }
+                        let model = build_model(&snapshot, &config, &layout);
                         if let Ok(mut slot) = shared.model.lock() {
                             *slot = model.clone();
                         }
@@ -1005,16 +1179,6 @@ mod imp {
         let theme_transparent_text = to_wide(text.theme_transparent);
         let theme_dark_text = to_wide(text.theme_dark);
         let theme_light_text = to_wide(text.theme_light);
-        let cpu_text = to_wide(text.cpu);
-        let cpu_freq_text = to_wide(text.cpu_freq);
-        let cpu_temp_text = to_wide(text.cpu_temp);
-        let gpu_text = to_wide(text.gpu);
-        let gpu_temp_text = to_wide(text.gpu_temp);
-        let memory_text = to_wide(text.memory);
-        let app_text = to_wide(text.app);
-        let down_text = to_wide(text.down);
-        let up_text = to_wide(text.up);
-        let latency_text = to_wide(text.latency);
         let close_taskbar_text = to_wide(text.close_taskbar);
         let exit_text_w = to_wide(text.exit_app);
         unsafe {
@@ -1111,117 +1275,17 @@ mod imp {
                 theme_light_text.as_ptr(),
             );
             AppendMenuW(menu, MF_SEPARATOR, 0, ptr::null());
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_cpu {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_CPU,
-                cpu_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_cpu_freq {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_CPU_FREQ,
-                cpu_freq_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_cpu_temp {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_CPU_TEMP,
-                cpu_temp_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_gpu {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_GPU,
-                gpu_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_gpu_temp {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_GPU_TEMP,
-                gpu_temp_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_memory {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_MEMORY,
-                memory_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_app {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_APP,
-                app_text.as_ptr(),
-            );
-            AppendMenuW(menu, MF_SEPARATOR, 0, ptr::null());
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_down {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_DOWN,
-                down_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_up {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_UP,
-                up_text.as_ptr(),
-            );
-            AppendMenuW(
-                menu,
-                MF_STRING
-                    | if config.show_latency {
-                        MF_CHECKED
-                    } else {
-                        MF_UNCHECKED
-                    },
-                MENU_SHOW_LATENCY,
-                latency_text.as_ptr(),
-            );
+        }
+        let snapshot = shared
+            .state
+            .lock()
+            .ok()
+            .and_then(|guard| guard.clone())
+            .map(|state| {
+                tauri::async_runtime::block_on(async { state.latest_snapshot.read().await.clone() })
+            });
+        let segment_actions = append_segment_items(menu, &text, &config, snapshot.as_ref());
+        unsafe {
             AppendMenuW(menu, MF_SEPARATOR, 0, ptr::null());
             AppendMenuW(
                 menu,
@@ -1309,36 +1373,6 @@ mod imp {
             MENU_THEME_LIGHT => apply_runtime_config_change(&shared, |config| {
                 config.background_mode = "light".to_string();
             }),
-            MENU_SHOW_CPU => apply_runtime_config_change(&shared, |config| {
-                config.show_cpu = !config.show_cpu;
-            }),
-            MENU_SHOW_CPU_FREQ => apply_runtime_config_change(&shared, |config| {
-                config.show_cpu_freq = !config.show_cpu_freq;
-            }),
-            MENU_SHOW_CPU_TEMP => apply_runtime_config_change(&shared, |config| {
-                config.show_cpu_temp = !config.show_cpu_temp;
-            }),
-            MENU_SHOW_GPU => apply_runtime_config_change(&shared, |config| {
-                config.show_gpu = !config.show_gpu;
-            }),
-            MENU_SHOW_GPU_TEMP => apply_runtime_config_change(&shared, |config| {
-                config.show_gpu_temp = !config.show_gpu_temp;
-            }),
-            MENU_SHOW_MEMORY => apply_runtime_config_change(&shared, |config| {
-                config.show_memory = !config.show_memory;
-            }),
-            MENU_SHOW_APP => apply_runtime_config_change(&shared, |config| {
-                config.show_app = !config.show_app;
-            }),
-            MENU_SHOW_DOWN => apply_runtime_config_change(&shared, |config| {
-                config.show_down = !config.show_down;
-            }),
-            MENU_SHOW_UP => apply_runtime_config_change(&shared, |config| {
-                config.show_up = !config.show_up;
-            }),
-            MENU_SHOW_LATENCY => apply_runtime_config_change(&shared, |config| {
-                config.show_latency = !config.show_latency;
-            }),
             MENU_CLOSE_TASKBAR => {
                 update_settings(&shared, |settings| {
                     settings.native_taskbar_monitor_enabled = false;
@@ -1347,6 +1381,13 @@ mod imp {
                 push_command(NativeTaskbarCommand::Close);
             }
             MENU_EXIT_APP => app.exit(0),
+            id if id >= MENU_SEGMENT_FIRST => {
+                if let Some(action) = segment_actions.into_iter().nth(id - MENU_SEGMENT_FIRST) {
+                    apply_runtime_config_change(&shared, |config| {
+                        apply_segment_action(config, action);
+                    });
+                }
+            }
             _ => {}
         }
 
@@ -1410,38 +1451,22 @@ mod imp {
                 let mut ps: PAINTSTRUCT = std::mem::zeroed();
                 let hdc = BeginPaint(hwnd, &mut ps as *mut PAINTSTRUCT);
                 if !hdc.is_null() {
-                    let fallback = NativeTaskbarConfig {
-                        language: "en-US".to_string(),
-                        always_on_top: true,
-                        auto_hide_on_fullscreen: false,
-                        remember_position: true,
-                        position_locked: false,
-                        show_cpu: true,
-                        show_cpu_freq: true,
-                        show_cpu_temp: true,
-                        show_gpu: true,
-                        show_gpu_temp: true,
-                        show_memory: true,
-                        show_app: true,
-                        show_down: true,
-                        show_up: true,
-                        show_latency: false,
-                        two_line_mode: false,
-                        background_mode: "dark".to_string(),
-                    };
-                    let config = config.unwrap_or(fallback);
+                    let config = config.unwrap_or_default();
                     let (bg, _fg, _) = config_colors(&config);
                     let brush = CreateSolidBrush(bg);
                     let mut rect: RECT = std::mem::zeroed();
                     GetClientRect(hwnd, &mut rect as *mut RECT);
                     FillRect(hdc, &rect as *const RECT, brush);
                     SetBkMode(hdc, TRANSPARENT as i32);
+                    let history = shared.history.lock().ok();
+                    let empty_history = MetricHistory::default();
+                    let history = history.as_deref().unwrap_or(&empty_history);
                     let row1 = model.rows.first().cloned().unwrap_or_default();
-                    draw_row(hdc, &config, &row1, 6);
+                    draw_row(hdc, &config, &row1, 6, history);
 
                     if config.two_line_mode || model.rows.len() > 1 {
                         let row2 = model.rows.get(1).cloned().unwrap_or_default();
-                        draw_row(hdc, &config, &row2, 24);
+                        draw_row(hdc, &config, &row2, 24, history);
                     }
 
                     DeleteObject(brush);
@@ -1538,7 +1563,9 @@ mod imp {
         enabled: bool,
         config: NativeTaskbarConfig,
     ) -> Result<(), String> {
-        state.settings.write().await.native_taskbar_monitor_enabled = enabled;
+        state
+            .update_settings(|settings| settings.native_taskbar_monitor_enabled = enabled)
+            .await;
         let shared = shared();
         if let Ok(mut slot) = shared.app.lock() {
             *slot = Some(app);
@@ -1571,12 +1598,41 @@ mod imp {
         }
         push_command(NativeTaskbarCommand::UpdateSnapshot(snapshot));
     }
+
+    pub fn set_thresholds(thresholds: Vec<MetricThreshold>, snapshot: TelemetrySnapshot) {
+        let shared = shared();
+        if !shared.running.load(Ordering::Relaxed) {
+            return;
+        }
+        let next_config = with_config_mut(&shared, |config| {
+            config.thresholds = thresholds;
+            config.clone()
+        });
+        if let Some(config) = next_config {
+            push_command(NativeTaskbarCommand::ApplyConfig(config));
+            push_command(NativeTaskbarCommand::UpdateSnapshot(snapshot));
+        }
+    }
+
+    pub fn set_units(units: UnitPreferences, snapshot: TelemetrySnapshot) {
+        let shared = shared();
+        if !shared.running.load(Ordering::Relaxed) {
+            return;
+        }
+        let next_config = with_config_mut(&shared, |config| {
+            config.units = units;
+            config.clone()
+        });
+        if let Some(config) = next_config {
+            push_command(NativeTaskbarCommand::ApplyConfig(config));
+            push_command(NativeTaskbarCommand::UpdateSnapshot(snapshot));
+        }
+    }
 }
 
 #[cfg(not(windows))]
 mod imp {
     use super::*;
-    use tauri::AppHandle;
 
     pub async fn configure(
         _app: AppHandle,
@@ -1588,10 +1644,14 @@ mod imp {
     }
 
     pub fn refresh(_snapshot: TelemetrySnapshot) {}
+
+    pub fn set_thresholds(_thresholds: Vec<MetricThreshold>, _snapshot: TelemetrySnapshot) {}
+
+    pub fn set_units(_units: UnitPreferences, _snapshot: TelemetrySnapshot) {}
 }
 
 pub async fn configure(
-    app: tauri::AppHandle,
+    app: AppHandle,
     state: SharedState,
     enabled: bool,
     config: NativeTaskbarConfig,
@@ -1602,3 +1662,13 @@ pub async fn configure(
 pub fn refresh(snapshot: TelemetrySnapshot) {
     imp::refresh(snapshot)
 }
+
+/// Recolors a running native taskbar after the shared threshold bands change.
+pub fn set_thresholds(thresholds: Vec<MetricThreshold>, snapshot: TelemetrySnapshot) {
+    imp::set_thresholds(thresholds, snapshot)
+}
+
+/// Re-renders a running native taskbar after the unit preferences change.
+pub fn set_units(units: UnitPreferences, snapshot: TelemetrySnapshot) {
+    imp::set_units(units, snapshot)
+}
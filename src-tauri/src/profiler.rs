@@ -1,298 +1,534 @@
 use std::{
-    collections::{HashMap, VecDeque},
-    path::PathBuf,
-    sync::{
-        atomic::{AtomicU64, Ordering},
-        Arc,
-    },
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex as StdMutex},
     time::{Duration, Instant},
 };
 
-use chrono::{DateTime, Utc};
-use serde::Serialize;
-use sysinfo::{Pid, ProcessesToUpdate, System};
+use chrono::Utc;
 use tauri::async_runtime::JoinHandle;
-use tauri::{AppHandle, Manager};
-use tokio::{
-    fs::OpenOptions,
-    io::{AsyncWriteExt, BufWriter},
-    sync::watch,
-};
+use tokio::sync::watch;
 
-use crate::state::SharedState;
+use crate::{
+    core::{
+        events::CoreEvent,
+        ports::CoreContext,
+        process_sampler::ProcessSampler,
+        process_scope::ProcessScope,
+        profile::ProfileSample,
+        profile_store::{same_capture, ProfileWriter},
+    },
+    state::SharedState,
+    types::{ProfileStatus, ProfileStorageOptions},
+};
 
-#[derive(Debug, Serialize, Clone)]
-pub struct ProfileStatus {
-    pub active: bool,
-    pub path: Option<String>,
-    #[serde(rename = "startedAt")]
-    pub started_at: Option<DateTime<Utc>>,
-    pub samples: u64,
-}
+/// Progress events are sent at most this often per capture.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+const WRITER_PANICKED: &str = "profile writer panicked";
 
-#[derive(Debug, Serialize)]
-struct ProcessSample {
-    pid: u32,
-    name: String,
-    #[serde(rename = "parentPid")]
-    parent_pid: Option<u32>,
-    #[serde(rename = "memoryMb")]
-    memory_mb: f64,
-    #[serde(rename = "cpuPct")]
-    cpu_pct: f64,
-    kind: String,
+fn read_status(status: &StdMutex<ProfileStatus>) -> ProfileStatus {
+    status
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
 }
 
-#[derive(Debug, Serialize)]
-struct WindowSample {
-    label: String,
-    visible: bool,
-}
-
-#[derive(Debug, Serialize)]
-struct ProfileSample {
-    timestamp: DateTime<Utc>,
-    #[serde(rename = "appPid")]
-    app_pid: u32,
-    #[serde(rename = "refreshRateMs")]
-    refresh_rate_ms: u64,
-    snapshot: crate::types::TelemetrySnapshot,
-    processes: Vec<ProcessSample>,
-    windows: Vec<WindowSample>,
+fn update_status(status: &StdMutex<ProfileStatus>, update: impl FnOnce(&mut ProfileStatus)) {
+    let mut guard = status
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    update(&mut guard);
 }
 
 pub struct ProfilerHandle {
+    id: u64,
     stop: watch::Sender<bool>,
-    started_at: DateTime<Utc>,
-    path: PathBuf,
-    samples: Arc<AtomicU64>,
+    status: Arc<StdMutex<ProfileStatus>>,
     task: JoinHandle<()>,
 }
 
 impl ProfilerHandle {
     pub fn status(&self) -> ProfileStatus {
-        ProfileStatus {
-            active: true,
-            path: Some(self.path.to_string_lossy().to_string()),
-            started_at: Some(self.started_at),
-            samples: self.samples.load(Ordering::Relaxed),
-        }
+        read_status(&self.status)
     }
 
-    pub async fn stop(self) {
+    /// Stops the capture and returns its final status once the last segment is closed.
+    pub async fn stop(mut self) -> ProfileStatus {
         let _ = self.stop.send(true);
-        let _ = self.task.await;
+        let _ = (&mut self.task).await;
+        self.status()
     }
 }
 
-pub fn resolve_profile_path(base_dir: &PathBuf, path: &str) -> PathBuf {
-    let candidate = PathBuf::from(path);
-    let resolved = if candidate.is_absolute() {
-        candidate
-    } else {
-        base_dir.join(candidate)
-    };
+/// Named profile captures: the ones running and the last result under each name.
+#[derive(Default)]
+pub struct ProfilerRuntime {
+    running: BTreeMap<String, ProfilerHandle>,
+    finished: BTreeMap<String, ProfileStatus>,
+    next_id: u64,
+}
+
+impl ProfilerRuntime {
+    pub fn is_active(&self) -> bool {
+        !self.running.is_empty()
+    }
+
+    /// Running captures first, then finished ones not shadowed by a running namesake.
+    pub fn statuses(&self) -> Vec<ProfileStatus> {
+        let mut statuses: Vec<ProfileStatus> =
+            self.running.values().map(ProfilerHandle::status).collect();
+        statuses.extend(
+            self.finished
+                .iter()
+                .filter(|(name, _)| !self.running.contains_key(*name))
+                .map(|(_, status)| status.clone()),
+        );
+        statuses
+    }
+
+    /// The named capture, or without a name the first running one; idle when there is none.
+    pub fn status(&self, name: Option<&str>) -> ProfileStatus {
+        let status = match name {
+            Some(name) => self
+                .running
+                .get(name)
+                .map(ProfilerHandle::status)
+                .or_else(|| self.finished.get(name).cloned()),
+            None => self.running.values().next().map(ProfilerHandle::status),
+        };
+        status.unwrap_or_default()
+    }
+
+    /// Starts a capture under `options.name`, which must not be running already.
+    pub fn start(
+        &mut self,
+        ctx: CoreContext,
+        state: SharedState,
+        options: CaptureOptions,
+    ) -> Result<ProfileStatus, String> {
+        if self.running.contains_key(&options.name) {
+            return Err(format!(
+                "Profile capture `{}` already running.",
+                options.name
+            ));
+        }
+        // The status holds the first segment's path, so compare capture names rather than paths.
+        if let Some(other) = self
+            .running
+            .values()
+            .map(ProfilerHandle::status)
+            .find(|status| {
+                status
+                    .path
+                    .as_deref()
+                    .is_some_and(|path| same_capture(Path::new(path), &options.path))
+            })
+        {
+            return Err(format!(
+                "Profile capture `{}` already writes to this path.",
+                other.name
+            ));
+        }
+        self.next_id += 1;
+        let name = options.name.clone();
+        let handle = spawn_capture(ctx, state, self.next_id, options)?;
+        let status = handle.status();
+        self.finished.remove(&name);
+        self.running.insert(name, handle);
+        Ok(status)
+    }
+
+    pub fn take(&mut self, name: &str) -> Option<ProfilerHandle> {
+        self.running.remove(name)
+    }
 
-    if resolved.extension().is_some() {
-        return resolved;
+    pub fn take_all(&mut self) -> Vec<ProfilerHandle> {
+        std::mem::take(&mut self.running).into_values().collect()
     }
 
-    let filename = format!("profile-{}.jsonl", Utc::now().format("%Y%m%d-%H%M%S"));
-    resolved.join(filename)
+    /// Called by a capture task as it ends; a newer capture under the same name stays.
+    fn finish(&mut self, id: u64, status: ProfileStatus) {
+        if self
+            .running
+            .get(&status.name)
+            .is_some_and(|handle| handle.id == id)
+        {
+            self.running.remove(&status.name);
+        }
+        if !self.running.contains_key(&status.name) {
+            self.finished.insert(status.name.clone(), status);
+        }
+    }
 }
 
-pub async fn start_profile_capture(
-    app: AppHandle,
+/// What a capture records and for how long.
+pub struct CaptureOptions {
+    pub name: String,
+    pub path: PathBuf,
+    pub interval_ms: u64,
+    pub duration_ms: Option<u64>,
+    pub storage: ProfileStorageOptions,
+    pub scope: ProcessScope,
+}
+
+fn spawn_capture(
+    ctx: CoreContext,
     state: SharedState,
-    path: PathBuf,
-    interval_ms: u64,
-    duration_ms: Option<u64>,
+    id: u64,
+    options: CaptureOptions,
 ) -> Result<ProfilerHandle, String> {
-    if let Some(parent) = path.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .map_err(|e| e.to_string())?;
-    }
-
-    let file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(&path)
-        .await
-        .map_err(|e| e.to_string())?;
+    let writer = ProfileWriter::create(&options.path, options.storage)?;
 
     let (stop_tx, stop_rx) = watch::channel(false);
-    let samples = Arc::new(AtomicU64::new(0));
-    let samples_task = Arc::clone(&samples);
-    let started_at = Utc::now();
-    let interval_ms = interval_ms.clamp(200, 10_000);
-    let duration = duration_ms.map(|value| Duration::from_millis(value.max(200)));
-    let path_clone = path.clone();
+    let interval_ms = options.interval_ms.clamp(200, 10_000);
+    let duration_ms = options.duration_ms.map(|value| value.max(200));
+    let status = Arc::new(StdMutex::new(ProfileStatus {
+        name: options.name,
+        active: true,
+        path: Some(writer.path().to_string_lossy().to_string()),
+        started_at: Some(Utc::now()),
+        interval_ms,
+        duration_ms,
+        segments: writer.segments_written() as u64,
+        bytes_written: writer.bytes_written(),
+        eta_ms: duration_ms,
+        ..ProfileStatus::default()
+    }));
+    let task_status = Arc::clone(&status);
+    let scope = options.scope;
 
     let task = tauri::async_runtime::spawn(async move {
-        let mut writer = BufWriter::new(file);
-        let mut system = System::new_all();
-        let app_pid = Pid::from_u32(std::process::id());
+        let status = task_status;
+        let sampler = ProfileSampler::new(scope);
         let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
         ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
         let start = Instant::now();
+        let mut last_progress: Option<Instant> = None;
+        let mut error = None;
+        let mut writer = Some(writer);
 
         loop {
             ticker.tick().await;
             if *stop_rx.borrow() {
                 break;
             }
-            if let Some(max_duration) = duration {
-                if start.elapsed() >= max_duration {
-                    break;
-                }
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            if duration_ms.is_some_and(|duration| elapsed_ms >= duration) {
+                break;
             }
 
-            system.refresh_processes(ProcessesToUpdate::All, true);
-            let processes = collect_process_samples(&system, app_pid);
-            let snapshot = state.latest_snapshot.read().await.clone();
-            let refresh_rate_ms = state
-                .refresh_rate_ms
-                .load(std::sync::atomic::Ordering::Relaxed)
-                .max(10);
-
-            let windows = collect_window_samples(&app);
-            let sample = ProfileSample {
-                timestamp: Utc::now(),
-                app_pid: app_pid.as_u32(),
-                refresh_rate_ms,
-                snapshot,
-                processes,
-                windows,
+            let sample = sampler.sample(&ctx, &state).await;
+            let Some(current) = writer.take() else {
+                break;
+            };
+            let Some((current, written)) =
+                on_blocking_pool(current, move |writer| writer.write_sample(&sample)).await
+            else {
+                error = Some(WRITER_PANICKED.to_string());
+                break;
             };
+            let current = writer.insert(current);
+            // A failed write (usually a full disk) ends the capture instead of dropping lines.
+            if let Err(err) = written {
+                error = Some(format!("profile write failed: {err}"));
+                break;
+            }
+            update_status(&status, |status| {
+                status.samples += 1;
+                status.segments = current.segments_written() as u64;
+                status.bytes_written = current.bytes_written();
+                status.eta_ms = duration_ms.map(|duration| duration.saturating_sub(elapsed_ms));
+            });
+            if last_progress.is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL) {
+                last_progress = Some(Instant::now());
+                ctx.events
+                    .publish(CoreEvent::ProfileProgress(read_status(&status)));
+            }
+        }
 
-            match serde_json::to_string(&sample) {
-                Ok(line) => {
-                    if writer.write_all(line.as_bytes()).await.is_ok() {
-                        let _ = writer.write_all(b"\n").await;
-                        let _ = writer.flush().await;
-                        samples_task.fetch_add(1, Ordering::Relaxed);
+        if let Some(current) = writer.take() {
+            match on_blocking_pool(current, ProfileWriter::close).await {
+                Some((current, closed)) => {
+                    if let Err(err) = closed {
+                        error.get_or_insert(format!("profile close failed: {err}"));
                     }
+                    writer = Some(current);
                 }
-                Err(err) => {
-                    tracing::warn!("profile capture serialize failed: {err}");
+                None => {
+                    error.get_or_insert(WRITER_PANICKED.to_string());
                 }
             }
         }
-
-        let _ = writer.flush().await;
-        tracing::info!(
-            "profile capture stopped: {} samples -> {}",
-            samples_task.load(Ordering::Relaxed),
-            path_clone.to_string_lossy()
-        );
+        update_status(&status, |status| {
+            status.active = false;
+            status.finished_at = Some(Utc::now());
+            if let Some(writer) = &writer {
+                status.segments = writer.segments_written() as u64;
+                status.bytes_written = writer.bytes_written();
+            }
+            status.eta_ms = None;
+            status.error = error;
+        });
+        let finished = read_status(&status);
+        match &finished.error {
+            Some(err) => tracing::warn!("profile capture {} failed: {err}", finished.name),
+            None => tracing::info!(
+                "profile capture {} stopped: {} samples -> {}",
+                finished.name,
+                finished.samples,
+                finished.path.as_deref().unwrap_or_default()
+            ),
+        }
+        ctx.events
+            .publish(CoreEvent::ProfileFinished(finished.clone()));
+        crate::digest::record_profile_run(
+            &state,
+            crate::digest::DigestProfileRun {
+                path: finished.path.clone().unwrap_or_default(),
+                started_at: finished.started_at.unwrap_or_else(Utc::now),
+                finished_at: finished.finished_at.unwrap_or_else(Utc::now),
+                samples: finished.samples,
+            },
+        )
+        .await;
+        state.profiler.lock().await.finish(id, finished);
     });
 
     Ok(ProfilerHandle {
+        id,
         stop: stop_tx,
-        started_at,
-        path,
-        samples,
+        status,
         task,
     })
 }
 
-fn collect_window_samples(app: &AppHandle) -> Vec<WindowSample> {
-    let mut samples = Vec::with_capacity(3);
-    for label in ["main", "taskbar", "toolkit"] {
-        if let Some(win) = app.get_webview_window(label) {
-            let visible = win.is_visible().unwrap_or(false);
-            samples.push(WindowSample {
-                label: label.to_string(),
-                visible,
-            });
-        }
-    }
-    samples
+/// Runs `op` on the blocking pool and hands the writer back, since gzip and file writes
+/// would stall the async runtime; `None` if `op` panicked.
+pub(crate) async fn on_blocking_pool<T: Send + 'static>(
+    mut writer: ProfileWriter,
+    op: impl FnOnce(&mut ProfileWriter) -> T + Send + 'static,
+) -> Option<(ProfileWriter, T)> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = op(&mut writer);
+        (writer, result)
+    })
+    .await
+    .ok()
+}
+
+/// Builds profile samples from the latest snapshot plus a process and window scan.
+pub struct ProfileSampler {
+    app_pid: u32,
+    /// Scanned on the blocking pool: sysinfo refreshes every process, which takes a while on
+    /// large process tables.
+    processes: Arc<StdMutex<ProcessSampler>>,
 }
 
-fn collect_process_samples(system: &System, root_pid: Pid) -> Vec<ProcessSample> {
-    let mut children_map: HashMap<Pid, Vec<Pid>> = HashMap::new();
-    let mut parent_map: HashMap<Pid, Pid> = HashMap::new();
-    for (pid, process) in system.processes() {
-        if let Some(parent) = process.parent() {
-            children_map.entry(parent).or_default().push(*pid);
-            parent_map.insert(*pid, parent);
+impl ProfileSampler {
+    pub fn new(scope: ProcessScope) -> Self {
+        let processes = ProcessSampler::new(std::process::id(), scope);
+        Self {
+            app_pid: processes.root_pid(),
+            processes: Arc::new(StdMutex::new(processes)),
         }
     }
 
-    let mut queue = VecDeque::new();
-    let mut seen = HashMap::new();
-    queue.push_back(root_pid);
-    while let Some(pid) = queue.pop_front() {
-        if seen.insert(pid, ()).is_some() {
-            continue;
+    pub async fn sample(&self, ctx: &CoreContext, state: &SharedState) -> ProfileSample {
+        let sampler = Arc::clone(&self.processes);
+        let processes = tauri::async_runtime::spawn_blocking(move || {
+            sampler
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .sample()
+        })
+        .await
+        .unwrap_or_default();
+        let snapshot = state.latest_snapshot.read().await.clone();
+        let refresh_rate_ms = state
+            .refresh_rate_ms
+            .load(std::sync::atomic::Ordering::Relaxed)
+            .max(10);
+
+        ProfileSample {
+            timestamp: Utc::now(),
+            app_pid: self.app_pid,
+            refresh_rate_ms,
+            snapshot,
+            processes,
+            windows: ctx.windows.windows(),
         }
-        if let Some(children) = children_map.get(&pid) {
-            for child in children {
-                queue.push_back(*child);
-            }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{
+            events::EventBus,
+            ports::{DirPaths, NoWindows, RecordingNotifier, SystemClock},
+        },
+        state::AppState,
+        types::AppSettings,
+    };
+
+    fn context(dir: &std::path::Path) -> CoreContext {
+        CoreContext {
+            clock: Arc::new(SystemClock),
+            paths: Arc::new(DirPaths(dir.to_path_buf())),
+            notifier: Arc::new(RecordingNotifier::default()),
+            events: Arc::new(EventBus::default()),
+            windows: Arc::new(NoWindows),
         }
     }
 
-    let mut samples = Vec::new();
-    for pid in seen.keys() {
-        if let Some(process) = system.process(*pid) {
-            let name = process.name().to_string_lossy().to_string();
-            // sysinfo on Windows reports process memory in bytes; normalize to MB.
-            let memory_mb = process.memory() as f64 / 1024.0 / 1024.0;
-            let cpu_pct = process.cpu_usage() as f64;
-            let parent_pid = parent_map.get(pid).map(|p| p.as_u32());
-            samples.push(ProcessSample {
-                pid: pid.as_u32(),
-                name: name.clone(),
-                parent_pid,
-                memory_mb,
-                cpu_pct,
-                kind: classify_process(&name),
-            });
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn options(name: &str, path: PathBuf) -> CaptureOptions {
+        CaptureOptions {
+            name: name.to_string(),
+            path,
+            interval_ms: 200,
+            duration_ms: None,
+            storage: ProfileStorageOptions::default(),
+            scope: ProcessScope::default(),
         }
     }
-    samples.sort_by(|a, b| {
-        b.memory_mb
-            .partial_cmp(&a.memory_mb)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    samples
-}
 
-fn classify_process(name: &str) -> String {
-    let lower = name.to_lowercase();
-    if lower.contains("msedgewebview2") {
-        "webview".to_string()
-    } else if lower.contains("pulsecore") {
-        "app".to_string()
-    } else {
-        "child".to_string()
+    fn names(statuses: &[ProfileStatus]) -> Vec<(&str, bool)> {
+        statuses
+            .iter()
+            .map(|status| (status.name.as_str(), status.active))
+            .collect()
     }
-}
 
-pub fn ensure_profile_path(app: &AppHandle, path: &str) -> PathBuf {
-    // Resolve relative paths into app data to avoid dev hot-reload watching project folders.
-    let base_dir = app
-        .path()
-        .app_data_dir()
-        .or_else(|_| std::env::current_dir())
-        .unwrap_or_else(|_| PathBuf::from("."));
+    /// Stops outside the runtime lock, which the capture task takes to report back.
+    async fn stop(state: &SharedState, name: &str) -> ProfileStatus {
+        let handle = state.profiler.lock().await.take(name).unwrap();
+        handle.stop().await
+    }
+
+    #[tokio::test]
+    async fn start_rejects_running_names_and_shared_paths() {
+        let dir = temp_dir("pulsecore-profiler-start");
+        let ctx = context(&dir);
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        let first = dir.join("first.jsonl");
+        let second = dir.join("second.jsonl");
+        let mut runtime = state.profiler.lock().await;
+
+        runtime
+            .start(ctx.clone(), state.clone(), options("a", first.clone()))
+            .unwrap();
+        let same_name = runtime
+            .start(ctx.clone(), state.clone(), options("a", second.clone()))
+            .unwrap_err();
+        assert!(same_name.contains("`a` already running"), "{same_name}");
+        // `first.jsonl.gz` writes the same `first.NNN.jsonl.gz` segments as `first.jsonl`.
+        let same_path = runtime
+            .start(
+                ctx.clone(),
+                state.clone(),
+                options("b", dir.join("first.jsonl.gz")),
+            )
+            .unwrap_err();
+        assert!(same_path.contains("`a` already writes"), "{same_path}");
+        runtime
+            .start(ctx.clone(), state.clone(), options("b", second))
+            .unwrap();
+
+        assert_eq!(names(&runtime.statuses()), vec![("a", true), ("b", true)]);
+        assert_eq!(runtime.status(None).name, "a");
+        drop(runtime);
 
-    if path.trim().is_empty() {
-        return resolve_profile_path(&base_dir, "profile-data");
+        let handles = state.profiler.lock().await.take_all();
+        for handle in handles {
+            handle.stop().await;
+        }
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    resolve_profile_path(&base_dir, path)
-}
+    #[tokio::test]
+    async fn finish_keeps_a_newer_capture_under_the_same_name() {
+        let dir = temp_dir("pulsecore-profiler-finish");
+        let ctx = context(&dir);
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        let start = |name: &str, file: &str| {
+            let options = options(name, dir.join(file));
+            let (ctx, state) = (ctx.clone(), state.clone());
+            async move {
+                let status = state
+                    .profiler
+                    .lock()
+                    .await
+                    .start(ctx, state.clone(), options);
+                status.unwrap()
+            }
+        };
+
+        start("a", "old.jsonl").await;
+        let old = state.profiler.lock().await.take("a").unwrap();
+        start("a", "new.jsonl").await;
+        // The replaced capture reports back under its old id and must not evict the new one.
+        let stopped = old.stop().await;
+        assert!(!stopped.active);
+        {
+            let runtime = state.profiler.lock().await;
+            assert!(runtime.is_active());
+            assert_eq!(names(&runtime.statuses()), vec![("a", true)]);
+            assert!(runtime
+                .status(Some("a"))
+                .path
+                .is_some_and(|path| path.ends_with("new.001.jsonl.gz")));
+        }
+
+        let stopped = stop(&state, "a").await;
+        assert!(!stopped.active && stopped.error.is_none());
+        let runtime = state.profiler.lock().await;
+        assert!(!runtime.is_active());
+        assert_eq!(names(&runtime.statuses()), vec![("a", false)]);
+        assert!(runtime
+            .status(Some("a"))
+            .path
+            .is_some_and(|path| path.ends_with("new.001.jsonl.gz")));
+        assert_eq!(runtime.status(None).name, "");
+        drop(runtime);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
-pub fn profile_output_dir(app: &AppHandle) -> PathBuf {
-    let base_dir = app
-        .path()
-        .app_data_dir()
-        .or_else(|_| std::env::current_dir())
-        .unwrap_or_else(|_| PathBuf::from("."));
-    base_dir.join("profile-data")
+    #[tokio::test]
+    async fn finished_captures_list_after_running_ones() {
+        let dir = temp_dir("pulsecore-profiler-list");
+        let ctx = context(&dir);
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        for name in ["a", "b", "c"] {
+            let options = options(name, dir.join(format!("{name}.jsonl")));
+            state
+                .profiler
+                .lock()
+                .await
+                .start(ctx.clone(), state.clone(), options)
+                .unwrap();
+        }
+
+        stop(&state, "a").await;
+        stop(&state, "c").await;
+        let runtime = state.profiler.lock().await;
+        assert_eq!(
+            names(&runtime.statuses()),
+            vec![("b", true), ("a", false), ("c", false)]
+        );
+        assert_eq!(runtime.status(None).name, "b");
+        assert!(!runtime.status(Some("c")).active);
+        assert_eq!(runtime.status(Some("missing")).name, "");
+        drop(runtime);
+
+        stop(&state, "b").await;
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
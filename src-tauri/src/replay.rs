@@ -0,0 +1,314 @@
+use std::{
+    sync::{Arc, Mutex as StdMutex},
+    time::{Duration, Instant},
+};
+
+use tauri::{async_runtime::JoinHandle, AppHandle, State};
+use tokio::sync::mpsc;
+
+use crate::{
+    app::{publish_snapshot, SnapshotSource},
+    core::{
+        events::CoreEvent,
+        ports::CoreContext,
+        profile::resolve_existing_profile,
+        replay::{ReplayTimeline, MAX_REPLAY_SPEED, MIN_REPLAY_SPEED},
+    },
+    state::SharedState,
+    tauri_adapter::core_context,
+    types::ReplayStatus,
+};
+
+type CmdResult<T> = Result<T, String>;
+
+enum ReplayControl {
+    Pause,
+    Resume,
+    Seek(u64),
+    Speed(f64),
+    Stop,
+}
+
+struct ReplayHandle {
+    control: mpsc::UnboundedSender<ReplayControl>,
+    status: Arc<StdMutex<ReplayStatus>>,
+    task: JoinHandle<()>,
+}
+
+/// The profile replay currently feeding the publishing path, if any.
+#[derive(Default)]
+pub struct ReplayRuntime {
+    handle: Option<ReplayHandle>,
+}
+
+impl ReplayRuntime {
+    /// True while a replay owns the publishing path (paused replays included).
+    pub fn is_active(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(|handle| read_status(&handle.status).active)
+    }
+
+    pub fn status(&self) -> ReplayStatus {
+        match &self.handle {
+            Some(handle) => read_status(&handle.status),
+            None => idle_status(),
+        }
+    }
+
+    fn send(&self, command: ReplayControl) -> CmdResult<()> {
+        match &self.handle {
+            Some(handle) if self.is_active() => handle
+                .control
+                .send(command)
+                .map_err(|_| "Profile replay has already ended.".to_string()),
+            _ => Err("No profile replay is running.".to_string()),
+        }
+    }
+}
+
+fn idle_status() -> ReplayStatus {
+    ReplayStatus {
+        speed: 1.0,
+        ..ReplayStatus::default()
+    }
+}
+
+fn read_status(status: &StdMutex<ReplayStatus>) -> ReplayStatus {
+    status
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+}
+
+fn update_status(status: &StdMutex<ReplayStatus>, update: impl FnOnce(&mut ReplayStatus)) {
+    let mut guard = status
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    update(&mut guard);
+}
+
+fn normalize_speed(speed: f64) -> CmdResult<f64> {
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err("replay speed must be a positive number".to_string());
+    }
+    Ok(speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED))
+}
+
+/// Playback position of a running replay.
+struct ReplayCursor {
+    index: usize,
+    paused: bool,
+    speed: f64,
+    /// Publish the next sample without waiting (start and after a seek).
+    immediate: bool,
+    /// Recorded offset of the last published sample, for the virtual alert clock.
+    last_offset_ms: Option<u64>,
+}
+
+impl ReplayCursor {
+    /// Applies a control command; returns false when the replay should end.
+    fn apply(&mut self, command: ReplayControl, timeline: &ReplayTimeline) -> bool {
+        match command {
+            ReplayControl::Pause => self.paused = true,
+            ReplayControl::Resume => self.paused = false,
+            ReplayControl::Seek(position_ms) => {
+                self.index = timeline.index_at(position_ms);
+                self.immediate = true;
+                self.last_offset_ms = None;
+            }
+            ReplayControl::Speed(speed) => self.speed = speed,
+            ReplayControl::Stop => return false,
+        }
+        true
+    }
+}
+
+async fn run_replay(
+    ctx: CoreContext,
+    state: SharedState,
+    timeline: ReplayTimeline,
+    status: Arc<StdMutex<ReplayStatus>>,
+    mut control: mpsc::UnboundedReceiver<ReplayControl>,
+    speed: f64,
+) {
+    let mut cursor = ReplayCursor {
+        index: 0,
+        paused: false,
+        speed,
+        immediate: true,
+        last_offset_ms: None,
+    };
+    let mut virtual_now = Instant::now();
+
+    while cursor.index < timeline.len() {
+        let command = if cursor.paused {
+            control.recv().await
+        } else {
+            let delay = if cursor.immediate {
+                Duration::ZERO
+            } else {
+                timeline.delay_before(cursor.index, cursor.speed)
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {
+                    virtual_now =
+                        publish_sample(&ctx, &state, &timeline, &mut cursor, virtual_now).await;
+                    update_status(&status, |status| {
+                        status.sample_index = cursor.index;
+                        status.position_ms = timeline.offset_ms(cursor.index.saturating_sub(1));
+                    });
+                    continue;
+                }
+                command = control.recv() => command,
+            }
+        };
+
+        let Some(command) = command else {
+            break;
+        };
+        if !cursor.apply(command, &timeline) {
+            break;
+        }
+        update_status(&status, |status| {
+            status.paused = cursor.paused;
+            status.speed = cursor.speed;
+            status.sample_index = cursor.index;
+            status.position_ms = timeline.offset_ms(cursor.index);
+        });
+        ctx.events
+            .publish(CoreEvent::ReplayStatus(read_status(&status)));
+    }
+
+    update_status(&status, |status| {
+        status.active = false;
+        status.paused = false;
+        status.finished = cursor.index >= timeline.len();
+    });
+    ctx.events
+        .publish(CoreEvent::ReplayStatus(read_status(&status)));
+}
+
+async fn publish_sample(
+    ctx: &CoreContext,
+    state: &SharedState,
+    timeline: &ReplayTimeline,
+    cursor: &mut ReplayCursor,
+    virtual_now: Instant,
+) -> Instant {
+    let offset_ms = timeline.offset_ms(cursor.index);
+    let elapsed_ms = cursor
+        .last_offset_ms
+        .map(|last| offset_ms.saturating_sub(last))
+        .unwrap_or(0);
+    let now = virtual_now + Duration::from_millis(elapsed_ms);
+
+    if let Some(snapshot) = timeline.snapshot(cursor.index) {
+        publish_snapshot(ctx, state, snapshot.clone(), SnapshotSource::Replay { now }).await;
+    }
+    cursor.index += 1;
+    cursor.immediate = false;
+    cursor.last_offset_ms = Some(offset_ms);
+    now
+}
+
+#[tauri::command]
+pub async fn start_profile_replay(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    path: String,
+    speed: Option<f64>,
+) -> CmdResult<ReplayStatus> {
+    if path.trim().is_empty() {
+        return Err("profile path is required".to_string());
+    }
+    let speed = normalize_speed(speed.unwrap_or(1.0))?;
+    if state.profiler.lock().await.is_active() {
+        return Err("Stop the profile capture before starting a replay.".to_string());
+    }
+    let mut runtime = state.replay.lock().await;
+    if runtime.is_active() {
+        return Err("Profile replay already running.".to_string());
+    }
+
+    let ctx = core_context(&app);
+    let resolved = resolve_existing_profile(&*ctx.paths, &path);
+    let load_path = resolved.clone();
+    let timeline = tauri::async_runtime::spawn_blocking(move || ReplayTimeline::load(&load_path))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let status = Arc::new(StdMutex::new(ReplayStatus {
+        active: true,
+        paused: false,
+        path: Some(resolved.to_string_lossy().to_string()),
+        speed,
+        position_ms: 0,
+        duration_ms: timeline.duration_ms(),
+        sample_index: 0,
+        total_samples: timeline.len(),
+        skipped_lines: timeline.skipped_lines(),
+        finished: false,
+    }));
+    crate::alerts::reset_replay_alerts(state.inner()).await;
+    let initial = read_status(&status);
+    let (control, control_rx) = mpsc::unbounded_channel();
+    let task = tauri::async_runtime::spawn(run_replay(
+        ctx.clone(),
+        state.inner().clone(),
+        timeline,
+        status.clone(),
+        control_rx,
+        speed,
+    ));
+    runtime.handle = Some(ReplayHandle {
+        control,
+        status,
+        task,
+    });
+    drop(runtime);
+
+    ctx.events.publish(CoreEvent::ReplayStatus(initial.clone()));
+    Ok(initial)
+}
+
+#[tauri::command]
+pub async fn stop_profile_replay(state: State<'_, SharedState>) -> CmdResult<ReplayStatus> {
+    let handle = state.replay.lock().await.handle.take();
+    let Some(handle) = handle else {
+        return Ok(idle_status());
+    };
+    let _ = handle.control.send(ReplayControl::Stop);
+    let _ = handle.task.await;
+    Ok(read_status(&handle.status))
+}
+
+#[tauri::command]
+pub async fn pause_profile_replay(state: State<'_, SharedState>) -> CmdResult<()> {
+    state.replay.lock().await.send(ReplayControl::Pause)
+}
+
+#[tauri::command]
+pub async fn resume_profile_replay(state: State<'_, SharedState>) -> CmdResult<()> {
+    state.replay.lock().await.send(ReplayControl::Resume)
+}
+
+#[tauri::command]
+pub async fn seek_profile_replay(state: State<'_, SharedState>, position_ms: u64) -> CmdResult<()> {
+    state
+        .replay
+        .lock()
+        .await
+        .send(ReplayControl::Seek(position_ms))
+}
+
+#[tauri::command]
+pub async fn set_profile_replay_speed(state: State<'_, SharedState>, speed: f64) -> CmdResult<()> {
+    let speed = normalize_speed(speed)?;
+    state.replay.lock().await.send(ReplayControl::Speed(speed))
+}
+
+#[tauri::command]
+pub async fn get_profile_replay_status(state: State<'_, SharedState>) -> CmdResult<ReplayStatus> {
+    Ok(state.replay.lock().await.status())
+}
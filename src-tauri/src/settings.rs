@@ -0,0 +1,124 @@
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, State};
+
+use crate::{
+    core::{
+        events::CoreEvent, settings_store, template::Template, thresholds::ThresholdBands,
+        units::UnitFormatter,
+    },
+    state::SharedState,
+    tauri_adapter::{core_context, TauriAdapter},
+    types::AppSettings,
+};
+
+type CmdResult<T> = Result<T, String>;
+
+/// Changes closer together than this are written once.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// A steady stream of changes is still written at least this often.
+const SAVE_MAX_WAIT: Duration = Duration::from_secs(5);
+
+/// Reads `settings.json` for [`crate::state::AppState::initialize`]; a missing or unreadable
+/// file starts from the defaults.
+pub fn load_settings(app: &AppHandle) -> AppSettings {
+    let mut settings = match settings_store::load_settings(&TauriAdapter::new(app)) {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(err) => {
+            tracing::warn!("failed to load settings, using defaults: {err}");
+            AppSettings::default()
+        }
+    };
+    // The native taskbar only opens through `configure_native_taskbar_monitor`, so it is
+    // closed at startup whatever the last session saved.
+    settings.native_taskbar_monitor_enabled = false;
+    settings
+}
+
+/// Writes pending changes and publishes `settings://changed`; a no-op when nothing changed.
+/// A failed write stays pending for the next change or shutdown.
+async fn save_pending(app: &AppHandle, state: &SharedState) {
+    if !state.settings_dirty.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    let settings = state.settings.read().await.clone();
+    if let Err(err) = settings_store::save_settings(&TauriAdapter::new(app), &settings) {
+        tracing::warn!("failed to save settings: {err}");
+        state.settings_dirty.store(true, Ordering::Relaxed);
+        return;
+    }
+    core_context(app)
+        .events
+        .publish(CoreEvent::SettingsChanged(settings));
+}
+
+/// Saves settings once they have been quiet for [`SAVE_DEBOUNCE`], or [`SAVE_MAX_WAIT`] after
+/// the first change.
+pub fn start_settings_store(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            state.settings_changed.notified().await;
+            let deadline = Instant::now() + SAVE_MAX_WAIT;
+            loop {
+                let wait = SAVE_DEBOUNCE.min(deadline.saturating_duration_since(Instant::now()));
+                if wait.is_zero()
+                    || tokio::time::timeout(wait, state.settings_changed.notified())
+                        .await
+                        .is_err()
+                {
+                    break;
+                }
+            }
+            save_pending(&app, &state).await;
+        }
+    });
+}
+
+/// Writes a change still waiting out the debounce, for shutdown.
+pub async fn flush_settings(app: &AppHandle, state: &SharedState) {
+    save_pending(app, state).await;
+}
+
+/// Replaces all settings, e.g. when the front end imports a config; the same checks as the
+/// individual setters apply.
+#[tauri::command]
+pub async fn update_app_settings(
+    state: State<'_, SharedState>,
+    mut settings: AppSettings,
+) -> CmdResult<AppSettings> {
+    settings.memory_trim_interval_minutes = settings.memory_trim_interval_minutes.clamp(1, 30);
+    settings.window_title_template = settings
+        .window_title_template
+        .map(|template| template.trim().to_string())
+        .filter(|template| !template.is_empty());
+    if let Some(template) = settings.window_title_template.as_deref() {
+        Template::parse(template)?;
+    }
+    ThresholdBands::parse(&settings.metric_thresholds)?;
+    UnitFormatter::parse(&settings.units)?;
+
+    state
+        .memory_trim_enabled
+        .store(settings.memory_trim_enabled, Ordering::Relaxed);
+    state
+        .memory_trim_system_enabled
+        .store(settings.memory_trim_system_enabled, Ordering::Relaxed);
+    state.memory_trim_interval_ms.store(
+        settings.memory_trim_interval_minutes as u64 * 60 * 1000,
+        Ordering::Relaxed,
+    );
+    let snapshot = state.latest_snapshot.read().await.clone();
+    crate::native_taskbar::set_thresholds(settings.metric_thresholds.clone(), snapshot.clone());
+    crate::native_taskbar::set_units(settings.units.clone(), snapshot);
+    state
+        .update_settings(|current| {
+            // Only `configure_native_taskbar_monitor` starts and stops the native taskbar.
+            settings.native_taskbar_monitor_enabled = current.native_taskbar_monitor_enabled;
+            *current = settings.clone();
+        })
+        .await;
+    Ok(settings)
+}
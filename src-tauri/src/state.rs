@@ -1,24 +1,44 @@
 use std::sync::{
-    atomic::{AtomicBool, AtomicU64},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
 
 use std::collections::HashMap;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, Notify, RwLock};
 
 use crate::{
-    core::collectors::system_collector::SystemCollector,
+    adaptive_sampling::SamplingRuntime,
+    alerts::AlertEngine,
+    anomaly::AnomalyDetector,
+    core::{
+        collectors::system_collector::SystemCollector,
+        sampling::SamplingPlan,
+        template::{window_title_template, Template},
+    },
+    digest::HealthDigestRuntime,
+    flight_recorder::FlightRecorderRuntime,
     local_ai::LocalAiRuntime,
-    profiler::ProfilerHandle,
+    mqtt::MqttRuntime,
+    profiler::ProfilerRuntime,
+    replay::ReplayRuntime,
+    telemetry_stream::TelemetryStreamRuntime,
+    tray_icon::TrayIconRuntime,
     types::{AppSettings, HardwareInfo, SmtpEmailConfig, TaskReminder, TelemetrySnapshot},
 };
 
 pub struct AppState {
+    /// Change through [`AppState::update_settings`] so the change is saved.
     pub settings: RwLock<AppSettings>,
+    /// Set by [`AppState::update_settings`] until the settings store writes the change.
+    pub settings_dirty: AtomicBool,
+    pub settings_changed: Notify,
+    /// `settings.window_title_template` parsed; [`AppState::update_settings`] keeps it current.
+    pub window_title: RwLock<Template>,
     pub hardware_info: RwLock<HardwareInfo>,
     pub latest_snapshot: RwLock<TelemetrySnapshot>,
+    pub snapshot_tx: broadcast::Sender<TelemetrySnapshot>,
     pub collector: Mutex<SystemCollector>,
-    pub profiler: Mutex<Option<ProfilerHandle>>,
+    pub profiler: Mutex<ProfilerRuntime>,
     pub refresh_rate_ms: AtomicU64,
     pub memory_trim_interval_ms: AtomicU64,
     pub memory_trim_enabled: AtomicBool,
@@ -27,26 +47,47 @@ pub struct AppState {
     pub reminder_smtp_config: RwLock<Option<SmtpEmailConfig>>,
     pub reminder_last_fired: Mutex<HashMap<String, String>>,
     pub local_ai_runtime: Mutex<LocalAiRuntime>,
+    pub telemetry_stream: Mutex<TelemetryStreamRuntime>,
+    pub mqtt: Mutex<MqttRuntime>,
+    pub alerts: Mutex<AlertEngine>,
+    /// Evaluates replayed snapshots, whose virtual clock must not touch the live trackers.
+    pub replay_alerts: Mutex<AlertEngine>,
+    pub anomaly: Mutex<AnomalyDetector>,
+    pub health_digest: Mutex<HealthDigestRuntime>,
+    pub sampling: Mutex<SamplingRuntime>,
+    pub replay: Mutex<ReplayRuntime>,
+    pub flight_recorder: Mutex<FlightRecorderRuntime>,
+    pub tray_icon: Mutex<TrayIconRuntime>,
 }
 
 pub type SharedState = Arc<AppState>;
 
+/// Snapshots buffered per subscriber before slow consumers start skipping.
+const SNAPSHOT_CHANNEL_CAPACITY: usize = 16;
+
 impl AppState {
-    pub async fn initialize() -> anyhow::Result<SharedState> {
-        let settings = AppSettings::default();
+    /// Builds the state from the settings loaded from `settings.json`.
+    pub async fn initialize(settings: AppSettings) -> anyhow::Result<SharedState> {
         let trim_interval_ms = settings.memory_trim_interval_minutes as u64 * 60 * 1000;
         let trim_enabled = settings.memory_trim_enabled;
         let trim_system_enabled = settings.memory_trim_system_enabled;
         let collector = SystemCollector::new();
         let initial_snapshot = empty_snapshot();
         let hardware_info = empty_hardware_info();
+        let (snapshot_tx, _) = broadcast::channel(SNAPSHOT_CHANNEL_CAPACITY);
+        let window_title = window_title_template(settings.window_title_template.as_deref());
+        let tray_icon = TrayIconRuntime::new(&settings);
 
         Ok(Arc::new(Self {
             settings: RwLock::new(settings),
+            settings_dirty: AtomicBool::new(false),
+            settings_changed: Notify::new(),
+            window_title: RwLock::new(window_title),
             hardware_info: RwLock::new(hardware_info),
             latest_snapshot: RwLock::new(initial_snapshot),
+            snapshot_tx,
             collector: Mutex::new(collector),
-            profiler: Mutex::new(None),
+            profiler: Mutex::new(ProfilerRuntime::default()),
             refresh_rate_ms: AtomicU64::new(1000),
             memory_trim_interval_ms: AtomicU64::new(trim_interval_ms),
             memory_trim_enabled: AtomicBool::new(trim_enabled),
@@ -55,17 +96,58 @@ impl AppState {
             reminder_smtp_config: RwLock::new(None),
             reminder_last_fired: Mutex::new(HashMap::new()),
             local_ai_runtime: Mutex::new(LocalAiRuntime::default()),
+            telemetry_stream: Mutex::new(TelemetryStreamRuntime::default()),
+            mqtt: Mutex::new(MqttRuntime::default()),
+            alerts: Mutex::new(AlertEngine::default()),
+            replay_alerts: Mutex::new(AlertEngine::default()),
+            anomaly: Mutex::new(AnomalyDetector::default()),
+            health_digest: Mutex::new(HealthDigestRuntime::default()),
+            sampling: Mutex::new(SamplingRuntime::default()),
+            replay: Mutex::new(ReplayRuntime::default()),
+            flight_recorder: Mutex::new(FlightRecorderRuntime::default()),
+            tray_icon: Mutex::new(tray_icon),
         }))
     }
 
+    /// Applies `update` to the settings and schedules a debounced save.
+    pub async fn update_settings<R>(&self, update: impl FnOnce(&mut AppSettings) -> R) -> R {
+        let mut settings = self.settings.write().await;
+        let previous_title = settings.window_title_template.clone();
+        let previous_thresholds = settings.metric_thresholds.clone();
+        let previous_units = settings.units.clone();
+        let result = update(&mut settings);
+        if settings.window_title_template != previous_title {
+            *self.window_title.write().await =
+                window_title_template(settings.window_title_template.as_deref());
+        }
+        if settings.metric_thresholds != previous_thresholds || settings.units != previous_units {
+            self.tray_icon.lock().await.set_display_settings(&settings);
+        }
+        drop(settings);
+        self.settings_dirty.store(true, Ordering::Relaxed);
+        self.settings_changed.notify_one();
+        result
+    }
+
     pub async fn record_snapshot(&self, snapshot: TelemetrySnapshot) {
+        // Only pay for the clone when an external consumer is subscribed.
+        if self.snapshot_tx.receiver_count() > 0 {
+            let _ = self.snapshot_tx.send(snapshot.clone());
+        }
         let mut lock = self.latest_snapshot.write().await;
         *lock = snapshot;
     }
 
-    pub async fn collect_snapshot(&self, refresh_rate_ms: u64) -> TelemetrySnapshot {
+    /// Stores a replayed snapshot for the app's own views without passing it to stream or
+    /// MQTT subscribers, who would take it for the machine's current state.
+    pub async fn record_replayed_snapshot(&self, snapshot: TelemetrySnapshot) {
+        *self.latest_snapshot.write().await = snapshot;
+    }
+
+    pub async fn collect_snapshot(&self, plan: SamplingPlan) -> TelemetrySnapshot {
         let mut collector = self.collector.lock().await;
-        collector.collect(refresh_rate_ms)
+        collector.set_expensive_sources_paused(!plan.expensive_sources);
+        collector.collect(plan.interval_ms)
     }
 }
 
@@ -88,6 +170,8 @@ fn empty_snapshot() -> TelemetrySnapshot {
             used_mb: 0.0,
             total_mb: 1.0,
             usage_pct: 0.0,
+            swap_used_mb: None,
+            swap_total_mb: None,
         },
         disks: Vec::new(),
         network: crate::types::NetworkMetrics {
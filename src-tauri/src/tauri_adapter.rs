@@ -0,0 +1,137 @@
+use std::{path::PathBuf, sync::Arc};
+
+use tauri::{image::Image, AppHandle, Emitter, Manager};
+
+use crate::{
+    core::{
+        events::CoreEvent,
+        ports::{
+            CoreContext, EventSink, Notification, Notifier, PathProvider, PortFuture, SystemClock,
+            WindowProbe,
+        },
+        profile::WindowSample,
+    },
+    ipc::commands,
+    webhooks::{self, WebhookMessage},
+};
+
+/// Windows that render live telemetry, in the order reminders look for a host window.
+const CONSUMER_LABELS: [&str; 3] = ["main", "taskbar", "toolkit"];
+const REMINDER_HOST_LABELS: [&str; 3] = ["main", "toolkit", "taskbar"];
+
+/// Binds the core ports to a running Tauri app.
+#[derive(Clone)]
+pub struct TauriAdapter {
+    app: AppHandle,
+}
+
+impl TauriAdapter {
+    pub fn new(app: &AppHandle) -> Self {
+        Self { app: app.clone() }
+    }
+}
+
+pub fn core_context(app: &AppHandle) -> CoreContext {
+    let adapter = Arc::new(TauriAdapter::new(app));
+    CoreContext {
+        clock: Arc::new(SystemClock),
+        paths: adapter.clone(),
+        notifier: adapter.clone(),
+        events: adapter.clone(),
+        windows: adapter,
+    }
+}
+
+impl TauriAdapter {
+    fn set_main_title(&self, title: &str) -> tauri::Result<()> {
+        match self.app.get_webview_window("main") {
+            Some(win) => win.set_title(title),
+            None => Ok(()),
+        }
+    }
+
+    /// The tray appears once the front end has started; until then there is nothing to draw on.
+    fn set_tray_icon(&self, icon: &image::RgbaImage) -> tauri::Result<()> {
+        let Some(tray) = self.app.tray_by_id(crate::tray_icon::APP_TRAY_ID) else {
+            return Ok(());
+        };
+        let (width, height) = icon.dimensions();
+        tray.set_icon(Some(Image::new(icon.as_raw(), width, height)))
+    }
+}
+
+impl PathProvider for TauriAdapter {
+    fn data_dir(&self) -> Result<PathBuf, String> {
+        self.app.path().app_data_dir().map_err(|e| e.to_string())
+    }
+}
+
+impl WindowProbe for TauriAdapter {
+    fn windows(&self) -> Vec<WindowSample> {
+        CONSUMER_LABELS
+            .into_iter()
+            .filter_map(|label| {
+                let win = self.app.get_webview_window(label)?;
+                Some(WindowSample {
+                    label: label.to_string(),
+                    visible: win.is_visible().unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+}
+
+impl EventSink for TauriAdapter {
+    fn publish(&self, event: CoreEvent) {
+        let name = event.name();
+        let result = match &event {
+            // Hidden windows skip snapshots; the telemetry loop runs regardless.
+            CoreEvent::Snapshot(_) => self
+                .visible_labels()
+                .into_iter()
+                .try_for_each(|label| self.app.emit_to(label.as_str(), &name, event.payload())),
+            CoreEvent::ReminderScreen(_) => match REMINDER_HOST_LABELS
+                .into_iter()
+                .find(|label| self.app.get_webview_window(label).is_some())
+            {
+                Some(label) => self.app.emit_to(label, &name, event.payload()),
+                None => Ok(()),
+            },
+            CoreEvent::Alert(_)
+            | CoreEvent::ReplayStatus(_)
+            | CoreEvent::ProfileProgress(_)
+            | CoreEvent::ProfileFinished(_)
+            | CoreEvent::SettingsChanged(_) => self.app.emit(&name, event.payload()),
+            CoreEvent::WindowTitle(title) => self.set_main_title(title),
+            CoreEvent::TrayIcon(icon) => self.set_tray_icon(icon),
+        };
+        if let Err(err) = result {
+            tracing::warn!("failed to emit {name}: {err}");
+        }
+    }
+}
+
+impl Notifier for TauriAdapter {
+    fn notify<'a>(&'a self, notification: Notification) -> PortFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            match notification {
+                Notification::Email {
+                    smtp,
+                    to,
+                    subject,
+                    text,
+                    html,
+                } => commands::send_email_internal(smtp, to, subject, text, html).await,
+                Notification::Webhook {
+                    endpoint_id,
+                    event,
+                    title,
+                    text,
+                } => {
+                    let message = WebhookMessage::new(&event, title, text);
+                    webhooks::send_to_webhook_id(self, &endpoint_id, &message).await
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,816 @@
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tauri::{async_runtime::JoinHandle, AppHandle, State};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::{broadcast::error::RecvError, watch},
+};
+use tokio_tungstenite::{
+    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    WebSocketStream,
+};
+
+use crate::{
+    core::{ports::PathProvider, store},
+    state::SharedState,
+    tauri_adapter::TauriAdapter,
+    types::{TelemetrySnapshot, TelemetryStreamConfig, TelemetryStreamStatus},
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const TELEMETRY_STREAM_FILE: &str = "telemetry-stream.json";
+const MAX_STREAM_CLIENTS: usize = 32;
+const MAX_REQUEST_HEAD_BYTES: usize = 8 * 1024;
+const REQUEST_HEAD_TIMEOUT_MS: u64 = 5_000;
+const MIN_CLIENT_INTERVAL_MS: u64 = 50;
+/// Pause after a failed `accept` (e.g. out of file descriptors) so the loop does not spin.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Metric groups clients can subscribe to, mapped to the snapshot keys they carry.
+const SNAPSHOT_GROUPS: [(&str, &[&str]); 7] = [
+    ("cpu", &["cpu"]),
+    ("gpu", &["gpu"]),
+    ("memory", &["memory"]),
+    ("disks", &["disks"]),
+    ("network", &["network"]),
+    ("app", &["appCpuUsagePct", "appMemoryMb"]),
+    ("power", &["power_watts"]),
+];
+
+#[derive(Default)]
+pub struct TelemetryStreamRuntime {
+    handle: Option<TelemetryStreamHandle>,
+    last_error: Option<String>,
+}
+
+struct TelemetryStreamHandle {
+    address: SocketAddr,
+    clients: Arc<AtomicUsize>,
+    stop: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl TelemetryStreamRuntime {
+    pub fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    fn status(&self) -> TelemetryStreamStatus {
+        TelemetryStreamStatus {
+            running: self.handle.is_some(),
+            address: self
+                .handle
+                .as_ref()
+                .map(|handle| handle.address.to_string()),
+            clients: self
+                .handle
+                .as_ref()
+                .map(|handle| handle.clients.load(Ordering::Relaxed))
+                .unwrap_or(0),
+            error: self.last_error.clone(),
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.stop.send(true);
+            handle.task.abort();
+        }
+    }
+}
+
+/// What a request must present: the token, if set, and an allowed `Origin` when sent from a
+/// browser. WebSockets are not covered by CORS, so the origin is checked for every path.
+struct StreamAccess {
+    token: Option<String>,
+    allowed_origins: Vec<String>,
+}
+
+impl StreamAccess {
+    fn from_config(config: &TelemetryStreamConfig) -> Self {
+        Self {
+            token: (!config.token.is_empty()).then(|| config.token.clone()),
+            allowed_origins: config.allowed_origins.clone(),
+        }
+    }
+
+    /// Requests without an `Origin` come from scripts and tools rather than web pages.
+    fn origin_allowed(&self, origin: Option<&str>) -> bool {
+        origin.is_none_or(|origin| {
+            self.allowed_origins
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+        })
+    }
+
+    /// CORS headers for an allowed browser origin, empty otherwise.
+    fn cors_headers(&self, origin: Option<&str>) -> String {
+        match origin {
+            Some(origin) if self.origin_allowed(Some(origin)) => {
+                format!("Access-Control-Allow-Origin: {origin}\r\nVary: Origin\r\n")
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+/// Holds one of the [`MAX_STREAM_CLIENTS`] slots until the connection ends.
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl ClientSlot {
+    /// Reserves a slot before the connection is spawned, so concurrent connects cannot all
+    /// pass the limit.
+    fn reserve(clients: &Arc<AtomicUsize>) -> Option<Self> {
+        if clients.fetch_add(1, Ordering::AcqRel) >= MAX_STREAM_CLIENTS {
+            clients.fetch_sub(1, Ordering::AcqRel);
+            return None;
+        }
+        Some(Self(Arc::clone(clients)))
+    }
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct StreamSubscription {
+    groups: Option<HashSet<String>>,
+    interval: Duration,
+}
+
+impl StreamSubscription {
+    fn from_query(query: &str) -> Self {
+        let mut subscription = Self {
+            groups: None,
+            interval: Duration::ZERO,
+        };
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "groups" => subscription.set_groups(value.split(',')),
+                "intervalMs" => {
+                    if let Ok(ms) = value.trim().parse::<u64>() {
+                        subscription.set_interval_ms(ms);
+                    }
+                }
+                _ => {}
+            }
+        }
+        subscription
+    }
+
+    /// Applies a `{"groups": [...], "intervalMs": n}` message sent over the socket.
+    fn apply_update(&mut self, value: &Value) {
+        if let Some(groups) = value.get("groups").and_then(Value::as_array) {
+            self.set_groups(groups.iter().filter_map(Value::as_str));
+        }
+        if let Some(ms) = value.get("intervalMs").and_then(Value::as_u64) {
+            self.set_interval_ms(ms);
+        }
+    }
+
+    fn set_groups<'a>(&mut self, names: impl Iterator<Item = &'a str>) {
+        let groups: HashSet<String> = names
+            .map(|name| name.trim().to_ascii_lowercase())
+            .filter(|name| SNAPSHOT_GROUPS.iter().any(|(group, _)| group == name))
+            .collect();
+        self.groups = (!groups.is_empty()).then_some(groups);
+    }
+
+    fn set_interval_ms(&mut self, ms: u64) {
+        self.interval = if ms == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_millis(ms.max(MIN_CLIENT_INTERVAL_MS))
+        };
+    }
+
+    fn is_due(&self, last_sent: Option<Instant>) -> bool {
+        last_sent.is_none_or(|sent| sent.elapsed() >= self.interval)
+    }
+
+    fn payload(&self, snapshot: &TelemetrySnapshot) -> Option<String> {
+        let value = serde_json::to_value(snapshot).ok()?;
+        let Some(groups) = &self.groups else {
+            return serde_json::to_string(&value).ok();
+        };
+        let Value::Object(full) = value else {
+            return None;
+        };
+
+        let mut filtered = Map::new();
+        if let Some(timestamp) = full.get("timestamp") {
+            filtered.insert("timestamp".to_string(), timestamp.clone());
+        }
+        for (group, keys) in SNAPSHOT_GROUPS {
+            if !groups.contains(group) {
+                continue;
+            }
+            for key in keys {
+                if let Some(field) = full.get(*key) {
+                    filtered.insert((*key).to_string(), field.clone());
+                }
+            }
+        }
+        serde_json::to_string(&Value::Object(filtered)).ok()
+    }
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn query_value(&self, name: &str) -> Option<String> {
+        url::form_urlencoded::parse(self.query.as_bytes())
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    fn wants_websocket(&self) -> bool {
+        self.header("upgrade")
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+fn read_telemetry_stream_config(paths: &dyn PathProvider) -> Option<TelemetryStreamConfig> {
+    store::read_json(paths, TELEMETRY_STREAM_FILE)
+}
+
+fn write_telemetry_stream_config(
+    paths: &dyn PathProvider,
+    config: &TelemetryStreamConfig,
+) -> CmdResult<()> {
+    store::write_json(paths, TELEMETRY_STREAM_FILE, config)
+}
+
+/// Compares digests of both tokens so the time taken does not reveal how much of a guess
+/// matched, or the token's length.
+fn token_matches(provided: Option<&str>, expected: &str) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    provided
+        .iter()
+        .zip(expected.iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+fn normalize_telemetry_stream_config(input: TelemetryStreamConfig) -> TelemetryStreamConfig {
+    let bind_address = input.bind_address.trim();
+    TelemetryStreamConfig {
+        enabled: input.enabled,
+        bind_address: if bind_address.is_empty() {
+            TelemetryStreamConfig::default().bind_address
+        } else {
+            bind_address.to_string()
+        },
+        port: if input.port == 0 {
+            TelemetryStreamConfig::default().port
+        } else {
+            input.port
+        },
+        token: input.token.trim().to_string(),
+        allowed_origins: input
+            .allowed_origins
+            .iter()
+            .map(|origin| origin.trim().trim_end_matches('/').to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect(),
+    }
+}
+
+/// Starts the stream server at launch when the persisted config has it enabled.
+pub fn start_telemetry_stream_service(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        let Some(config) = read_telemetry_stream_config(&TauriAdapter::new(&app)) else {
+            return;
+        };
+        let config = normalize_telemetry_stream_config(config);
+        if let Err(err) = apply_telemetry_stream_config(&state, &config).await {
+            tracing::warn!("telemetry stream failed to start: {err}");
+        }
+    });
+}
+
+async fn apply_telemetry_stream_config(
+    state: &SharedState,
+    config: &TelemetryStreamConfig,
+) -> CmdResult<TelemetryStreamStatus> {
+    let mut runtime = state.telemetry_stream.lock().await;
+    runtime.stop();
+    runtime.last_error = None;
+    if !config.enabled {
+        return Ok(runtime.status());
+    }
+
+    let bind = format!("{}:{}", config.bind_address, config.port);
+    let listener = match TcpListener::bind(&bind).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            let message = format!("failed to bind telemetry stream on {bind}: {err}");
+            runtime.last_error = Some(message.clone());
+            return Err(message);
+        }
+    };
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    let clients = Arc::new(AtomicUsize::new(0));
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let access = Arc::new(StreamAccess::from_config(config));
+
+    let task = tauri::async_runtime::spawn(accept_loop(
+        listener,
+        state.clone(),
+        access,
+        Arc::clone(&clients),
+        stop_rx,
+    ));
+    tracing::info!("telemetry stream listening on {address}");
+
+    runtime.handle = Some(TelemetryStreamHandle {
+        address,
+        clients,
+        stop: stop_tx,
+        task,
+    });
+    Ok(runtime.status())
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    state: SharedState,
+    access: Arc<StreamAccess>,
+    clients: Arc<AtomicUsize>,
+    stop: watch::Receiver<bool>,
+) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                tracing::debug!("telemetry stream accept failed: {err}");
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
+        let Some(slot) = ClientSlot::reserve(&clients) else {
+            let mut stream = stream;
+            let _ =
+                write_plain_response(&mut stream, "503 Service Unavailable", "too many clients")
+                    .await;
+            continue;
+        };
+
+        let state = state.clone();
+        let access = Arc::clone(&access);
+        let stop = stop.clone();
+        tauri::async_runtime::spawn(async move {
+            let _slot = slot;
+            if let Err(err) = handle_connection(stream, state, &access, stop).await {
+                tracing::debug!("telemetry stream client {peer} closed: {err}");
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    state: SharedState,
+    access: &StreamAccess,
+    stop: watch::Receiver<bool>,
+) -> Result<(), String> {
+    let head = tokio::time::timeout(
+        Duration::from_millis(REQUEST_HEAD_TIMEOUT_MS),
+        read_request_head(&mut stream),
+    )
+    .await
+    .map_err(|_| "request head timed out".to_string())??;
+
+    let origin = head.header("origin");
+    if !access.origin_allowed(origin) {
+        return write_plain_response(&mut stream, "403 Forbidden", "origin not allowed").await;
+    }
+    if head.method != "GET" {
+        return write_plain_response(
+            &mut stream,
+            "405 Method Not Allowed",
+            "only GET is supported",
+        )
+        .await;
+    }
+    if let Some(expected) = access.token.as_deref() {
+        if !token_matches(head.query_value("token").as_deref(), expected) {
+            return write_plain_response(&mut stream, "401 Unauthorized", "invalid token").await;
+        }
+    }
+
+    let cors = access.cors_headers(origin);
+    let subscription = StreamSubscription::from_query(&head.query);
+    match head.path.as_str() {
+        "/snapshot" => {
+            let snapshot = state.latest_snapshot.read().await.clone();
+            let body = subscription
+                .payload(&snapshot)
+                .ok_or_else(|| "failed to serialize snapshot".to_string())?;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{cors}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream
+                .write_all(response.as_bytes())
+                .await
+                .map_err(|e| e.to_string())
+        }
+        "/events" => serve_sse(stream, state, &cors, subscription, stop).await,
+        "/ws" if head.wants_websocket() => {
+            serve_websocket(stream, &head, state, subscription, stop).await
+        }
+        _ => {
+            write_plain_response(
+                &mut stream,
+                "404 Not Found",
+                "use /snapshot, /events or /ws",
+            )
+            .await
+        }
+    }
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> Result<RequestHead, String> {
+    let mut buffer = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    let head_len = loop {
+        let read = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("connection closed before request head".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(pos) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err("request head too large".to_string());
+        }
+    };
+
+    let text = String::from_utf8_lossy(&buffer[..head_len]);
+    let mut lines = text.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_ascii_uppercase();
+    let target = parts.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok(RequestHead {
+        method,
+        path: path.to_string(),
+        query: query.to_string(),
+        headers,
+    })
+}
+
+async fn write_plain_response(
+    stream: &mut TcpStream,
+    status: &str,
+    body: &str,
+) -> Result<(), String> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn serve_sse(
+    mut stream: TcpStream,
+    state: SharedState,
+    cors: &str,
+    subscription: StreamSubscription,
+    mut stop: watch::Receiver<bool>,
+) -> Result<(), String> {
+    let mut receiver = state.snapshot_tx.subscribe();
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n{cors}\r\n"
+    );
+    stream
+        .write_all(header.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut last_sent = None;
+    loop {
+        let snapshot = tokio::select! {
+            _ = stop.changed() => return Ok(()),
+            received = receiver.recv() => match received {
+                Ok(snapshot) => snapshot,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+        if !subscription.is_due(last_sent) {
+            continue;
+        }
+        let Some(payload) = subscription.payload(&snapshot) else {
+            continue;
+        };
+        let event = format!("event: snapshot\ndata: {payload}\n\n");
+        stream
+            .write_all(event.as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        last_sent = Some(Instant::now());
+    }
+}
+
+async fn serve_websocket(
+    mut stream: TcpStream,
+    head: &RequestHead,
+    state: SharedState,
+    mut subscription: StreamSubscription,
+    mut stop: watch::Receiver<bool>,
+) -> Result<(), String> {
+    let key = head
+        .header("sec-websocket-key")
+        .ok_or_else(|| "missing Sec-WebSocket-Key".to_string())?;
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+    let mut receiver = state.snapshot_tx.subscribe();
+    let mut last_sent = None;
+
+    loop {
+        tokio::select! {
+            _ = stop.changed() => {
+                let _ = socket.close(None).await;
+                return Ok(());
+            }
+            incoming = socket.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    if let Ok(value) = serde_json::from_str::<Value>(&text) {
+                        subscription.apply_update(&value);
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err.to_string()),
+            },
+            received = receiver.recv() => {
+                let snapshot = match received {
+                    Ok(snapshot) => snapshot,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return Ok(()),
+                };
+                if !subscription.is_due(last_sent) {
+                    continue;
+                }
+                if let Some(payload) = subscription.payload(&snapshot) {
+                    socket
+                        .send(Message::text(payload))
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    last_sent = Some(Instant::now());
+                }
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_telemetry_stream_config(app: AppHandle) -> CmdResult<TelemetryStreamConfig> {
+    Ok(read_telemetry_stream_config(&TauriAdapter::new(&app))
+        .map(normalize_telemetry_stream_config)
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn save_telemetry_stream_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: TelemetryStreamConfig,
+) -> CmdResult<TelemetryStreamStatus> {
+    let normalized = normalize_telemetry_stream_config(config);
+    write_telemetry_stream_config(&TauriAdapter::new(&app), &normalized)?;
+    apply_telemetry_stream_config(state.inner(), &normalized).await
+}
+
+#[tauri::command]
+pub async fn get_telemetry_stream_status(
+    state: State<'_, SharedState>,
+) -> CmdResult<TelemetryStreamStatus> {
+    Ok(state.telemetry_stream.lock().await.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{state::AppState, types::AppSettings};
+
+    /// Serves one connection with `handle_connection` and returns what the client received up
+    /// to the end of the response head, or everything for responses that close the connection.
+    async fn exchange(state: &SharedState, token: Option<&str>, request: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut client = TcpStream::connect(address).await.unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        let (_stop_tx, stop_rx) = watch::channel(false);
+        let access = StreamAccess {
+            token: token.map(str::to_string),
+            allowed_origins: vec!["http://localhost:3000".to_string()],
+        };
+        let state = state.clone();
+        let server =
+            tokio::spawn(async move { handle_connection(server, state, &access, stop_rx).await });
+
+        client.write_all(request.as_bytes()).await.unwrap();
+        let mut received = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let read = client.read(&mut chunk).await.unwrap();
+            received.extend_from_slice(&chunk[..read]);
+            if read == 0 || received.starts_with(b"HTTP/1.1 101") && received.ends_with(b"\r\n\r\n")
+            {
+                break;
+            }
+        }
+        drop(client);
+        let _ = server.await;
+        String::from_utf8(received).unwrap()
+    }
+
+    #[test]
+    fn token_matches_only_the_exact_token() {
+        assert!(token_matches(Some("s3cret"), "s3cret"));
+        assert!(!token_matches(Some("s3cre"), "s3cret"));
+        assert!(!token_matches(Some("s3cret "), "s3cret"));
+        assert!(!token_matches(None, "s3cret"));
+    }
+
+    #[test]
+    fn client_slots_are_reserved_up_front_and_released_on_drop() {
+        let clients = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_STREAM_CLIENTS)
+            .map(|_| ClientSlot::reserve(&clients).unwrap())
+            .collect();
+        assert!(ClientSlot::reserve(&clients).is_none());
+        assert_eq!(clients.load(Ordering::Relaxed), MAX_STREAM_CLIENTS);
+        drop(slots);
+        assert_eq!(clients.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn browser_origins_must_be_allowed() {
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        let response = exchange(
+            &state,
+            None,
+            "GET /snapshot HTTP/1.1\r\nOrigin: https://evil.example\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+        let response = exchange(
+            &state,
+            None,
+            "GET /ws HTTP/1.1\r\nOrigin: https://evil.example\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+        let response = exchange(
+            &state,
+            None,
+            "GET /snapshot HTTP/1.1\r\nOrigin: http://localhost:3000\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(
+            response.contains("Access-Control-Allow-Origin: http://localhost:3000\r\n"),
+            "{response}"
+        );
+        let response = exchange(&state, None, "GET /snapshot HTTP/1.1\r\n\r\n").await;
+        assert!(
+            !response.contains("Access-Control-Allow-Origin"),
+            "{response}"
+        );
+    }
+
+    #[tokio::test]
+    async fn subscriptions_filter_groups_and_clamp_intervals() {
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        let snapshot = state.latest_snapshot.read().await.clone();
+
+        let mut subscription = StreamSubscription::from_query("groups=CPU,bogus&intervalMs=10");
+        assert_eq!(
+            subscription.interval,
+            Duration::from_millis(MIN_CLIENT_INTERVAL_MS)
+        );
+        let payload: Value =
+            serde_json::from_str(&subscription.payload(&snapshot).unwrap()).unwrap();
+        let mut keys: Vec<_> = payload.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(keys, ["cpu", "timestamp"]);
+
+        subscription.apply_update(&serde_json::json!({"groups": ["app"], "intervalMs": 0}));
+        assert_eq!(subscription.interval, Duration::ZERO);
+        assert!(subscription.is_due(Some(Instant::now())));
+        let payload: Value =
+            serde_json::from_str(&subscription.payload(&snapshot).unwrap()).unwrap();
+        assert!(payload.get("appCpuUsagePct").is_some());
+        assert!(payload.get("cpu").is_none());
+
+        let everything = StreamSubscription::from_query("groups=bogus");
+        assert!(everything.groups.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_tokens_and_serves_snapshots() {
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+
+        let response = exchange(&state, Some("s3cret"), "GET /snapshot HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+        let response = exchange(
+            &state,
+            Some("s3cret"),
+            "GET /snapshot?token=wrong HTTP/1.1\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+
+        let response = exchange(
+            &state,
+            Some("s3cret"),
+            "GET /snapshot?token=s3cret&groups=memory HTTP/1.1\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert!(body.get("memory").is_some());
+        assert!(body.get("cpu").is_none());
+
+        let response = exchange(&state, None, "POST /snapshot HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 405"), "{response}");
+        let response = exchange(&state, None, "GET /nope HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn upgrades_websocket_requests() {
+        let state = AppState::initialize(AppSettings::default()).await.unwrap();
+        let response = exchange(
+            &state,
+            Some("s3cret"),
+            "GET /ws?token=s3cret HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+             Sec-WebSocket-Version: 13\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 101"), "{response}");
+        // The accept key from RFC 6455, section 1.3.
+        assert!(
+            response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"),
+            "{response}"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use tauri::{AppHandle, State};
+
+use crate::{
+    core::{
+        events::CoreEvent, ports::CoreContext, store, thresholds::ThresholdBands,
+        tray_icon::TrayIconRenderer, units::UnitFormatter,
+    },
+    state::SharedState,
+    tauri_adapter::{core_context, TauriAdapter},
+    types::{AppSettings, TelemetrySnapshot, TrayIconConfig},
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const TRAY_ICON_CONFIG_FILE: &str = "tray-icon.json";
+/// Created by the front end (`windowStore.ensureTray`); the backend only redraws its icon.
+pub(crate) const APP_TRAY_ID: &str = "pulsecorelite-main-tray";
+
+#[derive(Default)]
+pub struct TrayIconRuntime {
+    config: TrayIconConfig,
+    renderer: Option<TrayIconRenderer>,
+    /// The settings' thresholds and units, parsed by [`Self::set_display_settings`] when they
+    /// change rather than on every snapshot.
+    bands: ThresholdBands,
+    units: UnitFormatter,
+}
+
+impl TrayIconRuntime {
+    pub fn new(settings: &AppSettings) -> Self {
+        let mut runtime = Self::default();
+        runtime.set_display_settings(settings);
+        runtime
+    }
+
+    pub fn set_display_settings(&mut self, settings: &AppSettings) {
+        self.bands = ThresholdBands::lenient(&settings.metric_thresholds);
+        self.units = UnitFormatter::lenient(&settings.units);
+    }
+
+    fn set_config(&mut self, config: TrayIconConfig) {
+        self.renderer = if config.enabled {
+            TrayIconRenderer::parse(&config)
+                .inspect_err(|err| tracing::warn!("tray icon disabled: {err}"))
+                .ok()
+        } else {
+            None
+        };
+        self.config = config;
+    }
+}
+
+fn read_tray_icon_config(app: &AppHandle) -> Option<TrayIconConfig> {
+    store::read_json(&TauriAdapter::new(app), TRAY_ICON_CONFIG_FILE)
+}
+
+fn write_tray_icon_config(app: &AppHandle, config: &TrayIconConfig) -> CmdResult<()> {
+    store::write_json(&TauriAdapter::new(app), TRAY_ICON_CONFIG_FILE, config)
+}
+
+fn normalize_tray_icon_config(input: TrayIconConfig) -> CmdResult<TrayIconConfig> {
+    let config = TrayIconConfig {
+        enabled: input.enabled,
+        metric: input.metric.trim().to_string(),
+        style: input.style.trim().to_ascii_lowercase(),
+        theme: input.theme.trim().to_ascii_lowercase(),
+        max: input.max.filter(|max| max.is_finite() && *max > 0.0),
+    };
+    TrayIconRenderer::parse(&config)?;
+    Ok(config)
+}
+
+pub fn start_tray_icon(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        if let Some(config) = read_tray_icon_config(&app) {
+            state.tray_icon.lock().await.set_config(config);
+        }
+    });
+}
+
+/// Redraws the tray icon when the rounded value or its threshold band changed.
+pub async fn refresh(ctx: &CoreContext, state: &SharedState, snapshot: &TelemetrySnapshot) {
+    let icon = {
+        let mut runtime = state.tray_icon.lock().await;
+        let TrayIconRuntime {
+            renderer,
+            bands,
+            units,
+            ..
+        } = &mut *runtime;
+        match renderer.as_mut() {
+            Some(renderer) => renderer.next_icon(snapshot, bands, units),
+            None => return,
+        }
+    };
+    if let Some(icon) = icon {
+        ctx.events.publish(CoreEvent::TrayIcon(icon));
+    }
+}
+
+/// Puts the app icon back after the dynamic icon is switched off.
+fn restore_default_icon(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(APP_TRAY_ID) else {
+        return;
+    };
+    if let Some(icon) = app.default_window_icon() {
+        if let Err(err) = tray.set_icon(Some(icon.clone())) {
+            tracing::debug!("failed to restore tray icon: {err}");
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_tray_icon_config(state: State<'_, SharedState>) -> CmdResult<TrayIconConfig> {
+    Ok(state.tray_icon.lock().await.config.clone())
+}
+
+#[tauri::command]
+pub async fn save_tray_icon_config(
+    app: AppHandle,
+    state: State<'_, SharedState>,
+    config: TrayIconConfig,
+) -> CmdResult<TrayIconConfig> {
+    let config = normalize_tray_icon_config(config)?;
+    write_tray_icon_config(&app, &config)?;
+    state.tray_icon.lock().await.set_config(config.clone());
+    if config.enabled {
+        let snapshot = state.latest_snapshot.read().await.clone();
+        refresh(&core_context(&app), state.inner(), &snapshot).await;
+    } else {
+        restore_default_icon(&app);
+    }
+    Ok(config)
+}
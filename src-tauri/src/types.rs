@@ -1,631 +0,0 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CpuMetrics {
-    pub usage_pct: f64,
-    pub frequency_mhz: Option<u64>,
-    pub temperature_c: Option<f64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GpuMetrics {
-    pub usage_pct: Option<f64>,
-    pub temperature_c: Option<f64>,
-    pub memory_used_mb: Option<f64>,
-    pub memory_total_mb: Option<f64>,
-    pub frequency_mhz: Option<f64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MemoryMetrics {
-    pub used_mb: f64,
-    pub total_mb: f64,
-    pub usage_pct: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DiskMetrics {
-    pub name: String,
-    pub label: String,
-    pub used_gb: f64,
-    pub total_gb: f64,
-    pub usage_pct: f64,
-    pub read_bytes_per_sec: Option<f64>,
-    pub write_bytes_per_sec: Option<f64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NetworkMetrics {
-    pub download_bytes_per_sec: f64,
-    pub upload_bytes_per_sec: f64,
-    pub latency_ms: Option<f64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TelemetrySnapshot {
-    pub timestamp: DateTime<Utc>,
-    pub cpu: CpuMetrics,
-    pub gpu: GpuMetrics,
-    pub memory: MemoryMetrics,
-    pub disks: Vec<DiskMetrics>,
-    pub network: NetworkMetrics,
-    #[serde(rename = "appCpuUsagePct")]
-    pub app_cpu_usage_pct: Option<f64>,
-    #[serde(rename = "appMemoryMb")]
-    pub app_memory_mb: Option<f64>,
-    pub power_watts: Option<f64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HardwareInfo {
-    pub cpu_model: String,
-    pub cpu_max_freq_mhz: Option<u64>,
-    pub gpu_model: String,
-    pub ram_spec: String,
-    pub disk_models: Vec<String>,
-    pub motherboard: String,
-    pub device_brand: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AppSettings {
-    pub language: String,
-    #[serde(rename = "closeToTray")]
-    pub close_to_tray: bool,
-    #[serde(rename = "autoStartEnabled")]
-    pub auto_start_enabled: bool,
-    #[serde(rename = "memoryTrimEnabled")]
-    pub memory_trim_enabled: bool,
-    #[serde(rename = "memoryTrimSystemEnabled")]
-    pub memory_trim_system_enabled: bool,
-    #[serde(rename = "memoryTrimTargets")]
-    pub memory_trim_targets: Vec<String>,
-    #[serde(rename = "memoryTrimIntervalMinutes")]
-    pub memory_trim_interval_minutes: u8,
-    #[serde(rename = "rememberOverlayPosition")]
-    pub remember_overlay_position: bool,
-    #[serde(rename = "overlayAlwaysOnTop")]
-    pub overlay_always_on_top: bool,
-    #[serde(rename = "taskbarMonitorEnabled")]
-    pub taskbar_monitor_enabled: bool,
-    #[serde(rename = "nativeTaskbarMonitorEnabled")]
-    pub native_taskbar_monitor_enabled: bool,
-    #[serde(rename = "taskbarAlwaysOnTop")]
-    pub taskbar_always_on_top: bool,
-    #[serde(rename = "taskbarAutoHideOnFullscreen")]
-    pub taskbar_auto_hide_on_fullscreen: bool,
-    #[serde(rename = "taskbarPositionLocked")]
-    pub taskbar_position_locked: bool,
-    #[serde(rename = "factoryResetHotkey")]
-    pub factory_reset_hotkey: Option<String>,
-}
-
-impl Default for AppSettings {
-    fn default() -> Self {
-        Self {
-            language: "zh-CN".to_string(),
-            close_to_tray: false,
-            auto_start_enabled: false,
-            memory_trim_enabled: true,
-            memory_trim_system_enabled: false,
-            memory_trim_targets: vec!["app".to_string()],
-            memory_trim_interval_minutes: 5,
-            remember_overlay_position: true,
-            overlay_always_on_top: true,
-            taskbar_monitor_enabled: false,
-            native_taskbar_monitor_enabled: false,
-            taskbar_always_on_top: true,
-            taskbar_auto_hide_on_fullscreen: false,
-            taskbar_position_locked: false,
-            factory_reset_hotkey: None,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AppBootstrap {
-    pub settings: AppSettings,
-    pub hardware_info: HardwareInfo,
-    pub latest_snapshot: TelemetrySnapshot,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AppRuntimeInfo {
-    pub version: String,
-    pub installation_mode: String,
-    pub package_flavor: String,
-    pub can_switch_package_flavor: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NativeTaskbarConfig {
-    pub language: String,
-    #[serde(rename = "alwaysOnTop")]
-    pub always_on_top: bool,
-    #[serde(rename = "autoHideOnFullscreen")]
-    pub auto_hide_on_fullscreen: bool,
-    #[serde(rename = "rememberPosition")]
-    pub remember_position: bool,
-    #[serde(rename = "positionLocked")]
-    pub position_locked: bool,
-    #[serde(rename = "showCpu")]
-    pub show_cpu: bool,
-    #[serde(rename = "showCpuFreq")]
-    pub show_cpu_freq: bool,
-    #[serde(rename = "showCpuTemp")]
-    pub show_cpu_temp: bool,
-    #[serde(rename = "showGpu")]
-    pub show_gpu: bool,
-    #[serde(rename = "showGpuTemp")]
-    pub show_gpu_temp: bool,
-    #[serde(rename = "showMemory")]
-    pub show_memory: bool,
-    #[serde(rename = "showApp")]
-    pub show_app: bool,
-    #[serde(rename = "showDown")]
-    pub show_down: bool,
-    #[serde(rename = "showUp")]
-    pub show_up: bool,
-    #[serde(rename = "showLatency")]
-    pub show_latency: bool,
-    #[serde(rename = "twoLineMode")]
-    pub two_line_mode: bool,
-    #[serde(rename = "backgroundMode")]
-    pub background_mode: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TaskbarInfo {
-    /// `ABE_LEFT`=0, `ABE_TOP`=1, `ABE_RIGHT`=2, `ABE_BOTTOM`=3.
-    pub edge: u32,
-    pub left: i32,
-    pub top: i32,
-    pub right: i32,
-    pub bottom: i32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ShutdownPlan {
-    /// countdown | once | daily | weekly | monthly
-    pub mode: String,
-    pub created_at: DateTime<Utc>,
-    pub execute_at: Option<DateTime<Utc>>,
-    pub countdown_seconds: Option<u64>,
-    /// HH:mm format for repeat plans.
-    pub time: Option<String>,
-    /// 1=Monday ... 7=Sunday
-    pub weekday: Option<u8>,
-    /// 1..31
-    pub day_of_month: Option<u8>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ScheduleShutdownRequest {
-    /// countdown | once | daily | weekly | monthly
-    pub mode: String,
-    pub delay_seconds: Option<u64>,
-    pub execute_at: Option<DateTime<Utc>>,
-    /// HH:mm format for repeat plans.
-    pub time: Option<String>,
-    /// 1=Monday ... 7=Sunday
-    pub weekday: Option<u8>,
-    /// 1..31
-    pub day_of_month: Option<u8>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SmtpEmailConfig {
-    pub host: String,
-    pub port: u16,
-    pub username: String,
-    pub password: String,
-    pub from_email: String,
-    pub from_name: String,
-    /// none | starttls | tls
-    pub security: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SendReminderEmailRequest {
-    pub smtp: SmtpEmailConfig,
-    pub to: String,
-    pub subject: String,
-    pub body: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct WeeklyReminderSlot {
-    /// 1=Monday ... 7=Sunday
-    pub weekday: u8,
-    /// HH:mm
-    pub time: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MonthlyReminderSlot {
-    /// 1..31
-    pub day: u8,
-    /// HH:mm
-    pub time: String,
-}
-
-fn default_true() -> bool {
-    true
-}
-
-fn default_background_type() -> String {
-    "image".to_string()
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct ReminderAdvancedSettings {
-    #[serde(default = "default_background_type")]
-    pub background_type: String,
-    #[serde(default)]
-    pub background_image: String,
-    #[serde(default)]
-    pub background_color: String,
-    #[serde(default = "default_true")]
-    pub allow_close: bool,
-    #[serde(default)]
-    pub block_all_keys: bool,
-    #[serde(default)]
-    pub require_close_password: bool,
-    #[serde(default)]
-    pub close_password: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TaskReminder {
-    pub id: String,
-    pub enabled: bool,
-    pub title: String,
-    /// email | fullscreen
-    pub channel: String,
-    pub email: String,
-    pub daily_times: Vec<String>,
-    pub weekly_slots: Vec<WeeklyReminderSlot>,
-    pub monthly_slots: Vec<MonthlyReminderSlot>,
-    /// text | markdown | web | image
-    pub content_type: String,
-    pub content: String,
-    #[serde(default)]
-    pub advanced_settings: Option<ReminderAdvancedSettings>,
-    pub created_at: String,
-    pub updated_at: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct TaskReminderStore {
-    pub reminders: Vec<TaskReminder>,
-    pub smtp_config: Option<SmtpEmailConfig>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ReminderScreenEventPayload {
-    pub title: String,
-    pub content: String,
-    pub content_type: String,
-    #[serde(default)]
-    pub advanced_settings: Option<ReminderAdvancedSettings>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalAiStatus {
-    pub ready: bool,
-    pub running: bool,
-    pub model_name: String,
-    pub launch_mode: String,
-    pub launch_backend: String,
-    pub selected_model_dir: Option<String>,
-    pub selected_launcher_dir: Option<String>,
-    pub model_path: Option<String>,
-    pub server_path: Option<String>,
-    pub server_url: String,
-    pub vision_enabled: bool,
-    pub launcher_needs_selection: bool,
-    pub message: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalAiAttachment {
-    pub name: String,
-    pub media_type: String,
-    pub size: u64,
-    pub text_content: Option<String>,
-    pub data_url: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalAiChatMessage {
-    pub role: String,
-    pub content: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalAiChatRequest {
-    pub prompt: String,
-    #[serde(default)]
-    pub request_id: Option<String>,
-    #[serde(default)]
-    pub enable_thinking: Option<bool>,
-    #[serde(default)]
-    pub history: Vec<LocalAiChatMessage>,
-    #[serde(default)]
-    pub attachments: Vec<LocalAiAttachment>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalAiTokenUsage {
-    pub prompt_tokens: Option<u64>,
-    pub completion_tokens: Option<u64>,
-    pub total_tokens: Option<u64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalAiChatResponse {
-    pub reply: String,
-    pub reasoning: Option<String>,
-    pub model: String,
-    pub status: LocalAiStatus,
-    pub usage: Option<LocalAiTokenUsage>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalAiStreamEvent {
-    pub request_id: String,
-    pub channel: String,
-    pub delta: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamShortcutAccount {
-    pub id: String,
-    pub label: String,
-    pub shortcuts_path: String,
-    pub grid_dir: String,
-    pub selected: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct EpicSteamGameSyncStatus {
-    pub present_in_steam: bool,
-    pub managed_by_pulse: bool,
-    pub app_id: Option<u32>,
-    /// new | managed | existing
-    pub source: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct EpicInstalledGame {
-    pub id: String,
-    pub title: String,
-    pub install_dir: String,
-    pub launch_executable: Option<String>,
-    pub launch_command: Option<String>,
-    pub epic_app_name: Option<String>,
-    pub icon_path: Option<String>,
-    pub sync_status: EpicSteamGameSyncStatus,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct EpicSteamScanResult {
-    pub steam_path: Option<String>,
-    pub steam_running: bool,
-    pub selected_steam_user_id: Option<String>,
-    pub accounts: Vec<SteamShortcutAccount>,
-    pub epic_games: Vec<EpicInstalledGame>,
-    pub warnings: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SyncEpicGamesRequest {
-    pub steam_user_id: String,
-    pub game_ids: Vec<String>,
-    #[serde(default = "default_true")]
-    pub remove_missing_managed: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SyncEpicGamesResult {
-    pub shortcuts_path: String,
-    pub grid_dir: String,
-    pub launcher_dir: String,
-    pub backup_path: Option<String>,
-    pub created_count: usize,
-    pub updated_count: usize,
-    pub removed_count: usize,
-    pub synced_game_ids: Vec<String>,
-    pub warnings: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct StartupItem {
-    pub id: String,
-    pub name: String,
-    pub command: String,
-    pub source: String,
-    pub enabled: bool,
-    pub writable: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SystemToolsStatus {
-    pub context_menu_style: String,
-    pub windows_update_disabled: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSessionStatus {
-    pub logged_in: bool,
-    pub steam_id: Option<String>,
-    pub persona_name: Option<String>,
-    pub currency_label: Option<String>,
-    pub saved_at: Option<String>,
-    pub login_window_open: bool,
-    pub message: Option<String>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSessionInput {
-    pub session_id: String,
-    pub steam_login_secure: String,
-    pub steam_country: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketItem {
-    pub id: String,
-    pub asset_id: String,
-    pub class_id: String,
-    pub instance_id: String,
-    pub app_id: u32,
-    pub context_id: String,
-    pub name: String,
-    pub market_hash_name: String,
-    pub icon_url: Option<String>,
-    pub amount: u32,
-    pub marketable: bool,
-    pub tradable: bool,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketInventoryGroup {
-    pub app_id: u32,
-    pub context_id: String,
-    pub app_label: String,
-    pub items: Vec<SteamMarketItem>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketInventoryScanResult {
-    pub steam_id: String,
-    pub groups: Vec<SteamMarketInventoryGroup>,
-    pub total_marketable: usize,
-    pub warnings: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketPriceQuote {
-    pub item_id: String,
-    pub market_hash_name: String,
-    pub lowest_sell_cents: Option<u64>,
-    pub highest_buy_cents: Option<u64>,
-    pub median_sell_cents: Option<u64>,
-    pub currency_label: String,
-    pub volume: Option<u64>,
-    pub stale: bool,
-    pub error: Option<String>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSellStrategy {
-    pub mode: String,
-    pub discount_cents: Option<u64>,
-    pub discount_percent: Option<f64>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSellPlanItem {
-    pub item: SteamMarketItem,
-    pub reference_mode: String,
-    pub reference_cents: Option<u64>,
-    pub seller_receives_cents: u64,
-    pub buyer_pays_cents: Option<u64>,
-    pub skipped: bool,
-    pub skip_reason: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSellPlanResult {
-    pub items: Vec<SteamMarketSellPlanItem>,
-    pub total_seller_receives_cents: u64,
-    pub warnings: Vec<String>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSellItemRequest {
-    pub asset_id: String,
-    pub app_id: u32,
-    pub context_id: String,
-    pub amount: u32,
-    pub seller_receives_cents: u64,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSellBatchRequest {
-    pub items: Vec<SteamMarketSellItemRequest>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSellItemResult {
-    pub asset_id: String,
-    pub success: bool,
-    pub message: String,
-    pub requires_confirmation: bool,
-    pub listing_id: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketSellBatchResult {
-    pub results: Vec<SteamMarketSellItemResult>,
-    pub success_count: usize,
-    pub failure_count: usize,
-    pub confirmation_count: usize,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketListingItem {
-    pub listing_id: String,
-    pub name: String,
-    pub price_cents: u64,
-    pub status: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SteamMarketListingsResult {
-    pub items: Vec<SteamMarketListingItem>,
-    pub total: usize,
-}
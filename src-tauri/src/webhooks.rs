@@ -0,0 +1,455 @@
+use std::{sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, Local, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tauri::AppHandle;
+
+use crate::{
+    core::{ports::PathProvider, store},
+    ipc::commands,
+    tauri_adapter::TauriAdapter,
+    types::{WebhookEndpoint, WebhookStore},
+};
+
+type CmdResult<T> = Result<T, String>;
+
+const WEBHOOK_STORE_FILE: &str = "webhooks.json";
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+const WEBHOOK_MAX_RETRIES: u8 = 5;
+const WEBHOOK_RETRY_BASE_MS: u64 = 1000;
+const DEFAULT_GENERIC_TEMPLATE: &str =
+    r#"{"event":"{{event}}","title":"{{title}}","text":"{{text}}","timestamp":"{{timestamp}}"}"#;
+
+/// Channel-independent notification; each endpoint kind renders it into its own payload.
+#[derive(Debug, Clone)]
+pub struct WebhookMessage {
+    /// reminder | alert | shutdown | test
+    pub event: String,
+    pub title: String,
+    pub text: String,
+}
+
+impl WebhookMessage {
+    pub fn new(event: &str, title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            event: event.to_string(),
+            title: title.into(),
+            text: text.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WebhookKind {
+    Generic,
+    Discord,
+    Slack,
+    Feishu,
+    DingTalk,
+}
+
+impl WebhookKind {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "generic" => Some(Self::Generic),
+            "discord" => Some(Self::Discord),
+            "slack" => Some(Self::Slack),
+            "feishu" | "lark" => Some(Self::Feishu),
+            "dingtalk" => Some(Self::DingTalk),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Generic => "generic",
+            Self::Discord => "discord",
+            Self::Slack => "slack",
+            Self::Feishu => "feishu",
+            Self::DingTalk => "dingtalk",
+        }
+    }
+}
+
+pub(crate) fn read_webhook_store(paths: &dyn PathProvider) -> Option<WebhookStore> {
+    store::read_json(paths, WEBHOOK_STORE_FILE)
+}
+
+fn write_webhook_store(paths: &dyn PathProvider, webhooks: &WebhookStore) -> CmdResult<()> {
+    store::write_json(paths, WEBHOOK_STORE_FILE, webhooks)
+}
+
+fn normalize_webhook_endpoint(
+    mut endpoint: WebhookEndpoint,
+    index: usize,
+) -> CmdResult<WebhookEndpoint> {
+    endpoint.id = endpoint.id.trim().to_string();
+    if endpoint.id.is_empty() {
+        endpoint.id = format!("hook-{}-{index}", Utc::now().timestamp_millis());
+    }
+    endpoint.name = endpoint.name.trim().to_string();
+    endpoint.url = endpoint.url.trim().to_string();
+    let kind = WebhookKind::parse(&endpoint.kind).ok_or_else(|| {
+        format!(
+            "webhook \"{}\": unsupported kind {} (expected generic, discord, slack, feishu or dingtalk)",
+            endpoint.name, endpoint.kind
+        )
+    })?;
+    endpoint.kind = kind.as_str().to_string();
+    let url = url::Url::parse(&endpoint.url)
+        .map_err(|e| format!("webhook \"{}\": invalid url: {e}", endpoint.name))?;
+    if url.scheme() != "https" && url.scheme() != "http" {
+        return Err(format!(
+            "webhook \"{}\": url must use http or https",
+            endpoint.name
+        ));
+    }
+    if endpoint.name.is_empty() {
+        endpoint.name = url.host_str().unwrap_or("webhook").to_string();
+    }
+    endpoint.secret = endpoint.secret.trim().to_string();
+    if kind == WebhookKind::Generic {
+        if endpoint.body_template.trim().is_empty() {
+            endpoint.body_template = DEFAULT_GENERIC_TEMPLATE.to_string();
+        }
+        let sample = WebhookMessage::new("test", "title", "text");
+        render_template(&endpoint.body_template, &sample)
+            .map_err(|e| format!("webhook \"{}\": {e}", endpoint.name))?;
+    } else {
+        endpoint.body_template = String::new();
+    }
+    endpoint.max_retries = endpoint.max_retries.min(WEBHOOK_MAX_RETRIES);
+    Ok(endpoint)
+}
+
+pub(crate) fn normalize_webhook_store(input: WebhookStore) -> CmdResult<WebhookStore> {
+    let endpoints = input
+        .endpoints
+        .into_iter()
+        .enumerate()
+        .map(|(index, endpoint)| normalize_webhook_endpoint(endpoint, index))
+        .collect::<CmdResult<Vec<_>>>()?;
+    let warning_id = input.shutdown_warning_webhook_id.trim().to_string();
+    let shutdown_warning_webhook_id = if endpoints.iter().any(|e| e.id == warning_id) {
+        warning_id
+    } else {
+        String::new()
+    };
+    Ok(WebhookStore {
+        endpoints,
+        shutdown_warning_webhook_id,
+        shutdown_warning_minutes: input.shutdown_warning_minutes.clamp(1, 24 * 60),
+    })
+}
+
+/// Substitutes `{{placeholders}}` with JSON-escaped values, so the template stays valid JSON.
+fn render_template(template: &str, message: &WebhookMessage) -> CmdResult<Value> {
+    let escape = |value: &str| {
+        let quoted = Value::String(value.to_string()).to_string();
+        quoted[1..quoted.len() - 1].to_string()
+    };
+    let rendered = template
+        .replace("{{event}}", &escape(&message.event))
+        .replace("{{title}}", &escape(&message.title))
+        .replace("{{text}}", &escape(&message.text))
+        .replace("{{timestamp}}", &Utc::now().to_rfc3339());
+    serde_json::from_str(&rendered).map_err(|e| format!("body template is not valid JSON: {e}"))
+}
+
+fn hmac_sha256_base64(key: &[u8], data: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    BASE64.encode(mac.finalize().into_bytes())
+}
+
+/// Feishu signs `"{timestamp}\n{secret}"` as the HMAC key over an empty message.
+fn feishu_sign(timestamp_secs: i64, secret: &str) -> String {
+    hmac_sha256_base64(format!("{timestamp_secs}\n{secret}").as_bytes(), b"")
+}
+
+/// DingTalk signs `"{timestamp}\n{secret}"` with the secret as key, millisecond timestamp.
+fn dingtalk_sign(timestamp_ms: i64, secret: &str) -> String {
+    hmac_sha256_base64(
+        secret.as_bytes(),
+        format!("{timestamp_ms}\n{secret}").as_bytes(),
+    )
+}
+
+fn build_request(
+    endpoint: &WebhookEndpoint,
+    message: &WebhookMessage,
+) -> CmdResult<(String, Value)> {
+    let kind = WebhookKind::parse(&endpoint.kind)
+        .ok_or_else(|| format!("unsupported webhook kind: {}", endpoint.kind))?;
+    let mut url = endpoint.url.clone();
+    let body = match kind {
+        WebhookKind::Generic => {
+            let template = if endpoint.body_template.trim().is_empty() {
+                DEFAULT_GENERIC_TEMPLATE
+            } else {
+                endpoint.body_template.as_str()
+            };
+            render_template(template, message)?
+        }
+        WebhookKind::Discord => json!({
+            "username": "PulseCore",
+            "embeds": [{
+                "title": message.title,
+                "description": message.text,
+                "timestamp": Utc::now().to_rfc3339(),
+            }],
+        }),
+        WebhookKind::Slack => json!({
+            "text": format!("*{}*\n{}", message.title, message.text),
+        }),
+        WebhookKind::Feishu => {
+            let mut body = json!({
+                "msg_type": "text",
+                "content": { "text": format!("{}\n{}", message.title, message.text) },
+            });
+            if !endpoint.secret.is_empty() {
+                let timestamp = Utc::now().timestamp();
+                body["timestamp"] = json!(timestamp.to_string());
+                body["sign"] = json!(feishu_sign(timestamp, &endpoint.secret));
+            }
+            body
+        }
+        WebhookKind::DingTalk => {
+            if !endpoint.secret.is_empty() {
+                let timestamp = Utc::now().timestamp_millis();
+                let sign = dingtalk_sign(timestamp, &endpoint.secret);
+                let separator = if url.contains('?') { '&' } else { '?' };
+                url = format!(
+                    "{url}{separator}timestamp={timestamp}&sign={}",
+                    urlencoding::encode(&sign)
+                );
+            }
+            json!({
+                "msgtype": "markdown",
+                "markdown": {
+                    "title": message.title,
+                    "text": format!("### {}\n\n{}", message.title, message.text),
+                },
+            })
+        }
+    };
+    Ok((url, body))
+}
+
+/// Feishu and DingTalk answer HTTP 200 even when they reject a message.
+fn check_platform_response(kind: &str, text: &str) -> CmdResult<()> {
+    let Ok(value) = serde_json::from_str::<Value>(text) else {
+        return Ok(());
+    };
+    let (code, message) = match WebhookKind::parse(kind) {
+        Some(WebhookKind::DingTalk) => (value.get("errcode"), value.get("errmsg")),
+        Some(WebhookKind::Feishu) => (
+            value.get("code").or_else(|| value.get("StatusCode")),
+            value.get("msg").or_else(|| value.get("StatusMessage")),
+        ),
+        _ => return Ok(()),
+    };
+    match code.and_then(Value::as_i64) {
+        Some(code) if code != 0 => Err(format!(
+            "webhook rejected message ({code}): {}",
+            message.and_then(Value::as_str).unwrap_or("unknown error")
+        )),
+        _ => Ok(()),
+    }
+}
+
+pub async fn send_webhook(endpoint: &WebhookEndpoint, message: &WebhookMessage) -> CmdResult<()> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let attempts = u32::from(endpoint.max_retries.min(WEBHOOK_MAX_RETRIES)) + 1;
+    let mut last_error = String::new();
+
+    for attempt in 0..attempts {
+        if attempt > 0 {
+            let delay = WEBHOOK_RETRY_BASE_MS * 2u64.pow(attempt - 1);
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+        // Rebuild per attempt so signed timestamps stay fresh across retries.
+        let (url, body) = build_request(endpoint, message)?;
+        match client.post(&url).json(&body).send().await {
+            Ok(response) => {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                if status.is_success() {
+                    return check_platform_response(&endpoint.kind, &text);
+                }
+                last_error = format!(
+                    "webhook returned {status}: {}",
+                    text.chars().take(200).collect::<String>()
+                );
+                if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    break;
+                }
+            }
+            Err(err) => last_error = format!("webhook request failed: {err}"),
+        }
+    }
+    Err(last_error)
+}
+
+/// Looks up an endpoint in the persisted store and delivers the message to it.
+pub async fn send_to_webhook_id(
+    paths: &dyn PathProvider,
+    webhook_id: &str,
+    message: &WebhookMessage,
+) -> CmdResult<()> {
+    let store = read_webhook_store(paths).unwrap_or_default();
+    let endpoint = store
+        .endpoints
+        .iter()
+        .find(|endpoint| endpoint.id == webhook_id.trim())
+        .ok_or_else(|| format!("webhook {webhook_id} not found"))?;
+    if !endpoint.enabled {
+        return Err(format!("webhook \"{}\" is disabled", endpoint.name));
+    }
+    send_webhook(endpoint, message).await
+}
+
+/// Sends one warning per scheduled shutdown once it is within the configured lead time.
+/// `warned_for` remembers the shutdown instant that was already announced. The delivery runs
+/// as its own task so retries do not hold up the caller's loop.
+pub fn check_shutdown_warning(
+    paths: &Arc<dyn PathProvider>,
+    warned_for: &mut Option<DateTime<Utc>>,
+) {
+    let store = match read_webhook_store(paths.as_ref()) {
+        Some(store) if !store.shutdown_warning_webhook_id.is_empty() => store,
+        _ => return,
+    };
+    let Some(shutdown_at) = commands::next_scheduled_shutdown(paths.as_ref()) else {
+        return;
+    };
+    if *warned_for == Some(shutdown_at) {
+        return;
+    }
+    let remaining = shutdown_at - Utc::now();
+    let lead = chrono::Duration::minutes(i64::from(store.shutdown_warning_minutes.max(1)));
+    if remaining > lead || remaining <= chrono::Duration::zero() {
+        return;
+    }
+
+    *warned_for = Some(shutdown_at);
+    let minutes = (remaining.num_seconds() + 59) / 60;
+    let message = WebhookMessage::new(
+        "shutdown",
+        "Scheduled shutdown",
+        format!(
+            "This computer will shut down at {} (in about {minutes} min).",
+            shutdown_at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ),
+    );
+    let paths = Arc::clone(paths);
+    tauri::async_runtime::spawn(async move {
+        let webhook_id = store.shutdown_warning_webhook_id;
+        if let Err(err) = send_to_webhook_id(paths.as_ref(), &webhook_id, &message).await {
+            tracing::warn!("shutdown warning webhook failed: {err}");
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_webhook_store(app: AppHandle) -> CmdResult<WebhookStore> {
+    Ok(read_webhook_store(&TauriAdapter::new(&app))
+        .and_then(|store| normalize_webhook_store(store).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn save_webhook_store(app: AppHandle, store: WebhookStore) -> CmdResult<WebhookStore> {
+    let normalized = normalize_webhook_store(store)?;
+    write_webhook_store(&TauriAdapter::new(&app), &normalized)?;
+    Ok(normalized)
+}
+
+#[tauri::command]
+pub async fn test_webhook(endpoint: WebhookEndpoint) -> CmdResult<()> {
+    let endpoint = normalize_webhook_endpoint(endpoint, 0)?;
+    let message = WebhookMessage::new(
+        "test",
+        "PulseCore webhook test",
+        format!(
+            "If you can read this, the \"{}\" webhook is configured correctly.",
+            endpoint.name
+        ),
+    );
+    send_webhook(&endpoint, &message).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(kind: &str) -> WebhookEndpoint {
+        WebhookEndpoint {
+            id: "hook".to_string(),
+            enabled: true,
+            name: "Test".to_string(),
+            kind: kind.to_string(),
+            url: "https://example.com/hook?access_token=abc".to_string(),
+            secret: String::new(),
+            body_template: String::new(),
+            max_retries: 3,
+        }
+    }
+
+    #[test]
+    fn template_values_are_json_escaped() {
+        let message = WebhookMessage::new("alert", "GPU \"hot\"", "line1\nline2");
+        let body = render_template(DEFAULT_GENERIC_TEMPLATE, &message).unwrap();
+        assert_eq!(body["title"], json!("GPU \"hot\""));
+        assert_eq!(body["text"], json!("line1\nline2"));
+        assert!(render_template("{\"a\": {{title}}}", &message).is_err());
+    }
+
+    #[test]
+    fn dingtalk_signature_matches_reference() {
+        assert_eq!(
+            dingtalk_sign(1_700_000_000_000, "SECexample"),
+            "uroalHrWHORz59talE1o26b8HuX2kOp8WlAN91F9Pj0="
+        );
+        let mut hook = endpoint("dingtalk");
+        hook.secret = "SECexample".to_string();
+        let (url, body) = build_request(&hook, &WebhookMessage::new("test", "t", "x")).unwrap();
+        assert!(url.starts_with("https://example.com/hook?access_token=abc&timestamp="));
+        assert!(url.contains("&sign="));
+        assert_eq!(body["msgtype"], json!("markdown"));
+    }
+
+    #[test]
+    fn feishu_signed_payload_carries_timestamp_and_sign() {
+        assert_eq!(
+            feishu_sign(1_700_000_000, "secret"),
+            "fiWS2+gh28DOydAv7hzONH/mDn9+b1Y4Y5ivXWXy8vA="
+        );
+        let mut hook = endpoint("feishu");
+        hook.secret = "secret".to_string();
+        let (_, body) = build_request(&hook, &WebhookMessage::new("test", "t", "x")).unwrap();
+        let timestamp: i64 = body["timestamp"].as_str().unwrap().parse().unwrap();
+        assert_eq!(body["sign"], json!(feishu_sign(timestamp, "secret")));
+        assert_eq!(body["content"]["text"], json!("t\nx"));
+    }
+
+    #[test]
+    fn platform_error_codes_are_reported() {
+        assert!(check_platform_response("dingtalk", r#"{"errcode":0,"errmsg":"ok"}"#).is_ok());
+        assert!(check_platform_response(
+            "dingtalk",
+            r#"{"errcode":310000,"errmsg":"sign not match"}"#
+        )
+        .is_err());
+        assert!(
+            check_platform_response("feishu", r#"{"code":19021,"msg":"sign match fail"}"#).is_err()
+        );
+        assert!(check_platform_response("slack", "ok").is_ok());
+    }
+}